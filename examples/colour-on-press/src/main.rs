@@ -4,7 +4,7 @@ pub mod models;
 use models::traits::ColourOnPress;
 
 #[tokio::main]
-async fn main() -> RPiResult<'static, ()> {
+async fn main() -> RPiResult<()> {
     let mut unit = PimoroniDisplayHATMini::init()?;
 
     unit.run().await
@@ -5,16 +5,16 @@ use rpi_devices::{boards::PimoroniDisplayHATMini, errors::RPiResult, gpio::Butto
 
 #[async_trait]
 pub trait ColourOnPress {
-    async fn run<'e>(&mut self) -> RPiResult<'e, ()>;
+    async fn run(&mut self) -> RPiResult<()>;
 }
 
 /// Transition the LED to the specified colour during button press,
 /// stop and disable LED when the button is released.
-async fn on_button_press<'e>(
+async fn on_button_press(
     unit: &PimoroniDisplayHATMini,
     button: &Button,
     colour: (u8, u8, u8),
-) -> RPiResult<'e, ()> {
+) -> RPiResult<()> {
     loop {
         if button.pressed(None).await? {
             // Transition the LED to the specified colour or wait for the button to be released.
@@ -39,7 +39,7 @@ async fn on_button_press<'e>(
 
 #[async_trait]
 impl ColourOnPress for PimoroniDisplayHATMini {
-    async fn run<'e>(&mut self) -> RPiResult<'e, ()> {
+    async fn run(&mut self) -> RPiResult<()> {
         macro_rules! press_action {
             ($((
                 $colour:expr,
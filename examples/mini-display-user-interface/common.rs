@@ -5,8 +5,14 @@ pub use rpi_devices::{
     display_mipidsi::{
         images::OwnedBmp,
         pixelcolor::{Rgb565, RgbColor},
+        text::LogConsole,
         traits::{BacklightComponent, DisplayComponent, UserInterface},
         Bmp, Image, ImageDrawable, LcdDisplay, LcdST7789, Point, Size,
     },
     errors::{IntoRPiResult, RPiError, RPiResult},
+    gpio::{
+        traits::{Navigation, NavigationInput},
+        RotaryEncoder,
+    },
+    logger,
 };
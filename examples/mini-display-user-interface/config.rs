@@ -8,4 +8,12 @@ pub(crate) const BUTTON_ICON_MARGIN: u16 = 4;
 
 pub(crate) const LINE_STROKE_WIDTH: u32 = 2;
 
+pub(crate) const CONSOLE_LINES: usize = 64;
+
 pub(crate) static PATH_TO_ICONS: &str = "examples/mini-display-user-interface/assets/icons";
+
+pub(crate) static PATH_TO_TEXT: &str = "README.md";
+
+/// The pins `(a, b, switch)` of a rotary encoder to navigate with alongside the
+/// buttons, if one is wired up.
+pub(crate) const ROTARY_ENCODER: Option<(u8, u8, Option<u8>)> = None;
@@ -10,10 +10,27 @@ static BUTTON_B: OnceLock<models::interfaces::CornerButton<'static>> = OnceLock:
 static BUTTON_X: OnceLock<models::interfaces::CornerButton<'static>> = OnceLock::new();
 static BUTTON_Y: OnceLock<models::interfaces::CornerButton<'static>> = OnceLock::new();
 
+static ENCODER: OnceLock<RotaryEncoder> = OnceLock::new();
+
 #[tokio::main]
-async fn main() -> RPiResult<'static, ()> {
+async fn main() -> RPiResult<()> {
     let board: PimoroniDisplayHATMini = PimoroniDisplayHATMini::init()?;
 
+    if let Some((pin_a, pin_b, switch)) = config::ROTARY_ENCODER {
+        let gpio = rpi_devices::gpio::func::init_gpio()?;
+
+        if ENCODER
+            .set(RotaryEncoder::try_new(&gpio, pin_a, pin_b, switch)?)
+            .is_err()
+        {
+            panic!("Failed to create rotary encoder");
+        }
+    }
+
+    // Mirror the log onto the diagnostics screen.
+    let console = Arc::new(LogConsole::new(config::CONSOLE_LINES));
+    logger::add_sink(console.clone());
+
     BUTTON_A
         .set(
             models::interfaces::CornerButton::from_bmp_paths(
@@ -25,38 +42,50 @@ async fn main() -> RPiResult<'static, ()> {
         )
         .expect("Failed to create button A");
 
-    BUTTON_B
+    BUTTON_Y
         .set(
             models::interfaces::CornerButton::from_bmp_paths(
-                config::PATH_TO_ICONS.to_owned() + "/button-tick-on-black.bmp",
-                config::PATH_TO_ICONS.to_owned() + "/button-tick-on-white.bmp",
+                config::PATH_TO_ICONS.to_owned() + "/button-cross-on-black.bmp",
+                config::PATH_TO_ICONS.to_owned() + "/button-cross-on-white.bmp",
                 Arc::new(models::interfaces::DummyInterface {}),
             )
             .await?,
         )
-        .expect("Failed to create button B");
+        .expect("Failed to create button Y");
 
     BUTTON_X
         .set(
             models::interfaces::CornerButton::from_bmp_paths(
                 config::PATH_TO_ICONS.to_owned() + "/button-right-on-black.bmp",
                 config::PATH_TO_ICONS.to_owned() + "/button-right-on-white.bmp",
-                Arc::new(models::interfaces::DummyInterface {}),
+                Arc::new(models::interfaces::Diagnostics::new(
+                    Arc::clone(&console),
+                    BUTTON_Y.get().unwrap(),
+                )),
             )
             .await?,
         )
         .expect("Failed to create button X");
 
-    BUTTON_Y
+    // The text viewer navigates with the other buttons, so they are set first.
+    BUTTON_B
         .set(
             models::interfaces::CornerButton::from_bmp_paths(
-                config::PATH_TO_ICONS.to_owned() + "/button-cross-on-black.bmp",
-                config::PATH_TO_ICONS.to_owned() + "/button-cross-on-white.bmp",
-                Arc::new(models::interfaces::DummyInterface {}),
+                config::PATH_TO_ICONS.to_owned() + "/button-tick-on-black.bmp",
+                config::PATH_TO_ICONS.to_owned() + "/button-tick-on-white.bmp",
+                Arc::new(
+                    models::interfaces::TextViewer::from_path(
+                        config::PATH_TO_TEXT,
+                        BUTTON_A.get().unwrap(),
+                        BUTTON_X.get().unwrap(),
+                        BUTTON_Y.get().unwrap(),
+                    )
+                    .await?,
+                ),
             )
             .await?,
         )
-        .expect("Failed to create button Y");
+        .expect("Failed to create button B");
 
     board
         .execute_interface_layers(Arc::new(models::interfaces::Menu::new(
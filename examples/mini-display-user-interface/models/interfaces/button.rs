@@ -84,11 +84,11 @@ pub struct CornerButton<'i> {
 
 impl<'i> CornerButton<'i> {
     /// Create a new [`CornerButton`] instance.
-    pub async fn from_bmp_paths<'e>(
+    pub async fn from_bmp_paths(
         pressed_path: impl AsRef<Path> + std::fmt::Debug,
         released_path: impl AsRef<Path> + std::fmt::Debug,
         interface: Arc<dyn UserInterface<PimoroniDisplayHATMini>>,
-    ) -> RPiResult<'e, Self> {
+    ) -> RPiResult<Self> {
         let owned_bmps = [
             OwnedBmp::from_path(pressed_path).await?,
             OwnedBmp::from_path(released_path).await?,
@@ -101,12 +101,12 @@ impl<'i> CornerButton<'i> {
     }
 
     /// Draw the button on the display.
-    pub async fn draw<'e>(
+    pub async fn draw(
         &'i self,
         hat: &PimoroniDisplayHATMini,
         corner: Corner,
         pressed: bool,
-    ) -> RPiResult<'e, ()> {
+    ) -> RPiResult<()> {
         let bg_colour = if pressed {
             Rgb565::WHITE
         } else {
@@ -120,19 +120,19 @@ impl<'i> CornerButton<'i> {
             let mut display = hat.display.lock().await;
 
             let (point, size) = corner.bg_rect();
-            display.draw_rect(point, size, bg_colour)?;
-            display.draw_image(&image)?;
+            display.draw_rect(point, size, bg_colour).await?;
+            display.draw_image(&image).await?;
         }
 
         Ok(())
     }
 
     /// The handler for this button.
-    pub async fn handler<'e>(
+    pub async fn handler(
         &'i self,
         hat: &PimoroniDisplayHATMini,
         corner: Corner,
-    ) -> RPiResult<'e, Option<Arc<dyn UserInterface<PimoroniDisplayHATMini>>>> {
+    ) -> RPiResult<Option<Arc<dyn UserInterface<PimoroniDisplayHATMini>>>> {
         let button = match &corner {
             Corner::TopLeft => &hat.button_a,
             Corner::TopRight => &hat.button_x,
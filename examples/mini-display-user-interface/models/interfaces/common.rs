@@ -1,42 +1,48 @@
 use crate::{common::*, config};
 
 /// Draw the menu lines on the display.
-pub(crate) async fn draw_menu_lines<'e>(hat: &PimoroniDisplayHATMini) -> RPiResult<'e, ()> {
+pub(crate) async fn draw_menu_lines(hat: &PimoroniDisplayHATMini) -> RPiResult<()> {
     const BUTTON_WIDTH: i32 = (config::BUTTON_ICON_WIDTH + config::BUTTON_ICON_MARGIN * 2) as i32;
     const DIVIDER_HEIGHT: i32 = (PimoroniDisplayHATMini::H / 2) as i32;
 
     let mut display = hat.display.lock().await;
 
-    display.draw_vertical_line(BUTTON_WIDTH, Rgb565::WHITE, config::LINE_STROKE_WIDTH)?;
-    display.draw_vertical_line(
-        PimoroniDisplayHATMini::W as i32 - BUTTON_WIDTH,
-        Rgb565::WHITE,
-        2,
-    )?;
-    display.draw_line(
-        Point::new(0, DIVIDER_HEIGHT),
-        Point::new(BUTTON_WIDTH, DIVIDER_HEIGHT),
-        Rgb565::WHITE,
-        2,
-    )?;
-    display.draw_line(
-        Point::new(PimoroniDisplayHATMini::W as i32, DIVIDER_HEIGHT),
-        Point::new(
+    display
+        .draw_vertical_line(BUTTON_WIDTH, Rgb565::WHITE, config::LINE_STROKE_WIDTH)
+        .await?;
+    display
+        .draw_vertical_line(
             PimoroniDisplayHATMini::W as i32 - BUTTON_WIDTH,
-            DIVIDER_HEIGHT,
-        ),
-        Rgb565::WHITE,
-        2,
-    )?;
+            Rgb565::WHITE,
+            2,
+        )
+        .await?;
+    display
+        .draw_line(
+            Point::new(0, DIVIDER_HEIGHT),
+            Point::new(BUTTON_WIDTH, DIVIDER_HEIGHT),
+            Rgb565::WHITE,
+            2,
+        )
+        .await?;
+    display
+        .draw_line(
+            Point::new(PimoroniDisplayHATMini::W as i32, DIVIDER_HEIGHT),
+            Point::new(
+                PimoroniDisplayHATMini::W as i32 - BUTTON_WIDTH,
+                DIVIDER_HEIGHT,
+            ),
+            Rgb565::WHITE,
+            2,
+        )
+        .await?;
 
     Ok(())
 }
 
-/// Clear the body of the display.
-pub(crate) async fn clear_body<'e>(hat: &PimoroniDisplayHATMini) -> RPiResult<'e, ()> {
-    let mut display = hat.display.lock().await;
-
-    display.draw_rect(
+/// Get the position and size of the body of the display, between the buttons.
+pub(crate) fn body_rect() -> (Point, Size) {
+    (
         Point::new(
             config::BUTTON_ICON_WIDTH as i32
                 + config::BUTTON_ICON_MARGIN as i32 * 2
@@ -50,36 +56,56 @@ pub(crate) async fn clear_body<'e>(hat: &PimoroniDisplayHATMini) -> RPiResult<'e
                 - config::LINE_STROKE_WIDTH as i32) as u32,
             PimoroniDisplayHATMini::H as u32,
         ),
-        Rgb565::BLACK,
-    )?;
+    )
+}
+
+/// Clear the body of the display.
+pub(crate) async fn clear_body(hat: &PimoroniDisplayHATMini) -> RPiResult<()> {
+    let mut display = hat.display.lock().await;
+
+    let (position, size) = body_rect();
+    display.draw_rect(position, size, Rgb565::BLACK).await?;
 
     Ok(())
 }
 
 /// Draw a title in the middle of the screen.
-pub(crate) async fn draw_mid_title<'e>(
-    hat: &PimoroniDisplayHATMini,
-    title: &str,
-) -> RPiResult<'e, ()> {
+pub(crate) async fn draw_mid_title(hat: &PimoroniDisplayHATMini, title: &str) -> RPiResult<()> {
     let mut display = hat.display.lock().await;
 
-    display.draw_title::<20>(
-        title,
-        Rgb565::WHITE,
-        Some(Point::new(
-            config::BUTTON_ICON_WIDTH as i32
-                + config::BUTTON_ICON_MARGIN as i32 * 2
-                + config::LINE_STROKE_WIDTH as i32 / 2,
-            PimoroniDisplayHATMini::H as i32 / 2 - 10,
-        )),
-        Some(Size::new(
-            (PimoroniDisplayHATMini::W as i32
-                - config::BUTTON_ICON_MARGIN as i32 * 4
-                - config::BUTTON_ICON_WIDTH as i32 * 2
-                - config::LINE_STROKE_WIDTH as i32) as u32,
-            PimoroniDisplayHATMini::H as u32,
-        )),
-    )?;
+    display
+        .draw_title::<20>(
+            title,
+            Rgb565::WHITE,
+            Some(Point::new(
+                config::BUTTON_ICON_WIDTH as i32
+                    + config::BUTTON_ICON_MARGIN as i32 * 2
+                    + config::LINE_STROKE_WIDTH as i32 / 2,
+                PimoroniDisplayHATMini::H as i32 / 2 - 10,
+            )),
+            Some(Size::new(
+                (PimoroniDisplayHATMini::W as i32
+                    - config::BUTTON_ICON_MARGIN as i32 * 4
+                    - config::BUTTON_ICON_WIDTH as i32 * 2
+                    - config::LINE_STROKE_WIDTH as i32) as u32,
+                PimoroniDisplayHATMini::H as u32,
+            )),
+        )
+        .await?;
 
     Ok(())
 }
+
+/// Wait for the next navigation from the buttons of the HAT, or from the rotary
+/// encoder if one is configured.
+pub(crate) async fn next_navigation(hat: &PimoroniDisplayHATMini) -> RPiResult<Navigation> {
+    tokio::select! {
+        navigation = hat.next_navigation(None) => navigation,
+        navigation = async {
+            match crate::ENCODER.get() {
+                Some(encoder) => encoder.next_navigation(None).await,
+                None => std::future::pending().await,
+            }
+        } => navigation,
+    }
+}
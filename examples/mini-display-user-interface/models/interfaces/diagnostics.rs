@@ -0,0 +1,87 @@
+//! A live view of the log.
+//!
+//!
+
+use super::{common, Corner, CornerButton};
+use crate::{common::*, config};
+use std::sync::Arc;
+
+use rpi_devices::display_mipidsi::text::defaults::{DefaultStyle, ValidStyle};
+
+/// Show the log lines mirrored into a [`LogConsole`] as they come in, with the
+/// bottom right button going back.
+pub struct Diagnostics {
+    pub console: Arc<LogConsole>,
+    pub action_back: &'static CornerButton<'static>,
+}
+
+impl Diagnostics {
+    /// Create a new [`Diagnostics`] instance.
+    pub fn new(console: Arc<LogConsole>, action_back: &'static CornerButton<'static>) -> Self {
+        Self {
+            console,
+            action_back,
+        }
+    }
+
+    /// Redraw the console on the display.
+    pub async fn redraw(&self, hat: &PimoroniDisplayHATMini) -> RPiResult<()> {
+        common::clear_body(hat).await?;
+        {
+            let (position, size) = common::body_rect();
+            let mut display = hat.display.lock().await;
+
+            display
+                .draw_console(
+                    &self.console,
+                    DefaultStyle::<10>::default_style(Rgb565::WHITE),
+                    Some(position),
+                    Some(size),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UserInterface<PimoroniDisplayHATMini> for Diagnostics {
+    /// Execute the [`Diagnostics`] user interface.
+    async fn execute(
+        &self,
+        hat: &PimoroniDisplayHATMini,
+    ) -> RPiResult<Option<Arc<dyn UserInterface<PimoroniDisplayHATMini>>>> {
+        hat.fill_display(Rgb565::BLACK).await?;
+        self.action_back
+            .draw(hat, Corner::BottomRight, hat.button_y.is_pressed())
+            .await?;
+        common::draw_menu_lines(hat).await?;
+
+        logger::info("Showing diagnostics.");
+
+        let mut changed = Box::pin(self.console.changed());
+        self.redraw(hat).await?;
+        hat.backlight_fade_in(config::FADE_IN_STEPS, config::FADE_IN_DURATION)
+            .await?;
+
+        loop {
+            tokio::select! {
+                _ = &mut changed => {
+                    // Listen for the next change before drawing, so none are missed.
+                    changed = Box::pin(self.console.changed());
+                    self.redraw(hat).await?;
+                },
+                result = self.action_back.handler(hat, Corner::BottomRight) => {
+                    result?;
+                    break;
+                },
+            }
+        }
+
+        hat.backlight_fade_out(config::FADE_IN_STEPS, config::FADE_IN_DURATION)
+            .await?;
+
+        Ok(None)
+    }
+}
@@ -8,10 +8,10 @@ pub struct DummyInterface {}
 
 #[async_trait]
 impl UserInterface<PimoroniDisplayHATMini> for DummyInterface {
-    async fn execute<'e>(
+    async fn execute(
         &self,
         hat: &PimoroniDisplayHATMini,
-    ) -> RPiResult<'e, Option<Arc<dyn UserInterface<PimoroniDisplayHATMini>>>> {
+    ) -> RPiResult<Option<Arc<dyn UserInterface<PimoroniDisplayHATMini>>>> {
         hat.fill_display(Rgb565::BLACK).await?;
         common::clear_body(hat).await?;
         common::draw_menu_lines(hat).await?;
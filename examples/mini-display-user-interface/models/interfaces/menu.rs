@@ -25,8 +25,21 @@ impl Menu {
         }
     }
 
+    /// Get the action taken by a navigation, and the corner of its button.
+    ///
+    /// The buttons of the HAT navigate as their icons show: `A` for previous, `X`
+    /// for next, `B` to select and `Y` to go back.
+    fn action(&self, navigation: Navigation) -> (&'static CornerButton<'static>, Corner) {
+        match navigation {
+            Navigation::Previous => (self.action_a, Corner::TopLeft),
+            Navigation::Next => (self.action_x, Corner::TopRight),
+            Navigation::Select => (self.action_b, Corner::BottomLeft),
+            Navigation::Back => (self.action_y, Corner::BottomRight),
+        }
+    }
+
     /// Redraw the [`Menu`] on the display.
-    pub async fn redraw<'e>(&self, hat: &PimoroniDisplayHATMini) -> RPiResult<'e, ()> {
+    pub async fn redraw(&self, hat: &PimoroniDisplayHATMini) -> RPiResult<()> {
         self.action_a
             .draw(hat, Corner::TopLeft, hat.button_a.is_pressed())
             .await?;
@@ -50,25 +63,27 @@ impl Menu {
 #[async_trait]
 impl UserInterface<PimoroniDisplayHATMini> for Menu {
     /// Execute the [`Menu`] user interface.
-    async fn execute<'e>(
+    async fn execute(
         &self,
         hat: &PimoroniDisplayHATMini,
-    ) -> RPiResult<'e, Option<Arc<dyn UserInterface<PimoroniDisplayHATMini>>>> {
+    ) -> RPiResult<Option<Arc<dyn UserInterface<PimoroniDisplayHATMini>>>> {
         hat.fill_display(Rgb565::BLACK).await?;
         self.redraw(hat).await?;
         hat.backlight_fade_in(config::FADE_IN_STEPS, config::FADE_IN_DURATION)
             .await?;
 
-        let next = tokio::select! {
-            next_a = self.action_a.handler(hat, Corner::TopLeft) => next_a,
-            next_b = self.action_b.handler(hat, Corner::BottomLeft) => next_b,
-            next_x = self.action_x.handler(hat, Corner::TopRight) => next_x,
-            _ = self.action_y.handler(hat, Corner::BottomRight) => Ok(None),
-        }?;
+        let navigation = common::next_navigation(hat).await?;
+
+        // Highlight the action taken while the menu fades out.
+        let (action, corner) = self.action(navigation);
+        action.draw(hat, corner, true).await?;
 
         hat.backlight_fade_out(config::FADE_IN_STEPS, config::FADE_IN_DURATION)
             .await?;
 
-        Ok(next)
+        Ok(match navigation {
+            Navigation::Back => None,
+            _ => Some(Arc::clone(&action.interface)),
+        })
     }
 }
@@ -5,8 +5,14 @@ pub use button::*;
 
 pub mod common;
 
+mod diagnostics;
+pub use diagnostics::Diagnostics;
+
 mod dummy;
 pub use dummy::*;
 
 mod menu;
 pub use menu::Menu;
+
+mod viewer;
+pub use viewer::TextViewer;
@@ -0,0 +1,161 @@
+//! A paginated viewer for long text.
+//!
+//!
+
+use super::{common, Corner, CornerButton};
+use crate::{common::*, config};
+use std::{
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use rpi_devices::display_mipidsi::{
+    func::fs::read_bytes_from_file,
+    text::{
+        defaults::{DefaultStyle, ValidStyle},
+        paginate, TextBoxStyle,
+    },
+};
+
+/// The font height of the text.
+const FONT_HEIGHT: u8 = 15;
+
+/// The height reserved for the page indicator below the text.
+const INDICATOR_HEIGHT: u32 = 20;
+
+/// Show a long piece of text one page at a time, with the top buttons or the rotary
+/// encoder turning the pages and the bottom right button going back.
+pub struct TextViewer {
+    pages: Vec<String>,
+    page: AtomicUsize,
+
+    pub action_previous: &'static CornerButton<'static>,
+    pub action_next: &'static CornerButton<'static>,
+    pub action_back: &'static CornerButton<'static>,
+}
+
+impl TextViewer {
+    /// Create a new [`TextViewer`] instance, splitting `text` into pages that fit the
+    /// body of the display.
+    pub fn new(
+        text: &str,
+        action_previous: &'static CornerButton<'static>,
+        action_next: &'static CornerButton<'static>,
+        action_back: &'static CornerButton<'static>,
+    ) -> Self {
+        let (_, size) = common::body_rect();
+        let pages = paginate(
+            text,
+            &DefaultStyle::<FONT_HEIGHT>::default_style(Rgb565::WHITE),
+            &TextBoxStyle::default(),
+            Size::new(size.width, size.height - INDICATOR_HEIGHT),
+        )
+        .into_iter()
+        .map(str::to_owned)
+        .collect();
+
+        Self {
+            pages,
+            page: AtomicUsize::new(0),
+            action_previous,
+            action_next,
+            action_back,
+        }
+    }
+
+    /// Create a new [`TextViewer`] instance showing the contents of a file.
+    pub async fn from_path(
+        path: impl AsRef<Path> + std::fmt::Debug,
+        action_previous: &'static CornerButton<'static>,
+        action_next: &'static CornerButton<'static>,
+        action_back: &'static CornerButton<'static>,
+    ) -> RPiResult<Self> {
+        let bytes = read_bytes_from_file(path).await?;
+
+        Ok(Self::new(
+            &String::from_utf8_lossy(&bytes),
+            action_previous,
+            action_next,
+            action_back,
+        ))
+    }
+
+    /// Redraw the current page of the [`TextViewer`] on the display.
+    pub async fn redraw(&self, hat: &PimoroniDisplayHATMini) -> RPiResult<()> {
+        let page = self.page.load(Ordering::Relaxed);
+
+        common::clear_body(hat).await?;
+        {
+            let (position, size) = common::body_rect();
+            let mut display = hat.display.lock().await;
+
+            display
+                .draw_text::<FONT_HEIGHT>(
+                    &self.pages[page],
+                    Rgb565::WHITE,
+                    Some(position),
+                    Some(Size::new(size.width, size.height - INDICATOR_HEIGHT)),
+                )
+                .await?;
+            display
+                .draw_title::<10>(
+                    &format!("{} / {}", page + 1, self.pages.len()),
+                    Rgb565::WHITE,
+                    Some(position + Point::new(0, (size.height - INDICATOR_HEIGHT) as i32)),
+                    Some(Size::new(size.width, INDICATOR_HEIGHT)),
+                )
+                .await?;
+        }
+
+        self.action_previous
+            .draw(hat, Corner::TopLeft, hat.button_a.is_pressed())
+            .await?;
+        self.action_next
+            .draw(hat, Corner::TopRight, hat.button_x.is_pressed())
+            .await?;
+        self.action_back
+            .draw(hat, Corner::BottomRight, hat.button_y.is_pressed())
+            .await?;
+        common::draw_menu_lines(hat).await?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UserInterface<PimoroniDisplayHATMini> for TextViewer {
+    /// Execute the [`TextViewer`] user interface.
+    async fn execute(
+        &self,
+        hat: &PimoroniDisplayHATMini,
+    ) -> RPiResult<Option<Arc<dyn UserInterface<PimoroniDisplayHATMini>>>> {
+        hat.fill_display(Rgb565::BLACK).await?;
+        self.redraw(hat).await?;
+        hat.backlight_fade_in(config::FADE_IN_STEPS, config::FADE_IN_DURATION)
+            .await?;
+
+        loop {
+            let page = self.page.load(Ordering::Relaxed);
+
+            let next_page = match common::next_navigation(hat).await? {
+                Navigation::Previous => page.saturating_sub(1),
+                Navigation::Next => (page + 1).min(self.pages.len() - 1),
+                Navigation::Back => break,
+                Navigation::Select => page,
+            };
+
+            if next_page != page {
+                self.page.store(next_page, Ordering::Relaxed);
+                self.redraw(hat).await?;
+            }
+        }
+
+        hat.backlight_fade_out(config::FADE_IN_STEPS, config::FADE_IN_DURATION)
+            .await?;
+
+        Ok(None)
+    }
+}
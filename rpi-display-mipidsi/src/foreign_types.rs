@@ -4,9 +4,7 @@
 //! by it. This reduces code duplication between the different modules.
 pub(crate) use display_interface::{DataFormat, WriteOnlyDataCommand};
 pub(crate) use display_interface_spi::SPIInterfaceNoCS;
-#[cfg(feature = "text")]
-pub(crate) use embedded_graphics::geometry::OriginDimensions;
-pub(crate) use embedded_graphics::geometry::{Dimensions, Size};
+pub(crate) use embedded_graphics::geometry::{Dimensions, OriginDimensions, Size};
 pub(crate) use embedded_graphics::image::{Image, ImageRaw};
 pub(crate) use embedded_graphics::pixelcolor;
 pub(crate) use embedded_graphics::prelude::{
@@ -21,12 +19,11 @@ pub(crate) use mipidsi::{
     Builder as RawDisplayBuilder, ColorInversion, Display as RawDisplay, Error as MipidsiError,
     ModelOptions as DisplayModelOptions, Orientation, TearingEffect,
 };
-pub(crate) use rpi_errors::{IntoRPiResult, RPiError, RPiResult};
+pub(crate) use rpi_errors::{IntoRPiResult, RPiError, RPiResult, ResultContext};
 pub(crate) use rpi_gpio::{OutputPin, OutputPinType};
 pub(crate) use rppal::hal::Delay;
 pub(crate) use rppal::spi::Spi;
 
-#[cfg(feature = "debug")]
 pub(crate) use rpi_logger as logger;
 
 #[cfg(any(feature = "text", feature = "bmp"))]
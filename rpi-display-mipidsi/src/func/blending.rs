@@ -0,0 +1,70 @@
+//! Alpha blending functions.
+//!
+
+use crate::foreign_types::*;
+use pixelcolor::Rgb888;
+
+/// Blend a single 8-bit channel of `foreground` over `background`.
+pub fn blend_channel(foreground: u8, background: u8, alpha: u8) -> u8 {
+    ((foreground as u16 * alpha as u16 + background as u16 * (255 - alpha) as u16 + 127) / 255)
+        as u8
+}
+
+/// Blend `foreground` over `background` with the given opacity, where `0` is fully
+/// transparent and `255` is fully opaque.
+pub fn blend(foreground: Rgb888, background: Rgb888, alpha: u8) -> Rgb888 {
+    Rgb888::new(
+        blend_channel(foreground.r(), background.r(), alpha),
+        blend_channel(foreground.g(), background.g(), alpha),
+        blend_channel(foreground.b(), background.b(), alpha),
+    )
+}
+
+/// Blend `foreground` over `background` in the colour format of the display.
+///
+/// Fully opaque and fully transparent pixels are returned as they are, without
+/// converting through [`Rgb888`].
+pub fn blend_colour<COLOUR>(foreground: COLOUR, background: COLOUR, alpha: u8) -> COLOUR
+where
+    COLOUR: PixelColor + From<Rgb888> + Into<Rgb888>,
+{
+    match alpha {
+        0 => background,
+        255 => foreground,
+        alpha => blend(foreground.into(), background.into(), alpha).into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pixelcolor::Rgb565;
+
+    #[test]
+    fn channel_endpoints_and_midpoint() {
+        assert_eq!(blend_channel(200, 100, 0), 100);
+        assert_eq!(blend_channel(200, 100, 255), 200);
+        assert_eq!(blend_channel(255, 0, 128), 128);
+        assert_eq!(blend_channel(0, 255, 128), 127);
+    }
+
+    #[test]
+    fn blend_each_channel() {
+        let blended = blend(Rgb888::new(255, 0, 100), Rgb888::new(0, 255, 100), 51);
+
+        assert_eq!(blended, Rgb888::new(51, 204, 100));
+    }
+
+    #[test]
+    fn opaque_and_transparent_colours_are_not_converted() {
+        let foreground = Rgb565::new(31, 1, 0);
+        let background = Rgb565::new(0, 62, 31);
+
+        assert_eq!(blend_colour(foreground, background, 0), background);
+        assert_eq!(blend_colour(foreground, background, 255), foreground);
+        assert_eq!(
+            blend_colour(foreground, background, 128),
+            Rgb565::from(blend(foreground.into(), background.into(), 128))
+        );
+    }
+}
@@ -1,8 +1,10 @@
 //! Bitmap image functions.
 //!
 
-use crate::foreign_types::*;
-use tinybmp::Bmp;
+use embedded_graphics::Pixel;
+
+use crate::{foreign_types::*, images::DecodedPixels};
+use tinybmp::{Bmp, ParseError};
 
 /// Load a bitmap image from a bytes array.
 ///
@@ -14,10 +16,273 @@ use tinybmp::Bmp;
 /// [`Rgb565`]: pixelcolor::Rgb565
 /// [`Rgb888`]: pixelcolor::Rgb888
 #[allow(dead_code)]
-pub fn bmp_from_bytes<'e, COLOUR>(bytes: &[u8]) -> RPiResult<'e, Bmp<COLOUR>>
+pub fn bmp_from_bytes<COLOUR>(bytes: &[u8]) -> RPiResult<Bmp<COLOUR>>
 where
     COLOUR:
         PixelColor + From<pixelcolor::Rgb555> + From<pixelcolor::Rgb565> + From<pixelcolor::Rgb888>,
 {
     Bmp::from_slice(bytes).into_rpi_result()
 }
+
+/// The largest image decoded by [`decode_bmp`], in pixels, so that a crafted header
+/// cannot make it allocate more than 64 MiB of pixels and alpha.
+const MAX_PIXELS: u32 = 4096 * 4096;
+
+/// Get the number of pixels of an image of the given size, rejecting images larger
+/// than [`MAX_PIXELS`].
+fn pixel_count(size: Size) -> RPiResult<usize> {
+    size.width
+        .checked_mul(size.height)
+        .filter(|count| *count <= MAX_PIXELS)
+        .map(|count| count as usize)
+        .ok_or(RPiError::BMPError(ParseError::InvalidImageDimensions))
+}
+
+/// Read a little-endian [`u32`] at `offset`, if the bytes are long enough.
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|slice| u32::from_le_bytes([slice[0], slice[1], slice[2], slice[3]]))
+}
+
+/// Extract a channel from a raw pixel value using its mask, scaled to 8 bits.
+fn extract_channel(value: u32, mask: u32) -> u8 {
+    if mask == 0 {
+        return 0;
+    }
+
+    let shift = mask.trailing_zeros();
+    let max = mask >> shift;
+
+    (((value & mask) >> shift) as u64 * 255 / max as u64) as u8
+}
+
+/// Layout of an uncompressed 32-bit BMP with an alpha channel.
+struct AlphaBmpLayout {
+    size: Size,
+    top_down: bool,
+    data_start: usize,
+    masks: [u32; 4],
+}
+
+impl AlphaBmpLayout {
+    /// Parse the layout from the headers, if the file is a 32-bit BMP that declares
+    /// an alpha mask.
+    ///
+    /// The alpha mask is either part of a `BITMAPV3INFOHEADER` or later, or follows
+    /// a `BITMAPINFOHEADER` with the `BI_ALPHABITFIELDS` compression method.
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        const BI_BITFIELDS: u32 = 3;
+        const BI_ALPHABITFIELDS: u32 = 6;
+
+        let data_start = read_u32(bytes, 10)? as usize;
+        let header_size = read_u32(bytes, 14)?;
+        let width = read_u32(bytes, 18)? as i32;
+        let height = read_u32(bytes, 22)? as i32;
+        let bpp = u16::from_le_bytes([*bytes.get(28)?, *bytes.get(29)?]);
+        let compression = read_u32(bytes, 30)?;
+
+        let has_alpha_mask = match compression {
+            BI_BITFIELDS => header_size >= 56,
+            BI_ALPHABITFIELDS => true,
+            _ => false,
+        };
+
+        if bpp != 32 || width <= 0 || height == 0 || !has_alpha_mask {
+            return None;
+        }
+
+        // The masks immediately follow the 40 bytes of a `BITMAPINFOHEADER` in
+        // either case.
+        let masks = [
+            read_u32(bytes, 54)?,
+            read_u32(bytes, 58)?,
+            read_u32(bytes, 62)?,
+            read_u32(bytes, 66)?,
+        ];
+
+        (masks[3] != 0).then_some(Self {
+            size: Size::new(width as u32, height.unsigned_abs()),
+            top_down: height < 0,
+            data_start,
+            masks,
+        })
+    }
+}
+
+/// Decode a bitmap image from a bytes array into [`Rgb888`] pixels.
+///
+/// Uncompressed 32-bit images that declare an alpha mask keep their alpha channel;
+/// every other image supported by [`bmp_from_bytes`] is decoded as fully opaque.
+///
+/// [`Rgb888`]: pixelcolor::Rgb888
+pub fn decode_bmp(bytes: &[u8]) -> RPiResult<DecodedPixels> {
+    let Some(layout) = AlphaBmpLayout::parse(bytes) else {
+        let bmp = bmp_from_bytes::<pixelcolor::Rgb888>(bytes)?;
+        let size = bmp.size();
+
+        let mut pixels = vec![pixelcolor::Rgb888::BLACK; pixel_count(size)?];
+        bmp.pixels().for_each(|Pixel(point, colour)| {
+            pixels[point.y as usize * size.width as usize + point.x as usize] = colour;
+        });
+
+        return Ok(DecodedPixels {
+            size,
+            pixels,
+            alpha: None,
+        });
+    };
+
+    let AlphaBmpLayout {
+        size,
+        top_down,
+        data_start,
+        masks: [red, green, blue, alpha],
+    } = layout;
+    let pixel_count = pixel_count(size)?;
+
+    let data = data_start
+        .checked_add(pixel_count * 4)
+        .and_then(|data_end| bytes.get(data_start..data_end))
+        .ok_or(RPiError::BMPError(ParseError::UnexpectedEndOfFile))?;
+
+    let mut pixels = Vec::with_capacity(pixel_count);
+    let mut alphas = Vec::with_capacity(pixel_count);
+
+    for row in 0..size.height as usize {
+        // Rows are stored bottom-up unless the height is negative.
+        let row = if top_down {
+            row
+        } else {
+            size.height as usize - 1 - row
+        };
+        let start = row * size.width as usize * 4;
+
+        for pixel in data[start..start + size.width as usize * 4].chunks_exact(4) {
+            let value = u32::from_le_bytes([pixel[0], pixel[1], pixel[2], pixel[3]]);
+
+            pixels.push(pixelcolor::Rgb888::new(
+                extract_channel(value, red),
+                extract_channel(value, green),
+                extract_channel(value, blue),
+            ));
+            alphas.push(extract_channel(value, alpha));
+        }
+    }
+
+    Ok(DecodedPixels {
+        size,
+        pixels,
+        alpha: Some(alphas),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pixelcolor::Rgb888;
+
+    /// Build a 32-bit `BITMAPV4HEADER` image with 8-bit `BGRA` channels, from rows of
+    /// `(red, green, blue, alpha)` pixels stored in the given order.
+    fn bmp(width: i32, height: i32, rows: &[[(u8, u8, u8, u8); 2]]) -> Vec<u8> {
+        const DATA_START: u32 = 14 + 108;
+
+        let mut bytes = Vec::new();
+        bytes.extend(b"BM");
+        bytes.extend((DATA_START + rows.len() as u32 * 8).to_le_bytes());
+        bytes.extend([0; 4]);
+        bytes.extend(DATA_START.to_le_bytes());
+
+        bytes.extend(108_u32.to_le_bytes());
+        bytes.extend(width.to_le_bytes());
+        bytes.extend(height.to_le_bytes());
+        bytes.extend(1_u16.to_le_bytes());
+        bytes.extend(32_u16.to_le_bytes());
+        // `BI_BITFIELDS`
+        bytes.extend(3_u32.to_le_bytes());
+        bytes.extend([0; 20]);
+        for mask in [0x00FF_0000_u32, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000] {
+            bytes.extend(mask.to_le_bytes());
+        }
+        bytes.resize(DATA_START as usize, 0);
+
+        for (red, green, blue, alpha) in rows.iter().flatten() {
+            bytes.extend([*blue, *green, *red, *alpha]);
+        }
+
+        bytes
+    }
+
+    const ROWS: [[(u8, u8, u8, u8); 2]; 2] = [
+        [(255, 0, 0, 255), (0, 255, 0, 128)],
+        [(0, 0, 255, 0), (10, 20, 30, 40)],
+    ];
+
+    #[test]
+    fn bottom_up_with_alpha() {
+        let decoded = decode_bmp(&bmp(2, 2, &ROWS)).unwrap();
+
+        assert_eq!(decoded.size, Size::new(2, 2));
+        // The last row stored is the top row of the image.
+        assert_eq!(
+            decoded.pixels,
+            [
+                Rgb888::new(0, 0, 255),
+                Rgb888::new(10, 20, 30),
+                Rgb888::new(255, 0, 0),
+                Rgb888::new(0, 255, 0),
+            ]
+        );
+        assert_eq!(decoded.alpha.as_deref(), Some(&[0, 40, 255, 128][..]));
+    }
+
+    #[test]
+    fn top_down_with_alpha() {
+        let decoded = decode_bmp(&bmp(2, -2, &ROWS)).unwrap();
+
+        assert_eq!(decoded.pixels[0], Rgb888::new(255, 0, 0));
+        assert_eq!(decoded.alpha.as_deref(), Some(&[255, 128, 0, 40][..]));
+    }
+
+    #[test]
+    fn decoded_images_keep_the_alpha() {
+        use crate::images::{BmpDecoder, DecodedImage};
+
+        let bytes = bmp(2, 2, &ROWS);
+
+        let image = DecodedImage::<Rgb888, BmpDecoder>::from_bytes(&bytes).unwrap();
+        assert_eq!(image.alpha(), Some(&[0, 40, 255, 128][..]));
+        assert_eq!(image.pixel(Point::new(0, 0)), Some(Rgb888::new(0, 0, 255)));
+
+        let keyed = DecodedImage::<Rgb888, BmpDecoder>::from_bytes_with_colour_key(
+            &bytes,
+            Some(Rgb888::new(255, 0, 0)),
+        )
+        .unwrap();
+        assert_eq!(keyed.alpha(), Some(&[0, 40, 0, 128][..]));
+    }
+
+    #[test]
+    fn truncated_data_is_an_error() {
+        let bytes = bmp(2, 2, &ROWS);
+
+        for length in [bytes.len() - 1, 14 + 108, 70] {
+            let err = decode_bmp(&bytes[..length]).unwrap_err();
+            assert_eq!(err.code(), 201, "{length} bytes");
+        }
+    }
+
+    #[test]
+    fn oversized_images_are_rejected() {
+        // Neither the product overflowing `u32`, nor one too large to allocate, is
+        // read past the end of the file.
+        for (width, height) in [(0x1_0000, 0x1_0000), (i32::MAX, -2), (4097, 4096)] {
+            let err = decode_bmp(&bmp(width, height, &ROWS)).unwrap_err();
+
+            assert!(
+                matches!(err, RPiError::BMPError(ParseError::InvalidImageDimensions)),
+                "{width}x{height}: {err}"
+            );
+        }
+    }
+}
@@ -73,19 +73,19 @@ where
 
 /// Create an [`Image`] from an [`ImageDrawable`] by cropping it, and offsetting
 /// it to keep it in the same position as the original image.
-pub fn draw_cropped_in_place<'e, COLOUR, T, DT>(
+pub fn draw_cropped_in_place<COLOUR, T, DT>(
     target: &mut DT,
     raw: &T,
     x1: i32,
     y1: i32,
     x2: i32,
     y2: i32,
-) -> RPiResult<'e, ()>
+) -> RPiResult<()>
 where
     COLOUR: PixelColor + From<<COLOUR as PixelColor>::Raw>,
     T: ImageDrawable<Color = COLOUR>,
     DT: DrawTarget<Color = COLOUR>,
-    DT::Error: Into<RPiError<'e>>,
+    DT::Error: Into<RPiError>,
 {
     let cropped = crop_raw_to(raw, x1, y1, x2, y2);
 
@@ -0,0 +1,135 @@
+//! Dithering functions.
+//!
+
+use crate::foreign_types::*;
+use pixelcolor::Rgb888;
+
+/// Convert [`Rgb888`] pixels into `COLOUR`, diffusing the rounding error of each
+/// pixel onto its neighbours using the Floyd–Steinberg matrix:
+///
+/// ```text
+///        *   7/16
+/// 3/16 5/16  1/16
+/// ```
+///
+/// Pixels that are fully transparent in `alpha`, if given, are converted to the
+/// nearest colour as they are; they neither take nor spread any error, so that the
+/// colour hidden behind them does not bleed into the visible pixels around them.
+pub fn floyd_steinberg<COLOUR>(size: Size, pixels: &[Rgb888], alpha: Option<&[u8]>) -> Vec<COLOUR>
+where
+    COLOUR: PixelColor + From<Rgb888> + Into<Rgb888>,
+{
+    let width = size.width as usize;
+    if width == 0 {
+        return Vec::new();
+    }
+
+    let mut output = Vec::with_capacity(pixels.len());
+
+    // Errors carried into the current and the next row, 16 times their actual value,
+    // with a column of padding on each side.
+    let mut current = vec![[0_i32; 3]; width + 2];
+    let mut next = vec![[0_i32; 3]; width + 2];
+
+    for (y, row) in pixels.chunks(width).enumerate() {
+        for (x, pixel) in row.iter().enumerate() {
+            if alpha.and_then(|alpha| alpha.get(y * width + x)) == Some(&0) {
+                output.push(COLOUR::from(*pixel));
+                continue;
+            }
+
+            let carried = current[x + 1];
+            let channel = |value: u8, index: usize| {
+                (value as i32 + carried[index] / 16).clamp(0, u8::MAX as i32)
+            };
+            let wanted = [
+                channel(pixel.r(), 0),
+                channel(pixel.g(), 1),
+                channel(pixel.b(), 2),
+            ];
+
+            let colour = COLOUR::from(Rgb888::new(
+                wanted[0] as u8,
+                wanted[1] as u8,
+                wanted[2] as u8,
+            ));
+            let actual: Rgb888 = colour.into();
+
+            let error = [
+                wanted[0] - actual.r() as i32,
+                wanted[1] - actual.g() as i32,
+                wanted[2] - actual.b() as i32,
+            ];
+            for (index, error) in error.into_iter().enumerate() {
+                current[x + 2][index] += error * 7;
+                next[x][index] += error * 3;
+                next[x + 1][index] += error * 5;
+                next[x + 2][index] += error;
+            }
+
+            output.push(colour);
+        }
+
+        std::mem::swap(&mut current, &mut next);
+        next.fill([0; 3]);
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pixelcolor::Rgb565;
+
+    #[test]
+    fn diffuses_error_onto_neighbours() {
+        // A dark grey just below the first step of 16-bit colour, which converts to
+        // black everywhere without dithering.
+        let colour = Rgb888::new(4, 2, 4);
+        let pixels = [colour; 4];
+
+        let output: Vec<Rgb565> = floyd_steinberg(Size::new(2, 2), &pixels, None);
+
+        // 7/16 of the error of the first pixel rounds the second one up; the green
+        // error of both is carried down onto the third, and the red and blue error
+        // of the third onto the fourth.
+        assert_eq!(
+            output,
+            [
+                Rgb565::new(0, 0, 0),
+                Rgb565::new(1, 0, 1),
+                Rgb565::new(0, 1, 0),
+                Rgb565::new(1, 0, 1),
+            ]
+        );
+        assert_eq!(pixels.map(Rgb565::from), [Rgb565::BLACK; 4]);
+    }
+
+    #[test]
+    fn empty_images() {
+        assert!(floyd_steinberg::<Rgb565>(Size::new(0, 4), &[], None).is_empty());
+        assert!(floyd_steinberg::<Rgb565>(Size::new(4, 0), &[], None).is_empty());
+    }
+
+    #[test]
+    fn transparent_pixels_do_not_spread_error() {
+        // Just below a step of 16-bit colour, so that any error carried in rounds
+        // the pixel up.
+        let colour = Rgb888::new(4, 2, 4);
+        let size = Size::new(2, 2);
+        let pixels = [colour; 4];
+
+        let opaque: Vec<Rgb565> = floyd_steinberg(size, &pixels, None);
+        let masked: Vec<Rgb565> = floyd_steinberg(size, &pixels, Some(&[0, 255, 255, 255]));
+
+        // The transparent pixel behaves as one without any error of its own.
+        let mut exact = pixels;
+        exact[0] = Rgb888::BLACK;
+        let exact: Vec<Rgb565> = floyd_steinberg(size, &exact, None);
+
+        assert_eq!(masked[0], Rgb565::from(colour));
+        assert_eq!(masked[1..], exact[1..]);
+        assert_ne!(opaque[1..], masked[1..]);
+    }
+}
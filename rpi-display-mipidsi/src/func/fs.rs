@@ -1,6 +1,6 @@
 //! Basic functions for IO operations.
 
-use rpi_errors::{IntoRPiResult, RPiResult};
+use rpi_errors::{RPiResult, ResultContext};
 use std::path::Path;
 use tokio::fs;
 
@@ -8,11 +8,11 @@ use tokio::fs;
 use crate::foreign_types::logger;
 
 /// Read bytes from file.
-pub async fn read_bytes_from_file<'e>(
-    path: impl AsRef<Path> + std::fmt::Debug,
-) -> RPiResult<'e, Vec<u8>> {
+pub async fn read_bytes_from_file(path: impl AsRef<Path> + std::fmt::Debug) -> RPiResult<Vec<u8>> {
     #[cfg(feature = "debug")]
-    logger::debug(&format!("Reading bytes from {:?}...", path));
+    logger::debug!("Reading bytes from {:?}...", path);
 
-    fs::read(path).await.into_rpi_result()
+    fs::read(path.as_ref())
+        .await
+        .with_context(|| format!("read {}", path.as_ref().display()))
 }
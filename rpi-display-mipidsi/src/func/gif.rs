@@ -0,0 +1,208 @@
+//! GIF image functions.
+//!
+
+use std::time::Duration;
+
+use gif::{ColorOutput, DecodeOptions, DisposalMethod, Repeat};
+
+use crate::{
+    foreign_types::*,
+    images::{DecodedAnimation, DecodedFrame, DecodedPixels},
+};
+use pixelcolor::Rgb888;
+use primitives::Rectangle;
+
+/// Frame delays shorter than this are replaced by [`DEFAULT_FRAME_DELAY`], in line
+/// with what most browsers do.
+const MINIMUM_FRAME_DELAY: Duration = Duration::from_millis(20);
+
+/// The delay used for frames that do not specify a usable one.
+const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(100);
+
+/// Get the smallest rectangle containing both rectangles.
+fn envelope(a: &Rectangle, b: &Rectangle) -> Rectangle {
+    match (a.bottom_right(), b.bottom_right()) {
+        (None, _) => *b,
+        (_, None) => *a,
+        (Some(a_end), Some(b_end)) => Rectangle::with_corners(
+            a.top_left.component_min(b.top_left),
+            a_end.component_max(b_end),
+        ),
+    }
+}
+
+/// Copy the pixels within `area` out of a canvas of the given width.
+fn crop(canvas: &[Rgb888], width: u32, area: &Rectangle) -> Vec<Rgb888> {
+    area.rows()
+        .flat_map(|y| {
+            let start = y as usize * width as usize + area.top_left.x as usize;
+            canvas[start..start + area.size.width as usize]
+                .iter()
+                .copied()
+        })
+        .collect()
+}
+
+/// Decode all the frames of a GIF image from a bytes array.
+///
+/// Each frame is composited onto the frames before it according to their disposal
+/// methods; transparent areas that were never drawn are black. Only the first frame
+/// is kept whole: every other frame only keeps the area that changed from the frame
+/// before it, so that memory grows with the changes rather than with the number of
+/// frames.
+pub fn decode_gif(bytes: &[u8]) -> RPiResult<DecodedAnimation> {
+    let mut options = DecodeOptions::new();
+    options.set_color_output(ColorOutput::RGBA);
+
+    let mut decoder = options.read_info(bytes)?;
+    let size = Size::new(decoder.width() as u32, decoder.height() as u32);
+    let canvas_area = Rectangle::new(Point::zero(), size);
+
+    let mut canvas = vec![Rgb888::BLACK; (size.width * size.height) as usize];
+    let mut frames = Vec::new();
+
+    // The area cleared or restored by the disposal of the previous frame, which
+    // has to be redrawn even if the current frame does not cover it.
+    let mut disposed: Option<Rectangle> = None;
+
+    while let Some(frame) = decoder.read_next_frame()? {
+        let area = Rectangle::new(
+            Point::new(frame.left as i32, frame.top as i32),
+            Size::new(frame.width as u32, frame.height as u32),
+        )
+        .intersection(&canvas_area);
+
+        let previous = (frame.dispose == DisposalMethod::Previous).then(|| canvas.clone());
+
+        frame
+            .buffer
+            .chunks_exact(4)
+            .enumerate()
+            .filter(|(_, pixel)| pixel[3] != 0)
+            .for_each(|(offset, pixel)| {
+                let point = Point::new(
+                    frame.left as i32 + (offset % frame.width as usize) as i32,
+                    frame.top as i32 + (offset / frame.width as usize) as i32,
+                );
+
+                if canvas_area.contains(point) {
+                    canvas[point.y as usize * size.width as usize + point.x as usize] =
+                        Rgb888::new(pixel[0], pixel[1], pixel[2]);
+                }
+            });
+
+        let delay = Duration::from_millis(frame.delay as u64 * 10);
+        let delta = disposed
+            .take()
+            .map_or(area, |disposed| envelope(&disposed, &area));
+        let delta = if frames.is_empty() {
+            canvas_area
+        } else {
+            delta
+        };
+
+        frames.push(DecodedFrame {
+            pixels: DecodedPixels {
+                size: delta.size,
+                pixels: crop(&canvas, size.width, &delta),
+                alpha: None,
+            },
+            delay: if delay < MINIMUM_FRAME_DELAY {
+                DEFAULT_FRAME_DELAY
+            } else {
+                delay
+            },
+            delta,
+        });
+
+        match (frame.dispose, previous) {
+            (DisposalMethod::Background, _) => {
+                area.rows().for_each(|y| {
+                    let start = y as usize * size.width as usize + area.top_left.x as usize;
+                    canvas[start..start + area.size.width as usize].fill(Rgb888::BLACK);
+                });
+                disposed = Some(area);
+            }
+            (DisposalMethod::Previous, Some(previous)) => {
+                canvas = previous;
+                disposed = Some(area);
+            }
+            _ => {}
+        }
+    }
+
+    if frames.is_empty() {
+        return Err(RPiError::GIFError("No frames found.".into(), None));
+    }
+
+    Ok(DecodedAnimation {
+        size,
+        frames,
+        play_count: match decoder.repeat() {
+            Repeat::Infinite => None,
+            Repeat::Finite(repeats) => Some(repeats as u32 + 1),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use gif::{Encoder, Frame};
+
+    use super::*;
+
+    /// Encode a 4x4 GIF from `(area, colour, dispose)` frames of solid colours.
+    fn encode(frames: &[(Rectangle, [u8; 3], DisposalMethod)]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        let mut encoder = Encoder::new(&mut bytes, 4, 4, &[]).unwrap();
+
+        for &(area, [r, g, b], dispose) in frames {
+            let mut pixels =
+                [r, g, b, 255].repeat(area.size.width as usize * area.size.height as usize);
+            let mut frame =
+                Frame::from_rgba(area.size.width as u16, area.size.height as u16, &mut pixels);
+            frame.left = area.top_left.x as u16;
+            frame.top = area.top_left.y as u16;
+            frame.dispose = dispose;
+            encoder.write_frame(&frame).unwrap();
+        }
+
+        drop(encoder);
+        bytes
+    }
+
+    #[test]
+    fn only_the_first_frame_is_kept_whole() {
+        let full = Rectangle::new(Point::zero(), Size::new(4, 4));
+        let square = Rectangle::new(Point::new(1, 1), Size::new(2, 2));
+        let corner = Rectangle::new(Point::zero(), Size::new(1, 1));
+
+        let animation = decode_gif(&encode(&[
+            (full, [255, 0, 0], DisposalMethod::Keep),
+            (square, [0, 255, 0], DisposalMethod::Background),
+            (corner, [0, 0, 255], DisposalMethod::Keep),
+        ]))
+        .unwrap();
+
+        assert_eq!(animation.size, full.size);
+        let deltas: Vec<_> = animation.frames.iter().map(|frame| frame.delta).collect();
+        // The square cleared by the second frame has to be redrawn by the third.
+        let envelope = Rectangle::new(Point::zero(), Size::new(3, 3));
+        assert_eq!(deltas, [full, square, envelope]);
+
+        for frame in &animation.frames {
+            assert_eq!(frame.pixels.size, frame.delta.size);
+            assert_eq!(
+                frame.pixels.pixels.len(),
+                (frame.delta.size.width * frame.delta.size.height) as usize
+            );
+        }
+
+        assert_eq!(animation.frames[1].pixels.pixels, [Rgb888::GREEN; 4]);
+        let third = &animation.frames[2].pixels.pixels;
+        assert_eq!(third[0], Rgb888::BLUE);
+        assert_eq!(third[1], Rgb888::RED);
+        // Cleared by the disposal of the second frame.
+        assert_eq!(third[4], Rgb888::BLACK);
+    }
+}
@@ -0,0 +1,190 @@
+//! Pixel processing kernels for whole buffers of pixels.
+//!
+//! With the `simd` feature, these are vectorised using [`std::simd`], which
+//! requires a nightly compiler; otherwise they fall back to the equivalent
+//! [`scalar`] implementations. Both produce identical output.
+//!
+//! Each kernel only processes as many pixels as the shortest of its slices.
+
+use crate::foreign_types::*;
+use pixelcolor::Rgb888;
+
+pub mod scalar;
+
+#[cfg(feature = "simd")]
+pub mod simd;
+
+#[cfg(feature = "simd")]
+use simd as implementation;
+
+#[cfg(not(feature = "simd"))]
+use scalar as implementation;
+
+/// Convert [`Rgb888`] pixels into the raw values of [`Rgb565`], rounded in the same
+/// way as [`embedded_graphics`].
+///
+/// [`Rgb565`]: pixelcolor::Rgb565
+pub fn rgb888_to_rgb565(pixels: &[Rgb888], output: &mut [u16]) {
+    implementation::rgb888_to_rgb565(pixels, output)
+}
+
+/// Convert [`Rgb888`] pixels into the raw values of [`Bgr565`], rounded in the same
+/// way as [`embedded_graphics`].
+///
+/// [`Bgr565`]: pixelcolor::Bgr565
+pub fn rgb888_to_bgr565(pixels: &[Rgb888], output: &mut [u16]) {
+    implementation::rgb888_to_bgr565(pixels, output)
+}
+
+/// Swap the bytes of each value in place, such as to convert native little endian
+/// values into the big endian order expected by the panels.
+pub fn swap_bytes(values: &mut [u16]) {
+    implementation::swap_bytes(values)
+}
+
+/// Write each value into `bytes` in big endian order, as expected by the panels;
+/// `bytes` needs to be twice as long as `values`.
+pub fn pack_be(values: &[u16], bytes: &mut [u8]) {
+    implementation::pack_be(values, bytes)
+}
+
+/// Blend `foreground` over `background` with the opacity of each pixel in `alpha`,
+/// where `0` is fully transparent and `255` is fully opaque.
+pub fn blend(foreground: &[Rgb888], background: &[Rgb888], alpha: &[u8], output: &mut [Rgb888]) {
+    implementation::blend(foreground, background, alpha, output)
+}
+
+/// Interpolate linearly from `from` to `to`, where a `factor` of `0` is `from` and
+/// `255` is `to`.
+pub fn crossfade(from: &[Rgb888], to: &[Rgb888], factor: u8, output: &mut [Rgb888]) {
+    implementation::crossfade(from, to, factor, output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pixelcolor::{Bgr565, Rgb565};
+
+    /// Deterministic pseudo-random pixels, with a length that does not fill a whole
+    /// number of vectors.
+    fn pixels(seed: u32) -> Vec<Rgb888> {
+        let mut state = seed;
+        let mut next = move || {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (state >> 16) as u8
+        };
+
+        (0..1027)
+            .map(|_| Rgb888::new(next(), next(), next()))
+            .collect()
+    }
+
+    #[test]
+    fn rgb565_matches_embedded_graphics() {
+        let input = pixels(1);
+        let mut output = vec![0; input.len()];
+        rgb888_to_rgb565(&input, &mut output);
+
+        input.iter().zip(&output).for_each(|(pixel, value)| {
+            assert_eq!(Rgb565::from(*pixel).into_storage(), *value);
+        });
+
+        rgb888_to_bgr565(&input, &mut output);
+        input.iter().zip(&output).for_each(|(pixel, value)| {
+            assert_eq!(Bgr565::from(*pixel).into_storage(), *value);
+        });
+    }
+
+    #[test]
+    fn blend_matches_reference() {
+        for alpha in 0..=255 {
+            for foreground in 0..=255 {
+                for background in [0, 1, 127, 128, 254, 255] {
+                    assert_eq!(
+                        scalar::blend_channel(foreground, background, alpha),
+                        crate::func::blending::blend_channel(foreground, background, alpha),
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pack_be_matches_to_be_bytes() {
+        let values = (0..1027_u32)
+            .map(|value| (value * 64_013) as u16)
+            .collect::<Vec<_>>();
+        let mut bytes = vec![0; values.len() * 2];
+        pack_be(&values, &mut bytes);
+
+        let mut swapped = values.clone();
+        swap_bytes(&mut swapped);
+
+        values.iter().enumerate().for_each(|(index, value)| {
+            assert_eq!(bytes[index * 2..index * 2 + 2], value.to_be_bytes());
+            assert_eq!(swapped[index], value.swap_bytes());
+        });
+    }
+
+    #[cfg(feature = "simd")]
+    mod simd_equivalence {
+        use super::*;
+
+        #[test]
+        fn rgb888_to_565() {
+            let input = pixels(2);
+            let (mut vectorised, mut scalar_output) = (vec![0; input.len()], vec![0; input.len()]);
+
+            simd::rgb888_to_rgb565(&input, &mut vectorised);
+            scalar::rgb888_to_rgb565(&input, &mut scalar_output);
+            assert_eq!(vectorised, scalar_output);
+
+            simd::rgb888_to_bgr565(&input, &mut vectorised);
+            scalar::rgb888_to_bgr565(&input, &mut scalar_output);
+            assert_eq!(vectorised, scalar_output);
+        }
+
+        #[test]
+        fn bytes() {
+            let values = (0..1027_u32)
+                .map(|value| (value * 40_503) as u16)
+                .collect::<Vec<_>>();
+
+            let (mut vectorised, mut scalar_output) = (values.clone(), values.clone());
+            simd::swap_bytes(&mut vectorised);
+            scalar::swap_bytes(&mut scalar_output);
+            assert_eq!(vectorised, scalar_output);
+
+            let (mut vectorised, mut scalar_output) =
+                (vec![0; values.len() * 2], vec![0; values.len() * 2]);
+            simd::pack_be(&values, &mut vectorised);
+            scalar::pack_be(&values, &mut scalar_output);
+            assert_eq!(vectorised, scalar_output);
+        }
+
+        #[test]
+        fn blend() {
+            let (foreground, background) = (pixels(3), pixels(4));
+            let alpha = pixels(5).iter().map(|pixel| pixel.r()).collect::<Vec<_>>();
+
+            let mut vectorised = vec![Rgb888::BLACK; foreground.len()];
+            let mut scalar_output = vectorised.clone();
+            simd::blend(&foreground, &background, &alpha, &mut vectorised);
+            scalar::blend(&foreground, &background, &alpha, &mut scalar_output);
+            assert_eq!(vectorised, scalar_output);
+        }
+
+        #[test]
+        fn crossfade() {
+            let (from, to) = (pixels(6), pixels(7));
+
+            for factor in [0, 1, 64, 128, 200, 255] {
+                let mut vectorised = vec![Rgb888::BLACK; from.len()];
+                let mut scalar_output = vectorised.clone();
+                simd::crossfade(&from, &to, factor, &mut vectorised);
+                scalar::crossfade(&from, &to, factor, &mut scalar_output);
+                assert_eq!(vectorised, scalar_output);
+            }
+        }
+    }
+}
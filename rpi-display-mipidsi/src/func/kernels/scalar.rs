@@ -0,0 +1,88 @@
+//! Scalar implementations of the kernels, used when the `simd` feature is disabled
+//! and for the remainders that do not fill a whole vector.
+//!
+
+use crate::foreign_types::*;
+use pixelcolor::Rgb888;
+
+/// Fixed point shift used to scale channels, as in [`embedded_graphics`].
+const SHIFT: u32 = 24;
+
+/// Scale an 8-bit channel down to `MAX`, rounding to the nearest value in the same
+/// way as the colour conversions of [`embedded_graphics`].
+pub(super) fn scale_channel<const MAX: u32>(value: u8) -> u16 {
+    ((value as u32 * ((MAX << SHIFT) / 255) + (1 << (SHIFT - 1))) >> SHIFT) as u16
+}
+
+/// Divide by 255, rounding down; exact for all values below `65280`.
+pub(super) fn div_255(value: u16) -> u16 {
+    (value + 1 + (value >> 8)) >> 8
+}
+
+/// Blend a single channel, rounding to the nearest value.
+pub(super) fn blend_channel(foreground: u8, background: u8, alpha: u8) -> u8 {
+    div_255(foreground as u16 * alpha as u16 + background as u16 * (255 - alpha as u16) + 127) as u8
+}
+
+/// See [`super::rgb888_to_rgb565`].
+pub fn rgb888_to_rgb565(pixels: &[Rgb888], output: &mut [u16]) {
+    pixels.iter().zip(output).for_each(|(pixel, output)| {
+        *output = scale_channel::<31>(pixel.r()) << 11
+            | scale_channel::<63>(pixel.g()) << 5
+            | scale_channel::<31>(pixel.b());
+    });
+}
+
+/// See [`super::rgb888_to_bgr565`].
+pub fn rgb888_to_bgr565(pixels: &[Rgb888], output: &mut [u16]) {
+    pixels.iter().zip(output).for_each(|(pixel, output)| {
+        *output = scale_channel::<31>(pixel.b()) << 11
+            | scale_channel::<63>(pixel.g()) << 5
+            | scale_channel::<31>(pixel.r());
+    });
+}
+
+/// See [`super::swap_bytes`].
+pub fn swap_bytes(values: &mut [u16]) {
+    values
+        .iter_mut()
+        .for_each(|value| *value = value.swap_bytes());
+}
+
+/// See [`super::pack_be`].
+pub fn pack_be(values: &[u16], bytes: &mut [u8]) {
+    values
+        .iter()
+        .zip(bytes.chunks_exact_mut(2))
+        .for_each(|(value, bytes)| bytes.copy_from_slice(&value.to_be_bytes()));
+}
+
+/// See [`super::blend`].
+pub fn blend(foreground: &[Rgb888], background: &[Rgb888], alpha: &[u8], output: &mut [Rgb888]) {
+    foreground
+        .iter()
+        .zip(background)
+        .zip(alpha)
+        .zip(output)
+        .for_each(|(((foreground, background), alpha), output)| {
+            *output = Rgb888::new(
+                blend_channel(foreground.r(), background.r(), *alpha),
+                blend_channel(foreground.g(), background.g(), *alpha),
+                blend_channel(foreground.b(), background.b(), *alpha),
+            );
+        });
+}
+
+/// See [`super::crossfade`].
+pub fn crossfade(from: &[Rgb888], to: &[Rgb888], factor: u8, output: &mut [Rgb888]) {
+    from.iter()
+        .zip(to)
+        .zip(output)
+        .for_each(|((from, to), output)| {
+            *output = Rgb888::new(
+                blend_channel(to.r(), from.r(), factor),
+                blend_channel(to.g(), from.g(), factor),
+                blend_channel(to.b(), from.b(), factor),
+            );
+        });
+}
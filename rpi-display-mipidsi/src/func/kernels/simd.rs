@@ -0,0 +1,184 @@
+//! Vectorised implementations of the kernels, using [`std::simd`].
+//!
+//! Each kernel processes whole vectors of [`LANES`] pixels, then hands the
+//! remainder over to its scalar counterpart, so that both produce identical output.
+
+use std::simd::{prelude::*, ToBytes};
+
+use super::scalar;
+use crate::foreign_types::*;
+use pixelcolor::Rgb888;
+
+/// Number of pixels processed at a time.
+pub const LANES: usize = 8;
+
+/// Fixed point shift used to scale channels, as in [`embedded_graphics`].
+const SHIFT: u32 = 24;
+
+/// Load one channel of a vector of pixels.
+fn load_channel(pixels: &[Rgb888], channel: impl Fn(&Rgb888) -> u8) -> Simd<u8, LANES> {
+    Simd::from_array(std::array::from_fn(|index| channel(&pixels[index])))
+}
+
+/// Store channels into a vector of pixels.
+fn store_pixels(output: &mut [Rgb888], [r, g, b]: [Simd<u8, LANES>; 3]) {
+    let (r, g, b) = (r.to_array(), g.to_array(), b.to_array());
+
+    output
+        .iter_mut()
+        .enumerate()
+        .for_each(|(index, pixel)| *pixel = Rgb888::new(r[index], g[index], b[index]));
+}
+
+/// See [`scalar::scale_channel`].
+fn scale_channel<const MAX: u32>(value: Simd<u8, LANES>) -> Simd<u16, LANES> {
+    ((value.cast::<u32>() * Simd::splat((MAX << SHIFT) / 255) + Simd::splat(1 << (SHIFT - 1)))
+        >> Simd::splat(SHIFT))
+    .cast()
+}
+
+/// See [`scalar::div_255`].
+fn div_255(value: Simd<u16, LANES>) -> Simd<u16, LANES> {
+    (value + Simd::splat(1) + (value >> Simd::splat(8))) >> Simd::splat(8)
+}
+
+/// See [`scalar::blend_channel`].
+fn blend_channel(
+    foreground: Simd<u8, LANES>,
+    background: Simd<u8, LANES>,
+    alpha: Simd<u16, LANES>,
+) -> Simd<u8, LANES> {
+    div_255(
+        foreground.cast::<u16>() * alpha
+            + background.cast::<u16>() * (Simd::splat(255) - alpha)
+            + Simd::splat(127),
+    )
+    .cast()
+}
+
+/// Convert pixels into 16-bit values, with the channels in the given order from
+/// the most significant bits.
+fn rgb888_to_565(pixels: &[Rgb888], output: &mut [u16], channels: [fn(&Rgb888) -> u8; 3]) -> usize {
+    let length = pixels.len().min(output.len()) / LANES * LANES;
+
+    pixels[..length]
+        .chunks_exact(LANES)
+        .zip(output[..length].chunks_exact_mut(LANES))
+        .for_each(|(pixels, output)| {
+            let [high, middle, low] = channels;
+
+            (scale_channel::<31>(load_channel(pixels, high)) << Simd::splat(11)
+                | scale_channel::<63>(load_channel(pixels, middle)) << Simd::splat(5)
+                | scale_channel::<31>(load_channel(pixels, low)))
+            .copy_to_slice(output);
+        });
+
+    length
+}
+
+/// See [`super::rgb888_to_rgb565`].
+pub fn rgb888_to_rgb565(pixels: &[Rgb888], output: &mut [u16]) {
+    let done = rgb888_to_565(pixels, output, [Rgb888::r, Rgb888::g, Rgb888::b]);
+
+    scalar::rgb888_to_rgb565(&pixels[done..], &mut output[done..]);
+}
+
+/// See [`super::rgb888_to_bgr565`].
+pub fn rgb888_to_bgr565(pixels: &[Rgb888], output: &mut [u16]) {
+    let done = rgb888_to_565(pixels, output, [Rgb888::b, Rgb888::g, Rgb888::r]);
+
+    scalar::rgb888_to_bgr565(&pixels[done..], &mut output[done..]);
+}
+
+/// See [`super::swap_bytes`].
+pub fn swap_bytes(values: &mut [u16]) {
+    let mut chunks = values.chunks_exact_mut(LANES);
+
+    chunks.by_ref().for_each(|chunk| {
+        Simd::<u16, LANES>::from_slice(chunk)
+            .swap_bytes()
+            .copy_to_slice(chunk)
+    });
+
+    scalar::swap_bytes(chunks.into_remainder());
+}
+
+/// See [`super::pack_be`].
+pub fn pack_be(values: &[u16], bytes: &mut [u8]) {
+    let length = values.len().min(bytes.len() / 2) / LANES * LANES;
+
+    values[..length]
+        .chunks_exact(LANES)
+        .zip(bytes[..length * 2].chunks_exact_mut(LANES * 2))
+        .for_each(|(values, bytes)| {
+            Simd::<u16, LANES>::from_slice(values)
+                .to_be_bytes()
+                .copy_to_slice(bytes);
+        });
+
+    scalar::pack_be(&values[length..], &mut bytes[length * 2..]);
+}
+
+/// See [`super::blend`].
+pub fn blend(foreground: &[Rgb888], background: &[Rgb888], alpha: &[u8], output: &mut [Rgb888]) {
+    let length = foreground
+        .len()
+        .min(background.len())
+        .min(alpha.len())
+        .min(output.len())
+        / LANES
+        * LANES;
+
+    for start in (0..length).step_by(LANES) {
+        let range = start..start + LANES;
+        let (foreground, background) = (&foreground[range.clone()], &background[range.clone()]);
+        let alpha = Simd::<u8, LANES>::from_slice(&alpha[range.clone()]).cast::<u16>();
+
+        store_pixels(
+            &mut output[range],
+            [Rgb888::r, Rgb888::g, Rgb888::b].map(|channel| {
+                blend_channel(
+                    load_channel(foreground, channel),
+                    load_channel(background, channel),
+                    alpha,
+                )
+            }),
+        );
+    }
+
+    scalar::blend(
+        &foreground[length..],
+        &background[length..],
+        &alpha[length..],
+        &mut output[length..],
+    );
+}
+
+/// See [`super::crossfade`].
+pub fn crossfade(from: &[Rgb888], to: &[Rgb888], factor: u8, output: &mut [Rgb888]) {
+    let length = from.len().min(to.len()).min(output.len()) / LANES * LANES;
+    let alpha = Simd::splat(factor as u16);
+
+    for start in (0..length).step_by(LANES) {
+        let range = start..start + LANES;
+        let (from, to) = (&from[range.clone()], &to[range.clone()]);
+
+        store_pixels(
+            &mut output[range],
+            [Rgb888::r, Rgb888::g, Rgb888::b].map(|channel| {
+                blend_channel(
+                    load_channel(to, channel),
+                    load_channel(from, channel),
+                    alpha,
+                )
+            }),
+        );
+    }
+
+    scalar::crossfade(
+        &from[length..],
+        &to[length..],
+        factor,
+        &mut output[length..],
+    );
+}
@@ -1,11 +1,24 @@
 //! Helper functions for the display.
 
+pub mod blending;
 pub mod crop;
+pub mod dithering;
 pub mod fs;
 pub mod image_conversions;
+pub mod kernels;
+pub mod transform;
 
 #[cfg(feature = "transitions")]
 pub mod transitions;
 
 #[cfg(feature = "bmp")]
 pub mod bmp;
+
+#[cfg(feature = "gif")]
+pub mod gif;
+
+#[cfg(feature = "png")]
+pub mod png;
+
+#[cfg(feature = "qoi")]
+pub mod qoi;
@@ -0,0 +1,138 @@
+//! PNG image functions.
+//!
+
+use crate::{foreign_types::*, images::DecodedPixels};
+use png::{ColorType, Decoder, Transformations};
+
+/// Decode a PNG image from a bytes array.
+///
+/// # Note
+///
+/// Palettes are expanded, and 16-bit channels are reduced to 8-bit.
+pub fn decode_png(bytes: &[u8]) -> RPiResult<DecodedPixels> {
+    let mut decoder = Decoder::new(bytes);
+    decoder.set_transformations(Transformations::normalize_to_color8());
+
+    let mut reader = decoder.read_info().into_rpi_result()?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buffer).into_rpi_result()?;
+
+    let channels = &buffer[..info.buffer_size()];
+    let size = Size::new(info.width, info.height);
+
+    match info.color_type {
+        ColorType::Rgb => Ok(DecodedPixels::from_rgb(size, channels)),
+        ColorType::Rgba => Ok(DecodedPixels::from_rgba(size, channels)),
+        ColorType::Grayscale => Ok(DecodedPixels::from_rgb(
+            size,
+            &channels
+                .iter()
+                .flat_map(|value| [*value; 3])
+                .collect::<Vec<_>>(),
+        )),
+        ColorType::GrayscaleAlpha => Ok(DecodedPixels::from_rgba(
+            size,
+            &channels
+                .chunks_exact(2)
+                .flat_map(|pixel| [pixel[0], pixel[0], pixel[0], pixel[1]])
+                .collect::<Vec<_>>(),
+        )),
+        ColorType::Indexed => Err(RPiError::PNGError(
+            "Indexed colours were not expanded by the decoder.".into(),
+            None,
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::images::{DecodedImage, PngDecoder};
+    use pixelcolor::Rgb888;
+    use png::{BitDepth, Encoder};
+
+    /// Encode a 2x1 PNG image from its raw channels.
+    fn png(color_type: ColorType, bit_depth: BitDepth, data: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+
+        let mut encoder = Encoder::new(&mut bytes, 2, 1);
+        encoder.set_color(color_type);
+        encoder.set_depth(bit_depth);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(data).unwrap();
+        writer.finish().unwrap();
+
+        bytes
+    }
+
+    #[test]
+    fn rgb_without_alpha() {
+        let decoded = decode_png(&png(
+            ColorType::Rgb,
+            BitDepth::Eight,
+            &[255, 0, 0, 10, 20, 30],
+        ))
+        .unwrap();
+
+        assert_eq!(decoded.size, Size::new(2, 1));
+        assert_eq!(
+            decoded.pixels,
+            [Rgb888::new(255, 0, 0), Rgb888::new(10, 20, 30)]
+        );
+        assert_eq!(decoded.alpha, None);
+    }
+
+    #[test]
+    fn rgba_keeps_the_alpha() {
+        let bytes = png(
+            ColorType::Rgba,
+            BitDepth::Eight,
+            &[255, 0, 0, 255, 10, 20, 30, 40],
+        );
+
+        let decoded = decode_png(&bytes).unwrap();
+        assert_eq!(
+            decoded.pixels,
+            [Rgb888::new(255, 0, 0), Rgb888::new(10, 20, 30)]
+        );
+        assert_eq!(decoded.alpha.as_deref(), Some(&[255, 40][..]));
+
+        let image = DecodedImage::<Rgb888, PngDecoder>::from_bytes(&bytes).unwrap();
+        assert_eq!(image.alpha(), Some(&[255, 40][..]));
+        assert_eq!(image.pixel(Point::new(1, 0)), Some(Rgb888::new(10, 20, 30)));
+    }
+
+    #[test]
+    fn grayscale_is_expanded() {
+        let decoded = decode_png(&png(ColorType::Grayscale, BitDepth::Eight, &[0, 200])).unwrap();
+        assert_eq!(decoded.pixels, [Rgb888::BLACK, Rgb888::new(200, 200, 200)]);
+        assert_eq!(decoded.alpha, None);
+
+        let decoded = decode_png(&png(
+            ColorType::GrayscaleAlpha,
+            BitDepth::Eight,
+            &[50, 255, 200, 0],
+        ))
+        .unwrap();
+        assert_eq!(
+            decoded.pixels,
+            [Rgb888::new(50, 50, 50), Rgb888::new(200, 200, 200)]
+        );
+        assert_eq!(decoded.alpha.as_deref(), Some(&[255, 0][..]));
+    }
+
+    #[test]
+    fn sixteen_bit_channels_are_reduced() {
+        let decoded = decode_png(&png(
+            ColorType::Grayscale,
+            BitDepth::Sixteen,
+            &[0x12, 0x34, 0xFF, 0xFF],
+        ))
+        .unwrap();
+
+        assert_eq!(
+            decoded.pixels,
+            [Rgb888::new(0x12, 0x12, 0x12), Rgb888::WHITE]
+        );
+    }
+}
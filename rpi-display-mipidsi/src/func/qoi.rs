@@ -0,0 +1,53 @@
+//! QOI image functions.
+//!
+
+use crate::{foreign_types::*, images::DecodedPixels};
+use qoi::Channels;
+
+/// Decode a QOI image from a bytes array.
+pub fn decode_qoi(bytes: &[u8]) -> RPiResult<DecodedPixels> {
+    let (header, channels) = qoi::decode_to_vec(bytes).into_rpi_result()?;
+    let size = Size::new(header.width, header.height);
+
+    Ok(match header.channels {
+        Channels::Rgb => DecodedPixels::from_rgb(size, &channels),
+        Channels::Rgba => DecodedPixels::from_rgba(size, &channels),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::images::{DecodedImage, QoiDecoder};
+    use pixelcolor::Rgb888;
+
+    #[test]
+    fn rgb_without_alpha() {
+        let bytes = qoi::encode_to_vec([255, 0, 0, 10, 20, 30], 2, 1).unwrap();
+        let decoded = decode_qoi(&bytes).unwrap();
+
+        assert_eq!(decoded.size, Size::new(2, 1));
+        assert_eq!(
+            decoded.pixels,
+            [Rgb888::new(255, 0, 0), Rgb888::new(10, 20, 30)]
+        );
+        assert_eq!(decoded.alpha, None);
+    }
+
+    #[test]
+    fn rgba_keeps_the_alpha() {
+        let bytes = qoi::encode_to_vec([255, 0, 0, 255, 10, 20, 30, 40], 1, 2).unwrap();
+
+        let decoded = decode_qoi(&bytes).unwrap();
+        assert_eq!(decoded.size, Size::new(1, 2));
+        assert_eq!(
+            decoded.pixels,
+            [Rgb888::new(255, 0, 0), Rgb888::new(10, 20, 30)]
+        );
+        assert_eq!(decoded.alpha.as_deref(), Some(&[255, 40][..]));
+
+        let image = DecodedImage::<Rgb888, QoiDecoder>::from_bytes(&bytes).unwrap();
+        assert_eq!(image.alpha(), Some(&[255, 40][..]));
+        assert_eq!(image.pixel(Point::new(0, 1)), Some(Rgb888::new(10, 20, 30)));
+    }
+}
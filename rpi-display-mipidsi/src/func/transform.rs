@@ -0,0 +1,428 @@
+//! Transform images into new [`RasterImage`]s.
+//!
+//! All transforms keep the alpha channel of the image, if any.
+
+use crate::{foreign_types::*, images::RasterImage};
+use pixelcolor::Rgb888;
+
+/// Clockwise rotation of an image by a multiple of 90 degrees.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    Deg90,
+    Deg180,
+    Deg270,
+}
+
+/// Axis to flip an image across.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Flip {
+    /// Mirror the image left to right.
+    Horizontal,
+
+    /// Mirror the image top to bottom.
+    Vertical,
+}
+
+/// Filter used to sample the source image when scaling.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ScalingFilter {
+    /// Use the closest source pixel; keeps hard edges, suitable for pixel art and
+    /// integer scales.
+    #[default]
+    Nearest,
+
+    /// Interpolate between the 4 closest source pixels; smoother, suitable for
+    /// photos and fractional scales.
+    Bilinear,
+}
+
+/// Build a new image by looking up each of its pixels in the source image.
+///
+/// `source_index` maps each point of the new image to an index of the source.
+fn remap<COLOUR>(
+    image: &RasterImage<COLOUR>,
+    size: Size,
+    source_index: impl Fn(usize, usize) -> usize,
+) -> RasterImage<COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    let indices = (0..size.height as usize)
+        .flat_map(|y| (0..size.width as usize).map(move |x| (x, y)))
+        .map(|(x, y)| source_index(x, y))
+        .collect::<Vec<_>>();
+
+    RasterImage::from_parts(
+        size,
+        indices.iter().map(|index| image.pixels()[*index]).collect(),
+        image
+            .alpha()
+            .map(|alpha| indices.iter().map(|index| alpha[*index]).collect()),
+    )
+}
+
+/// Rotate an image clockwise.
+pub fn rotate<COLOUR>(image: &RasterImage<COLOUR>, rotation: Rotation) -> RasterImage<COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    let Size { width, height } = image.size();
+    let (w, h) = (width as usize, height as usize);
+
+    match rotation {
+        Rotation::Deg90 => remap(image, Size::new(height, width), |x, y| (h - 1 - x) * w + y),
+        Rotation::Deg180 => remap(image, image.size(), |x, y| (h - 1 - y) * w + (w - 1 - x)),
+        Rotation::Deg270 => remap(image, Size::new(height, width), |x, y| x * w + (w - 1 - y)),
+    }
+}
+
+/// Flip an image across an axis.
+pub fn flip<COLOUR>(image: &RasterImage<COLOUR>, flip: Flip) -> RasterImage<COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    let Size { width, height } = image.size();
+    let (w, h) = (width as usize, height as usize);
+
+    match flip {
+        Flip::Horizontal => remap(image, image.size(), |x, y| y * w + (w - 1 - x)),
+        Flip::Vertical => remap(image, image.size(), |x, y| (h - 1 - y) * w + x),
+    }
+}
+
+/// Map a coordinate of the scaled image to the source image, sampling at the
+/// centre of each pixel.
+fn source_coordinate(coordinate: usize, source_length: u32, length: u32) -> f32 {
+    ((coordinate as f32 + 0.5) * source_length as f32 / length as f32 - 0.5)
+        .clamp(0., (source_length - 1) as f32)
+}
+
+/// Scale an image to the given size, using the closest source pixel.
+///
+/// An empty image cannot be scaled, and is returned as it is.
+pub fn scale_nearest<COLOUR>(image: &RasterImage<COLOUR>, size: Size) -> RasterImage<COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    let source = image.size();
+    if source.width == 0 || source.height == 0 {
+        return image.clone();
+    }
+
+    remap(image, size, |x, y| {
+        let source_x = source_coordinate(x, source.width, size.width).round() as usize;
+        let source_y = source_coordinate(y, source.height, size.height).round() as usize;
+
+        source_y * source.width as usize + source_x
+    })
+}
+
+/// Scale an image to the given size, interpolating between the 4 closest source
+/// pixels.
+///
+/// Colours are weighted by their opacity, so that fully transparent pixels do not
+/// bleed their colour into the edges of the image. An empty image cannot be
+/// scaled, and is returned as it is.
+pub fn scale_bilinear<COLOUR>(image: &RasterImage<COLOUR>, size: Size) -> RasterImage<COLOUR>
+where
+    COLOUR: PixelColor + From<Rgb888> + Into<Rgb888>,
+{
+    let source = image.size();
+    if source.width == 0 || source.height == 0 {
+        return image.clone();
+    }
+
+    let source_width = source.width as usize;
+    let alpha_at = |index: usize| image.alpha().map_or(u8::MAX, |alpha| alpha[index]);
+
+    let mut pixels = Vec::with_capacity((size.width * size.height) as usize);
+    let mut alphas = Vec::with_capacity(pixels.capacity());
+
+    for y in 0..size.height as usize {
+        let source_y = source_coordinate(y, source.height, size.height);
+        let (y0, fraction_y) = (source_y.floor() as usize, source_y.fract());
+        let y1 = (y0 + 1).min(source.height as usize - 1);
+
+        for x in 0..size.width as usize {
+            let source_x = source_coordinate(x, source.width, size.width);
+            let (x0, fraction_x) = (source_x.floor() as usize, source_x.fract());
+            let x1 = (x0 + 1).min(source_width - 1);
+
+            let samples = [
+                (
+                    y0 * source_width + x0,
+                    (1. - fraction_x) * (1. - fraction_y),
+                ),
+                (y0 * source_width + x1, fraction_x * (1. - fraction_y)),
+                (y1 * source_width + x0, (1. - fraction_x) * fraction_y),
+                (y1 * source_width + x1, fraction_x * fraction_y),
+            ];
+
+            let mut channels = [0_f32; 3];
+            let mut alpha = 0_f32;
+            for (index, weight) in samples {
+                let colour: Rgb888 = image.pixels()[index].into();
+                let weight = weight * alpha_at(index) as f32 / 255.;
+
+                channels[0] += colour.r() as f32 * weight;
+                channels[1] += colour.g() as f32 * weight;
+                channels[2] += colour.b() as f32 * weight;
+                alpha += weight;
+            }
+
+            let [r, g, b] = channels.map(|channel| {
+                if alpha > 0. {
+                    (channel / alpha).round().clamp(0., 255.) as u8
+                } else {
+                    0
+                }
+            });
+
+            pixels.push(COLOUR::from(Rgb888::new(r, g, b)));
+            alphas.push((alpha * 255.).round().clamp(0., 255.) as u8);
+        }
+    }
+
+    RasterImage::from_parts(size, pixels.into(), image.alpha().map(|_| alphas.into()))
+}
+
+/// Scale an image to the given size, using the given filter.
+pub fn scale<COLOUR>(
+    image: &RasterImage<COLOUR>,
+    size: Size,
+    filter: ScalingFilter,
+) -> RasterImage<COLOUR>
+where
+    COLOUR: PixelColor + From<Rgb888> + Into<Rgb888>,
+{
+    match filter {
+        ScalingFilter::Nearest => scale_nearest(image, size),
+        ScalingFilter::Bilinear => scale_bilinear(image, size),
+    }
+}
+
+/// Tint an image by multiplying each of its pixels with `colour`.
+///
+/// For a monochrome icon drawn in white, this recolours the icon to `colour` while
+/// keeping any anti-aliased edges.
+pub fn tint<COLOUR>(image: &RasterImage<COLOUR>, colour: COLOUR) -> RasterImage<COLOUR>
+where
+    COLOUR: PixelColor + From<Rgb888> + Into<Rgb888>,
+{
+    let tint: Rgb888 = colour.into();
+    let multiply = |value: u8, by: u8| ((value as u16 * by as u16 + 127) / 255) as u8;
+
+    RasterImage::from_parts(
+        image.size(),
+        image
+            .pixels()
+            .iter()
+            .map(|pixel| {
+                let pixel: Rgb888 = (*pixel).into();
+
+                COLOUR::from(Rgb888::new(
+                    multiply(pixel.r(), tint.r()),
+                    multiply(pixel.g(), tint.g()),
+                    multiply(pixel.b(), tint.b()),
+                ))
+            })
+            .collect(),
+        image.alpha().map(Into::into),
+    )
+}
+
+/// Replace every pixel of the colour `from` with `to`.
+pub fn recolour<COLOUR>(
+    image: &RasterImage<COLOUR>,
+    from: COLOUR,
+    to: COLOUR,
+) -> RasterImage<COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    RasterImage::from_parts(
+        image.size(),
+        image
+            .pixels()
+            .iter()
+            .map(|pixel| if *pixel == from { to } else { *pixel })
+            .collect(),
+        image.alpha().map(Into::into),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 3x2 image whose pixels and alpha are numbered in row-major order:
+    ///
+    /// ```text
+    /// 0 1 2
+    /// 3 4 5
+    /// ```
+    fn numbered() -> RasterImage<Rgb888> {
+        RasterImage::new(
+            Size::new(3, 2),
+            (0..6)
+                .map(|index| Rgb888::new(index, 0, 0))
+                .collect::<Vec<_>>(),
+        )
+        .unwrap()
+        .with_alpha((0..6).map(|index| index * 10).collect::<Vec<_>>())
+        .unwrap()
+    }
+
+    /// Get the numbers of the pixels of an image, checking that the alpha channel
+    /// was moved along with them.
+    fn numbers(image: &RasterImage<Rgb888>) -> Vec<u8> {
+        let numbers = image
+            .pixels()
+            .iter()
+            .map(|pixel| pixel.r())
+            .collect::<Vec<_>>();
+        let alpha = image.alpha().unwrap();
+
+        assert_eq!(
+            alpha,
+            numbers.iter().map(|number| number * 10).collect::<Vec<_>>()
+        );
+        numbers
+    }
+
+    /// Create a single row image from the red channels and alpha of its pixels.
+    fn row(red: &[u8], alpha: Option<&[u8]>) -> RasterImage<Rgb888> {
+        let image = RasterImage::new(
+            Size::new(red.len() as u32, 1),
+            red.iter()
+                .map(|red| Rgb888::new(*red, 0, 0))
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+
+        match alpha {
+            Some(alpha) => image.with_alpha(alpha).unwrap(),
+            None => image,
+        }
+    }
+
+    #[test]
+    fn rotations() {
+        let rotated = rotate(&numbered(), Rotation::Deg90);
+        assert_eq!(rotated.size(), Size::new(2, 3));
+        assert_eq!(numbers(&rotated), [3, 0, 4, 1, 5, 2]);
+
+        let rotated = rotate(&numbered(), Rotation::Deg180);
+        assert_eq!(rotated.size(), Size::new(3, 2));
+        assert_eq!(numbers(&rotated), [5, 4, 3, 2, 1, 0]);
+
+        let rotated = rotate(&numbered(), Rotation::Deg270);
+        assert_eq!(rotated.size(), Size::new(2, 3));
+        assert_eq!(numbers(&rotated), [2, 5, 1, 4, 0, 3]);
+    }
+
+    #[test]
+    fn four_quarter_turns_are_the_identity() {
+        let image = numbered();
+        let turned = (0..4).fold(image.clone(), |image, _| image.rotated(Rotation::Deg90));
+
+        assert_eq!(turned, image);
+        assert_eq!(
+            image.rotated(Rotation::Deg90).rotated(Rotation::Deg270),
+            image
+        );
+    }
+
+    #[test]
+    fn flips() {
+        assert_eq!(
+            numbers(&flip(&numbered(), Flip::Horizontal)),
+            [2, 1, 0, 5, 4, 3]
+        );
+        assert_eq!(
+            numbers(&flip(&numbered(), Flip::Vertical)),
+            [3, 4, 5, 0, 1, 2]
+        );
+        assert_eq!(
+            numbered().flipped(Flip::Horizontal).flipped(Flip::Vertical),
+            numbered().rotated(Rotation::Deg180)
+        );
+    }
+
+    #[test]
+    fn nearest_scaling() {
+        let scaled = scale_nearest(&numbered(), Size::new(6, 4));
+        assert_eq!(scaled.size(), Size::new(6, 4));
+        assert_eq!(
+            numbers(&scaled),
+            [
+                0, 0, 1, 1, 2, 2, //
+                0, 0, 1, 1, 2, 2, //
+                3, 3, 4, 4, 5, 5, //
+                3, 3, 4, 4, 5, 5,
+            ]
+        );
+
+        assert_eq!(scale_nearest(&scaled, Size::new(3, 2)), numbered());
+    }
+
+    #[test]
+    fn bilinear_scaling() {
+        let scaled = scale_bilinear(&row(&[0, 255], None), Size::new(4, 1));
+        assert_eq!(scaled, row(&[0, 64, 191, 255], None));
+
+        // The same size samples exactly at each pixel.
+        assert_eq!(scale_bilinear(&numbered(), Size::new(3, 2)), numbered());
+    }
+
+    #[test]
+    fn bilinear_scaling_weighs_colours_by_opacity() {
+        let scaled = scale_bilinear(&row(&[255, 100], Some(&[255, 0])), Size::new(4, 1));
+
+        // The colour of the transparent pixel does not bleed into its neighbour,
+        // which only fades out.
+        assert_eq!(scaled, row(&[255, 255, 255, 0], Some(&[255, 191, 64, 0])));
+    }
+
+    #[test]
+    fn empty_images_are_not_scaled() {
+        let empty = RasterImage::<Rgb888>::new(Size::zero(), Vec::new()).unwrap();
+
+        assert_eq!(
+            scale(&empty, Size::new(4, 4), ScalingFilter::Nearest),
+            empty
+        );
+        assert_eq!(
+            scale(&empty, Size::new(4, 4), ScalingFilter::Bilinear),
+            empty
+        );
+    }
+
+    #[test]
+    fn tinting_multiplies_and_keeps_alpha() {
+        let image = RasterImage::new(
+            Size::new(2, 1),
+            vec![Rgb888::WHITE, Rgb888::new(128, 128, 128)],
+        )
+        .unwrap()
+        .with_alpha([255, 100])
+        .unwrap();
+
+        let tinted = tint(&image, Rgb888::new(255, 128, 0));
+        assert_eq!(
+            tinted.pixels(),
+            [Rgb888::new(255, 128, 0), Rgb888::new(128, 64, 0)]
+        );
+        assert_eq!(tinted.alpha(), Some(&[255, 100][..]));
+    }
+
+    #[test]
+    fn recolouring_replaces_exact_matches() {
+        let recoloured = recolour(&row(&[1, 2, 1], None), Rgb888::new(1, 0, 0), Rgb888::BLUE);
+
+        assert_eq!(
+            recoloured.pixels(),
+            [Rgb888::BLUE, Rgb888::new(2, 0, 0), Rgb888::BLUE]
+        );
+    }
+}
@@ -2,12 +2,17 @@
 //!
 
 use crate::foreign_types::*;
-use std::{sync::OnceLock, time::Duration};
-use tokio::time::Instant;
+use std::{future::Future, time::Duration};
+use tokio::sync::watch;
 
-use crate::traits::DrawTransition;
+use super::{CancelBehaviour, FrameClock, TransitionProgress, TransitionStats};
+use crate::{images::RasterImage, traits::DrawTransition};
 
 /// Transition between two images.
+///
+/// Progress can be reported through a [`watch`] channel with
+/// [`with_progress`](Self::with_progress), and the transition can be cancelled
+/// cleanly with [`start_until`](Self::start_until).
 pub struct Transition<'a, COLOUR, T1, T2, F, DT>
 where
     COLOUR: PixelColor + From<<COLOUR as PixelColor>::Raw>,
@@ -21,15 +26,16 @@ where
     from: &'a T1,
     to: &'a T2,
     steps: u32,
-    step: u32,
 
-    start_time: OnceLock<Instant>,
-    step_duration: Duration,
+    /// The step drawn last, if any.
+    step: Option<u32>,
+
+    clock: FrameClock,
 
     transition: F,
 
-    #[cfg(feature = "debug")]
-    skipped_count: u32,
+    stats: TransitionStats,
+    progress: Option<watch::Sender<TransitionProgress>>,
 }
 
 impl<'a, COLOUR, T1, T2, F, DT> Transition<'a, COLOUR, T1, T2, F, DT>
@@ -49,93 +55,139 @@ where
         steps: u32,
         duration: Duration,
     ) -> Self {
-        let step_duration = duration / steps;
-
         Self {
             target,
             from,
             to,
             steps,
-            step: 0,
+            step: None,
 
-            start_time: OnceLock::new(),
-            step_duration,
+            clock: FrameClock::with_steps(steps, duration),
 
             transition,
 
-            #[cfg(feature = "debug")]
-            skipped_count: 0,
+            stats: TransitionStats::default(),
+            progress: None,
         }
     }
 
-    /// Get the start time of the transition; if it has not been started yet,
-    /// start it now.
-    fn start_time(&self) -> &Instant {
-        self.start_time.get_or_init(Instant::now)
+    /// Get the draw target of the transition, such as to recover it after a failure.
+    pub(crate) fn target_mut(&mut self) -> &mut DT {
+        self.target
+    }
+
+    /// Report the progress of the transition to `sender` after each frame drawn.
+    pub fn with_progress(mut self, sender: watch::Sender<TransitionProgress>) -> Self {
+        self.progress = Some(sender);
+        self
     }
 
     /// Get the current step of the transition.
     ///
-    /// This calculation is based on the start time of the transition, and the
-    /// duration of each step. This means that steps can be skipped if the
-    /// transition has taken more than the desired frame time. Skipped frames
-    /// would not be calculated at all.
-    ///
-    /// The transition will simply try its best to keep up with the desired
-    /// frame rate.
+    /// This calculation is based on the [`FrameClock`] of the transition, so steps
+    /// can be skipped if the transition has taken more than the desired frame time;
+    /// it never goes backwards, nor beyond the last step.
     fn calculate_current_step(&self) -> u32 {
-        let elapsed = self.start_time().elapsed();
-        let step = (elapsed.as_millis() / self.step_duration.as_millis()) as u32;
+        let first = self.step.map_or(0, |step| step + 1).min(self.steps);
 
-        step.max(self.step)
+        self.clock.current_step().clamp(first, self.steps)
     }
 
-    /// Get the deadline for the given frame.
-    fn deadline(&self, step: u32) -> Instant {
-        *self.start_time() + self.step_duration * step
+    /// Draw the given step, and count the steps skipped since the last one drawn.
+    fn draw_step(&mut self, step: u32) -> RPiResult<()> {
+        // Drawing the last step again, such as when starting a finished transition,
+        // skips nothing.
+        let skipped = step.saturating_sub(self.step.map_or(0, |last| last + 1));
+
+        #[cfg(feature = "debug")]
+        if skipped > 0 {
+            logger::debug!("Frame overtime, skipping {} frames.", skipped);
+        }
+
+        self.transition
+            .draw_frame(self.target, self.from, self.to, step)?;
+
+        self.step = Some(step);
+        self.stats.rendered += 1;
+        self.stats.skipped += skipped;
+        self.stats.elapsed = self.clock.elapsed();
+
+        if let Some(progress) = &self.progress {
+            progress.send_replace(TransitionProgress {
+                step,
+                steps: self.steps,
+                skipped: self.stats.skipped,
+                fps: self.stats.fps(),
+            });
+        }
+
+        Ok(())
     }
 
     /// Draw the entire transition to the given draw target. On failure, return a
     /// [`RPiError`] in the [`RPiResult`].
-    pub async fn start<'e>(&mut self) -> RPiResult<'e, ()>
+    pub async fn start(&mut self) -> RPiResult<TransitionStats> {
+        self.start_until(std::future::pending(), CancelBehaviour::default())
+            .await
+    }
+
+    /// Draw the entire transition to the given draw target, until `stop` completes,
+    /// such as `rpi_gpio::func::termination::ctrl_c()` or the receiving end of a
+    /// channel. On failure, including of `stop` itself, return a [`RPiError`] in
+    /// the [`RPiResult`].
+    ///
+    /// Frames are never interrupted half drawn; on cancellation, the last step is
+    /// either drawn or not according to `behaviour`, and the returned
+    /// [`TransitionStats`] are marked as cancelled.
+    pub async fn start_until<S>(
+        &mut self,
+        stop: S,
+        behaviour: CancelBehaviour,
+    ) -> RPiResult<TransitionStats>
     where
-        'a: 'e,
+        S: Future<Output = RPiResult<()>>,
     {
+        tokio::pin!(stop);
+
         loop {
             let step = self.calculate_current_step();
+            self.draw_step(step)?;
 
-            // The second check is necessary to confirm that we have at least issued the
-            // last step of the transition once.
-            if step > self.steps && self.step > self.steps {
-                #[cfg(feature = "debug")]
-                {
-                    let rendered_count = self.step - self.skipped_count;
-                    logger::info(&format!(
-                        "Transition finished, rendered {} frames, skipped {} frames @ {} FPS.",
-                        rendered_count,
-                        self.skipped_count,
-                        rendered_count as f32 / self.start_time().elapsed().as_secs_f32()
-                    ));
-                }
-
+            // The last step is always drawn, even if the steps before it were skipped.
+            if step >= self.steps {
                 break;
             }
 
-            self.transition
-                .draw_frame(self.target, self.from, self.to, self.step)?;
+            tokio::select! {
+                _ = tokio::time::sleep_until(self.clock.deadline(step + 1)) => {},
+                result = &mut stop => {
+                    result?;
 
-            #[cfg(feature = "debug")]
-            if step > self.step + 1 {
-                let skipped = step - self.step - 1;
-                logger::debug(&format!("Frame overtime, skipping {} frames.", skipped));
-                self.skipped_count += skipped;
-            }
+                    if behaviour == CancelBehaviour::JumpToEnd {
+                        self.draw_step(self.steps)?;
+                    }
+                    self.stats.cancelled = true;
+                    self.stats.elapsed = self.clock.elapsed();
 
-            self.step = step;
-            tokio::time::sleep_until(self.deadline(step + 1)).await;
+                    break;
+                }
+            }
         }
 
-        Ok(())
+        #[cfg(feature = "debug")]
+        logger::info!(
+            "Transition {}.",
+            if self.stats.cancelled {
+                "cancelled"
+            } else {
+                "finished"
+            };
+            rendered = self.stats.rendered,
+            skipped = self.stats.skipped,
+            fps = self.stats.fps(),
+        );
+
+        Ok(self.stats)
     }
 }
 
@@ -157,3 +209,187 @@ where
         Self::new(target, image, image, transition, steps, duration)
     }
 }
+
+/// Get the progress of a transition at `step`, from `0` at the first step, which
+/// shows the outgoing image, to `1` at the last step, which shows the incoming
+/// image.
+///
+/// Transitions used to start at `1 / steps`, which skipped the outgoing image and
+/// drew the incoming image twice; see [`DrawTransition`] for the steps drawn.
+pub(crate) fn progress(step: u32, steps: u32) -> f32 {
+    if steps == 0 {
+        1.
+    } else {
+        (step as f32 / steps as f32).min(1.)
+    }
+}
+
+/// Draw an image into a new [`RasterImage`] of the given size, such as the size of
+/// the target, so that its pixels can be read back by a transition.
+///
+/// Pixels not drawn by the image are left as [`Default`].
+pub(crate) fn rasterise<COLOUR, T>(image: &T, size: Size) -> RasterImage<COLOUR>
+where
+    COLOUR: PixelColor + Default,
+    T: ImageDrawable<Color = COLOUR>,
+{
+    let mut raster = RasterImage::filled(size, COLOUR::default());
+
+    match image.draw(&mut raster) {
+        Ok(()) => raster,
+        Err(never) => match never {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+    use pixelcolor::Rgb888;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    /// A transition recording every step drawn, and drawing it as the red channel.
+    fn recorder(
+        steps: &RefCell<Vec<u32>>,
+    ) -> impl Fn(
+        &mut RasterImage<Rgb888>,
+        &RasterImage<Rgb888>,
+        &RasterImage<Rgb888>,
+        u32,
+    ) -> RPiResult<()>
+           + '_ {
+        move |target, _, _, step| {
+            steps.borrow_mut().push(step);
+            target.set_pixel(Point::zero(), Rgb888::new(step as u8, 0, 0));
+            Ok(())
+        }
+    }
+
+    /// Wait for `millis`, then complete with `result`.
+    async fn stop_after(millis: u32, result: RPiResult<()>) -> RPiResult<()> {
+        tokio::time::sleep(MS * millis).await;
+        result
+    }
+
+    fn image() -> RasterImage<Rgb888> {
+        RasterImage::filled(Size::new(1, 1), Rgb888::BLACK)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cancelling_jumps_to_the_end() {
+        let (from, to, mut target) = (image(), image(), image());
+        let steps = RefCell::new(Vec::new());
+        let mut transition =
+            Transition::new(&mut target, &from, &to, recorder(&steps), 10, MS * 100);
+
+        let stats = transition
+            .start_until(stop_after(25, Ok(())), CancelBehaviour::JumpToEnd)
+            .await
+            .unwrap();
+
+        assert_eq!(*steps.borrow(), [0, 1, 2, 10]);
+        assert!(stats.cancelled);
+        assert_eq!(stats.rendered, 4);
+        assert_eq!(stats.elapsed, MS * 25);
+        assert_eq!(target.pixel(Point::zero()), Some(Rgb888::new(10, 0, 0)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cancelling_can_stay_on_the_last_step_drawn() {
+        let (from, to, mut target) = (image(), image(), image());
+        let steps = RefCell::new(Vec::new());
+        let mut transition =
+            Transition::new(&mut target, &from, &to, recorder(&steps), 10, MS * 100);
+
+        let stats = transition
+            .start_until(stop_after(25, Ok(())), CancelBehaviour::Stay)
+            .await
+            .unwrap();
+
+        assert_eq!(*steps.borrow(), [0, 1, 2]);
+        assert!(stats.cancelled);
+        assert_eq!(target.pixel(Point::zero()), Some(Rgb888::new(2, 0, 0)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failing_to_stop_is_an_error() {
+        let (from, to, mut target) = (image(), image(), image());
+        let steps = RefCell::new(Vec::new());
+        let mut transition =
+            Transition::new(&mut target, &from, &to, recorder(&steps), 10, MS * 100);
+
+        let result = transition
+            .start_until(
+                stop_after(25, Err(RPiError::Cancelled)),
+                CancelBehaviour::JumpToEnd,
+            )
+            .await;
+
+        assert!(matches!(result, Err(RPiError::Cancelled)));
+        assert_eq!(*steps.borrow(), [0, 1, 2]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stopping_after_the_end_does_not_cancel() {
+        let (from, to, mut target) = (image(), image(), image());
+        let steps = RefCell::new(Vec::new());
+        let mut transition = Transition::new(&mut target, &from, &to, recorder(&steps), 4, MS * 40);
+
+        let stats = transition
+            .start_until(stop_after(100, Ok(())), CancelBehaviour::Stay)
+            .await
+            .unwrap();
+
+        assert_eq!(*steps.borrow(), [0, 1, 2, 3, 4]);
+        assert!(!stats.cancelled);
+
+        // Starting a finished transition again only draws its last step.
+        let again = transition.start().await.unwrap();
+        assert_eq!(*steps.borrow(), [0, 1, 2, 3, 4, 4]);
+        assert!(!again.cancelled);
+        assert_eq!(again.skipped, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn progress_is_reported_after_each_frame() {
+        let (from, to, mut target) = (image(), image(), image());
+        let steps = RefCell::new(Vec::new());
+        let (sender, mut receiver) = watch::channel(TransitionProgress::default());
+        let mut transition = Transition::new(&mut target, &from, &to, recorder(&steps), 4, MS * 40)
+            .with_progress(sender);
+
+        // Watch the progress from the future that would stop the transition.
+        let seen = RefCell::new(Vec::new());
+        let watch = async {
+            while receiver.changed().await.is_ok() {
+                seen.borrow_mut().push(*receiver.borrow_and_update());
+            }
+            Ok(())
+        };
+        let stats = transition
+            .start_until(watch, CancelBehaviour::Stay)
+            .await
+            .unwrap();
+
+        let seen = seen.into_inner();
+        assert_eq!(
+            seen.iter()
+                .map(|progress| progress.step)
+                .collect::<Vec<_>>(),
+            [0, 1, 2, 3]
+        );
+        assert!(seen
+            .iter()
+            .all(|progress| progress.steps == 4 && progress.skipped == 0));
+        assert_eq!(seen[2].fraction(), 0.5);
+
+        // The last step is reported too, after the stop future was last polled.
+        let last = *receiver.borrow();
+        assert_eq!(last.step, 4);
+        assert_eq!(last.fraction(), 1.);
+        assert_eq!(last.fps, stats.fps());
+        assert_eq!(stats.elapsed, MS * 40);
+    }
+}
@@ -0,0 +1,123 @@
+//! The clock pacing the frames of transitions and timelines.
+//!
+
+use std::{sync::OnceLock, time::Duration};
+use tokio::time::Instant;
+
+/// A clock dividing time since its start into steps of equal duration.
+///
+/// Steps are calculated from the start time rather than counted, which means that
+/// steps can be skipped if drawing a frame has taken more than the duration of a
+/// step. Skipped steps would not be drawn at all; the transition will simply try
+/// its best to keep up with the desired frame rate.
+#[derive(Clone, Debug)]
+pub struct FrameClock {
+    start_time: OnceLock<Instant>,
+    step_duration: Duration,
+}
+
+impl FrameClock {
+    /// Create a new clock with the given duration of each step.
+    ///
+    /// The clock does not start until it is first read.
+    pub fn new(step_duration: Duration) -> Self {
+        Self {
+            start_time: OnceLock::new(),
+            step_duration,
+        }
+    }
+
+    /// Create a new clock dividing `duration` into `steps`.
+    pub fn with_steps(steps: u32, duration: Duration) -> Self {
+        Self::new(duration / steps.max(1))
+    }
+
+    /// Get the duration of each step.
+    pub fn step_duration(&self) -> Duration {
+        self.step_duration
+    }
+
+    /// Get the start time of the clock; if it has not been started yet, start it
+    /// now.
+    pub fn start_time(&self) -> &Instant {
+        self.start_time.get_or_init(Instant::now)
+    }
+
+    /// Get the time elapsed since the start of the clock.
+    pub fn elapsed(&self) -> Duration {
+        self.start_time().elapsed()
+    }
+
+    /// Get the step at the given time since the start of the clock.
+    ///
+    /// This is calculated in nanoseconds, so that steps shorter than a millisecond
+    /// are not truncated; if the step duration is zero, every step is already due,
+    /// and [`u32::MAX`] is returned.
+    pub fn step_at(&self, elapsed: Duration) -> u32 {
+        match self.step_duration.as_nanos() {
+            0 => u32::MAX,
+            nanos => (elapsed.as_nanos() / nanos).min(u32::MAX as u128) as u32,
+        }
+    }
+
+    /// Get the current step of the clock.
+    pub fn current_step(&self) -> u32 {
+        self.step_at(self.elapsed())
+    }
+
+    /// Get the time since the start of the clock at which the given step begins.
+    pub fn offset(&self, step: u32) -> Duration {
+        self.step_duration
+            .checked_mul(step)
+            .unwrap_or(Duration::MAX)
+    }
+
+    /// Get the deadline for the given step.
+    pub fn deadline(&self, step: u32) -> Instant {
+        let start_time = *self.start_time();
+
+        start_time
+            .checked_add(self.offset(step))
+            .unwrap_or_else(|| Instant::now() + self.step_duration)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sub_millisecond_steps_are_not_truncated() {
+        // 500µs per step, which used to be truncated to 0ms and divide by zero.
+        let clock = FrameClock::with_steps(1000, Duration::from_millis(500));
+        assert_eq!(clock.step_duration(), Duration::from_micros(500));
+
+        assert_eq!(clock.step_at(Duration::ZERO), 0);
+        assert_eq!(clock.step_at(Duration::from_micros(499)), 0);
+        assert_eq!(clock.step_at(Duration::from_micros(500)), 1);
+        assert_eq!(clock.step_at(Duration::from_micros(1999)), 3);
+        assert_eq!(clock.step_at(Duration::from_millis(500)), 1000);
+    }
+
+    #[test]
+    fn zero_step_duration_is_always_due() {
+        let clock = FrameClock::new(Duration::ZERO);
+        assert_eq!(clock.step_at(Duration::ZERO), u32::MAX);
+        assert_eq!(clock.step_at(Duration::from_secs(1)), u32::MAX);
+
+        // No steps at all does not divide by zero either.
+        let clock = FrameClock::with_steps(0, Duration::from_secs(1));
+        assert_eq!(clock.step_duration(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn offsets_saturate() {
+        let clock = FrameClock::new(Duration::from_millis(10));
+        assert_eq!(clock.offset(3), Duration::from_millis(30));
+        assert_eq!(clock.step_at(clock.offset(7)), 7);
+
+        let clock = FrameClock::new(Duration::MAX);
+        assert_eq!(clock.offset(2), Duration::MAX);
+        assert_eq!(clock.step_at(Duration::MAX), 1);
+    }
+}
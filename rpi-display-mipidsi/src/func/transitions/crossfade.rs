@@ -0,0 +1,82 @@
+//! Fade the outgoing image into the incoming image.
+//!
+
+use std::sync::OnceLock;
+
+use super::{progress, rasterise};
+use crate::traits::DrawTransition;
+use crate::{foreign_types::*, func, images::RasterImage};
+use pixelcolor::Rgb888;
+
+/// Blend the outgoing image into the incoming image over the whole target.
+///
+/// Both images are rasterised at the size of the target on the first frame, and
+/// kept for the rest of the transition; the returned transition should therefore
+/// only be used for a single pair of images.
+pub fn crossfade<'a, COLOUR, T1, T2, DT>(steps: u32) -> impl DrawTransition<'a, COLOUR, T1, T2, DT>
+where
+    COLOUR: PixelColor + From<<COLOUR as PixelColor>::Raw> + From<Rgb888> + Into<Rgb888> + Default,
+    T1: ImageDrawable<Color = COLOUR> + 'a,
+    T2: ImageDrawable<Color = COLOUR> + 'a,
+    DT: DrawTarget<Color = COLOUR> + 'a,
+    DT::Error: Into<RPiError>,
+{
+    let rasters = OnceLock::<(Vec<Rgb888>, Vec<Rgb888>)>::new();
+
+    move |target: &mut DT, from: &'a T1, to: &'a T2, step: u32| -> RPiResult<()> {
+        let area = target.bounding_box();
+        let (from, to) = rasters.get_or_init(|| {
+            let convert = |raster: RasterImage<COLOUR>| {
+                raster
+                    .pixels()
+                    .iter()
+                    .map(|pixel| (*pixel).into())
+                    .collect::<Vec<_>>()
+            };
+
+            (
+                convert(rasterise(from, area.size)),
+                convert(rasterise(to, area.size)),
+            )
+        });
+
+        let ratio = progress(step, steps);
+        let factor = (ratio * u8::MAX as f32).round() as u8;
+
+        #[cfg(feature = "debug")]
+        logger::trace!("Crossfading step: {}, factor: {}", step, factor);
+
+        let mut blended = vec![Rgb888::BLACK; from.len()];
+        func::kernels::crossfade(from, to, factor, &mut blended);
+
+        target
+            .fill_contiguous(&area, blended.into_iter().map(COLOUR::from))
+            .into_rpi_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fades_from_one_image_to_the_other() {
+        let from = RasterImage::filled(Size::new(2, 1), Rgb888::BLACK);
+        let to = RasterImage::filled(Size::new(2, 1), Rgb888::new(200, 100, 0));
+        let mut target = RasterImage::filled(Size::new(2, 1), Rgb888::RED);
+        let transition = crossfade(2);
+
+        transition.draw_frame(&mut target, &from, &to, 0).unwrap();
+        assert_eq!(target.pixels(), [Rgb888::BLACK; 2]);
+
+        transition.draw_frame(&mut target, &from, &to, 1).unwrap();
+        for pixel in target.pixels() {
+            assert!((99..=101).contains(&pixel.r()), "{pixel:?}");
+            assert!((49..=51).contains(&pixel.g()), "{pixel:?}");
+            assert_eq!(pixel.b(), 0);
+        }
+
+        transition.draw_frame(&mut target, &from, &to, 2).unwrap();
+        assert_eq!(target.pixels(), [Rgb888::new(200, 100, 0); 2]);
+    }
+}
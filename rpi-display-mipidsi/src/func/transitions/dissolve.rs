@@ -0,0 +1,98 @@
+//! Replace the outgoing image with the incoming image one random pixel at a time.
+//!
+
+use super::wipe;
+use crate::foreign_types::*;
+use crate::traits::DrawTransition;
+
+/// Get a pseudo-random value for a pixel, which is stable for the same seed.
+///
+/// This is the finaliser of SplitMix64, which is enough to scatter the pixels
+/// evenly without depending on a random number generator.
+fn scatter(seed: u64, index: usize) -> u64 {
+    let mut value = seed.wrapping_add((index as u64 + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    value ^ (value >> 31)
+}
+
+/// Replace the outgoing image with the incoming image in a random order of pixels,
+/// scattered by `seed`.
+///
+/// This is a [`wipe`] through a random mask, so it only draws the pixels switched
+/// since the last frame.
+pub fn dissolve<'a, COLOUR, T1, T2, DT>(
+    steps: u32,
+    seed: u64,
+) -> impl DrawTransition<'a, COLOUR, T1, T2, DT>
+where
+    COLOUR: PixelColor + From<<COLOUR as PixelColor>::Raw> + Default,
+    T1: ImageDrawable<Color = COLOUR> + 'a,
+    T2: ImageDrawable<Color = COLOUR> + 'a,
+    DT: DrawTarget<Color = COLOUR> + 'a,
+    DT::Error: Into<RPiError>,
+{
+    wipe(steps, move |point: Point, size: Size| {
+        let index = point.y as usize * size.width as usize + point.x as usize;
+
+        (scatter(seed, index) >> 40) as f32 / (1 << 24) as f32
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::images::RasterImage;
+    use pixelcolor::Rgb888;
+
+    const SIZE: Size = Size::new(8, 8);
+
+    /// Draw the steps of a dissolve from black to white, and get which pixels are
+    /// white after each one.
+    fn dissolved(steps: u32, seed: u64) -> Vec<Vec<bool>> {
+        let from = RasterImage::filled(SIZE, Rgb888::BLACK);
+        let to = RasterImage::filled(SIZE, Rgb888::WHITE);
+        let mut target = RasterImage::filled(SIZE, Rgb888::RED);
+        let transition = dissolve(steps, seed);
+
+        (0..=steps)
+            .map(|step| {
+                transition
+                    .draw_frame(&mut target, &from, &to, step)
+                    .unwrap();
+                target
+                    .pixels()
+                    .iter()
+                    .map(|pixel| *pixel == Rgb888::WHITE)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn pixels_switch_once_and_for_all() {
+        let frames = dissolved(4, 7);
+        let counts: Vec<_> = frames
+            .iter()
+            .map(|frame| frame.iter().filter(|white| **white).count())
+            .collect();
+
+        assert_eq!(counts[0], 0);
+        assert_eq!(counts[4], 64);
+        // Roughly half of the pixels have switched half way through.
+        assert!((16..=48).contains(&counts[2]), "{counts:?}");
+
+        for (before, after) in frames.iter().zip(&frames[1..]) {
+            assert!(before
+                .iter()
+                .zip(after)
+                .all(|(before, after)| !before || *after));
+        }
+    }
+
+    #[test]
+    fn the_seed_decides_the_order() {
+        assert_eq!(dissolved(4, 7), dissolved(4, 7));
+        assert_ne!(dissolved(4, 7)[2], dissolved(4, 8)[2]);
+    }
+}
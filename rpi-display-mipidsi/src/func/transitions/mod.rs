@@ -4,10 +4,31 @@
 mod base;
 pub use base::*;
 
+mod clock;
+pub use clock::*;
+
+mod crossfade;
+pub use crossfade::*;
+
+mod dissolve;
+pub use dissolve::*;
+
+mod slide;
+pub use slide::*;
+
+mod stats;
+pub use stats::*;
+
 mod sweep;
 pub use sweep::*;
 
+mod timeline;
+pub use timeline::*;
+
 mod transverse;
 pub use transverse::*;
 
-pub use crate::traits::DrawTransition;
+mod wipe;
+pub use wipe::*;
+
+pub use crate::traits::{DrawTransition, Playable};
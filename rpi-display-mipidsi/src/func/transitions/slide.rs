@@ -0,0 +1,297 @@
+//! Slide the incoming image in, the outgoing image out, or both.
+//!
+
+use std::fmt;
+
+use embedded_graphics::{draw_target::DrawTargetExt, primitives::Rectangle};
+
+use super::progress;
+use crate::foreign_types::*;
+use crate::traits::DrawTransition;
+
+/// The direction in which the images slide.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SlideDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl fmt::Display for SlideDirection {
+    /// Describe the direction, such as `to the left`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Left => "to the left",
+            Self::Right => "to the right",
+            Self::Up => "upwards",
+            Self::Down => "downwards",
+        })
+    }
+}
+
+impl SlideDirection {
+    /// The unit vector of the direction.
+    fn unit(&self) -> Point {
+        match self {
+            Self::Left => Point::new(-1, 0),
+            Self::Right => Point::new(1, 0),
+            Self::Up => Point::new(0, -1),
+            Self::Down => Point::new(0, 1),
+        }
+    }
+
+    /// The distance to slide across `area` to replace one image with the other.
+    fn length(&self, area: &Rectangle) -> u32 {
+        match self {
+            Self::Left | Self::Right => area.size.width,
+            Self::Up | Self::Down => area.size.height,
+        }
+    }
+
+    /// Split `area` into the part still covered by the outgoing image, and the
+    /// strip taken over by the incoming image after sliding by `distance`.
+    fn split(&self, area: &Rectangle, distance: u32) -> (Rectangle, Rectangle) {
+        let Size { width, height } = area.size;
+        let top_left = area.top_left;
+
+        match self {
+            Self::Left => (
+                Rectangle::new(top_left, Size::new(width - distance, height)),
+                Rectangle::new(
+                    top_left + Point::new((width - distance) as i32, 0),
+                    Size::new(distance, height),
+                ),
+            ),
+            Self::Right => (
+                Rectangle::new(
+                    top_left + Point::new(distance as i32, 0),
+                    Size::new(width - distance, height),
+                ),
+                Rectangle::new(top_left, Size::new(distance, height)),
+            ),
+            Self::Up => (
+                Rectangle::new(top_left, Size::new(width, height - distance)),
+                Rectangle::new(
+                    top_left + Point::new(0, (height - distance) as i32),
+                    Size::new(width, distance),
+                ),
+            ),
+            Self::Down => (
+                Rectangle::new(
+                    top_left + Point::new(0, distance as i32),
+                    Size::new(width, height - distance),
+                ),
+                Rectangle::new(top_left, Size::new(width, distance)),
+            ),
+        }
+    }
+}
+
+/// Slide across the target, moving the outgoing image, the incoming image, or
+/// both, in the given direction.
+fn slide<'a, COLOUR, T1, T2, DT>(
+    steps: u32,
+    direction: SlideDirection,
+    move_from: bool,
+    move_to: bool,
+) -> impl DrawTransition<'a, COLOUR, T1, T2, DT>
+where
+    COLOUR: PixelColor + From<<COLOUR as PixelColor>::Raw>,
+    T1: ImageDrawable<Color = COLOUR> + 'a,
+    T2: ImageDrawable<Color = COLOUR> + 'a,
+    DT: DrawTarget<Color = COLOUR> + 'a,
+    DT::Error: Into<RPiError>,
+{
+    move |target: &mut DT, from: &'a T1, to: &'a T2, step: u32| -> RPiResult<()> {
+        let ratio = progress(step, steps);
+        let area = target.bounding_box();
+        let length = direction.length(&area);
+        let distance = ((ratio * length as f32) as u32).min(length);
+
+        let unit = direction.unit();
+        let from_position = if move_from {
+            area.top_left + unit * distance as i32
+        } else {
+            area.top_left
+        };
+        let to_position = if move_to {
+            area.top_left + unit * (distance as i32 - length as i32)
+        } else {
+            area.top_left
+        };
+
+        #[cfg(feature = "debug")]
+        logger::trace!(
+            "Sliding {} step: {}, distance: {}",
+            direction,
+            step,
+            distance
+        );
+
+        let (outgoing, incoming) = direction.split(&area, distance);
+
+        Image::new(from, from_position)
+            .draw(&mut target.clipped(&outgoing))
+            .into_rpi_result()?;
+        Image::new(to, to_position)
+            .draw(&mut target.clipped(&incoming))
+            .into_rpi_result()
+    }
+}
+
+/// Push the outgoing image off the target, as the incoming image slides in behind
+/// it in the same direction.
+pub fn push<'a, COLOUR, T1, T2, DT>(
+    steps: u32,
+    direction: SlideDirection,
+) -> impl DrawTransition<'a, COLOUR, T1, T2, DT>
+where
+    COLOUR: PixelColor + From<<COLOUR as PixelColor>::Raw>,
+    T1: ImageDrawable<Color = COLOUR> + 'a,
+    T2: ImageDrawable<Color = COLOUR> + 'a,
+    DT: DrawTarget<Color = COLOUR> + 'a,
+    DT::Error: Into<RPiError>,
+{
+    slide(steps, direction, true, true)
+}
+
+/// Slide the incoming image in over the outgoing image, which stays in place.
+pub fn cover<'a, COLOUR, T1, T2, DT>(
+    steps: u32,
+    direction: SlideDirection,
+) -> impl DrawTransition<'a, COLOUR, T1, T2, DT>
+where
+    COLOUR: PixelColor + From<<COLOUR as PixelColor>::Raw>,
+    T1: ImageDrawable<Color = COLOUR> + 'a,
+    T2: ImageDrawable<Color = COLOUR> + 'a,
+    DT: DrawTarget<Color = COLOUR> + 'a,
+    DT::Error: Into<RPiError>,
+{
+    slide(steps, direction, false, true)
+}
+
+/// Slide the outgoing image off the target, uncovering the incoming image, which
+/// stays in place.
+pub fn uncover<'a, COLOUR, T1, T2, DT>(
+    steps: u32,
+    direction: SlideDirection,
+) -> impl DrawTransition<'a, COLOUR, T1, T2, DT>
+where
+    COLOUR: PixelColor + From<<COLOUR as PixelColor>::Raw>,
+    T1: ImageDrawable<Color = COLOUR> + 'a,
+    T2: ImageDrawable<Color = COLOUR> + 'a,
+    DT: DrawTarget<Color = COLOUR> + 'a,
+    DT::Error: Into<RPiError>,
+{
+    slide(steps, direction, true, false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::images::RasterImage;
+    use pixelcolor::Rgb888;
+
+    /// An image of distinct pixels numbered from `first`, in a row or a column.
+    fn numbered(first: u8, size: Size) -> RasterImage<Rgb888> {
+        let count = (size.width * size.height) as u8;
+        let pixels: Vec<_> = (first..first + count)
+            .map(|n| Rgb888::new(n, 0, 0))
+            .collect();
+
+        RasterImage::new(size, pixels).unwrap()
+    }
+
+    /// The images to slide between, `1 2 3 4` and `5 6 7 8` of the given size.
+    fn images(size: Size) -> (RasterImage<Rgb888>, RasterImage<Rgb888>) {
+        (numbered(1, size), numbered(5, size))
+    }
+
+    /// Draw a step of a slide between `images`, and get the numbers shown.
+    fn slid<'a, F>(
+        transition: F,
+        (from, to): &'a (RasterImage<Rgb888>, RasterImage<Rgb888>),
+        step: u32,
+    ) -> Vec<u8>
+    where
+        F: DrawTransition<
+            'a,
+            Rgb888,
+            RasterImage<Rgb888>,
+            RasterImage<Rgb888>,
+            RasterImage<Rgb888>,
+        >,
+    {
+        let mut target = RasterImage::filled(from.size(), Rgb888::BLACK);
+
+        transition.draw_frame(&mut target, from, to, step).unwrap();
+
+        target.pixels().iter().map(|pixel| pixel.r()).collect()
+    }
+
+    const ROW: Size = Size::new(4, 1);
+    const COLUMN: Size = Size::new(1, 4);
+
+    #[test]
+    fn push_moves_both_images() {
+        assert_eq!(
+            slid(push(4, SlideDirection::Left), &images(ROW), 0),
+            [1, 2, 3, 4]
+        );
+        assert_eq!(
+            slid(push(4, SlideDirection::Left), &images(ROW), 1),
+            [2, 3, 4, 5]
+        );
+        assert_eq!(
+            slid(push(4, SlideDirection::Right), &images(ROW), 1),
+            [8, 1, 2, 3]
+        );
+        assert_eq!(
+            slid(push(4, SlideDirection::Up), &images(COLUMN), 3),
+            [4, 5, 6, 7]
+        );
+        assert_eq!(
+            slid(push(4, SlideDirection::Down), &images(COLUMN), 4),
+            [5, 6, 7, 8]
+        );
+    }
+
+    #[test]
+    fn cover_keeps_the_outgoing_image_in_place() {
+        assert_eq!(
+            slid(cover(4, SlideDirection::Left), &images(ROW), 1),
+            [1, 2, 3, 5]
+        );
+        assert_eq!(
+            slid(cover(4, SlideDirection::Right), &images(ROW), 2),
+            [7, 8, 3, 4]
+        );
+        assert_eq!(
+            slid(cover(4, SlideDirection::Down), &images(COLUMN), 1),
+            [8, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn uncover_keeps_the_incoming_image_in_place() {
+        assert_eq!(
+            slid(uncover(4, SlideDirection::Left), &images(ROW), 1),
+            [2, 3, 4, 8]
+        );
+        assert_eq!(
+            slid(uncover(4, SlideDirection::Right), &images(ROW), 2),
+            [5, 6, 1, 2]
+        );
+        assert_eq!(
+            slid(uncover(4, SlideDirection::Up), &images(COLUMN), 4),
+            [5, 6, 7, 8]
+        );
+    }
+
+    #[test]
+    fn directions_describe_themselves() {
+        assert_eq!(SlideDirection::Left.to_string(), "to the left");
+        assert_eq!(SlideDirection::Down.to_string(), "downwards");
+    }
+}
@@ -0,0 +1,69 @@
+//! Progress and results of a transition.
+//!
+
+use std::time::Duration;
+
+/// What to draw when a transition is cancelled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CancelBehaviour {
+    /// Draw the last step, as if the transition had finished.
+    #[default]
+    JumpToEnd,
+
+    /// Leave the step drawn last on the target.
+    Stay,
+}
+
+/// The progress of a running transition, reported after each frame drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TransitionProgress {
+    /// The step drawn last.
+    pub step: u32,
+
+    /// The total number of steps, excluding the first.
+    pub steps: u32,
+
+    /// The number of steps skipped so far, because the frames took too long.
+    pub skipped: u32,
+
+    /// The frames drawn per second so far.
+    pub fps: f32,
+}
+
+impl TransitionProgress {
+    /// The fraction of the transition drawn, between `0` and `1`.
+    pub fn fraction(&self) -> f32 {
+        if self.steps == 0 {
+            1.
+        } else {
+            (self.step as f32 / self.steps as f32).min(1.)
+        }
+    }
+}
+
+/// The result of a transition.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransitionStats {
+    /// The number of frames drawn.
+    pub rendered: u32,
+
+    /// The number of steps skipped, because the frames took too long.
+    pub skipped: u32,
+
+    /// The time from the first frame to the end of the transition.
+    pub elapsed: Duration,
+
+    /// Whether the transition was cancelled before its last step.
+    pub cancelled: bool,
+}
+
+impl TransitionStats {
+    /// The frames drawn per second.
+    pub fn fps(&self) -> f32 {
+        if self.elapsed.is_zero() {
+            0.
+        } else {
+            self.rendered as f32 / self.elapsed.as_secs_f32()
+        }
+    }
+}
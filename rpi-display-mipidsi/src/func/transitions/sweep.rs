@@ -35,10 +35,10 @@ where
     COLOUR: PixelColor + From<<COLOUR as PixelColor>::Raw>,
     T: ImageDrawable<Color = COLOUR> + 'a,
     DT: DrawTarget<Color = COLOUR> + 'a,
-    DT::Error: Into<RPiError<'a>>,
+    DT::Error: Into<RPiError>,
 {
     // The second image is not used.
-    move |target: &mut DT, from: &'a T, _: &'a T, step: u32| -> RPiResult<'a, ()> {
+    move |target: &mut DT, from: &'a T, _: &'a T, step: u32| -> RPiResult<()> {
         let ratio = ((step + 1) as f32 / steps as f32).min(1.);
         let size = target.bounding_box().size;
 
@@ -71,13 +71,13 @@ where
         };
 
         #[cfg(feature = "debug")]
-        logger::trace(&format!(
+        logger::trace!(
             "Sweeping {} step: {}, offset: {:?}, size: {:?}",
             direction.direction(),
             step,
             offset,
             size
-        ));
+        );
 
         func::crop::draw_cropped_in_place(
             target,
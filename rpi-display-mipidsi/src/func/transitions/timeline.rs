@@ -0,0 +1,678 @@
+//! Sequence, compose, reverse and loop transitions on a timeline.
+//!
+
+use std::{marker::PhantomData, time::Duration};
+
+use embedded_graphics::primitives::Rectangle;
+
+use super::FrameClock;
+use crate::traits::{DrawTransition, Playable};
+use crate::{foreign_types::*, images::RasterImage};
+
+/// A transition between two images, which can be played on a [`Timeline`].
+pub struct Clip<'a, COLOUR, T1, T2, F>
+where
+    COLOUR: PixelColor,
+    T1: ImageDrawable<Color = COLOUR> + 'a,
+    T2: ImageDrawable<Color = COLOUR> + 'a,
+{
+    from: &'a T1,
+    to: &'a T2,
+    transition: F,
+    steps: u32,
+    clock: FrameClock,
+
+    /// The last step drawn, so that the same frame is not drawn twice.
+    last_step: Option<u32>,
+
+    _phantom: PhantomData<COLOUR>,
+}
+
+impl<'a, COLOUR, T1, T2, F> Clip<'a, COLOUR, T1, T2, F>
+where
+    COLOUR: PixelColor,
+    T1: ImageDrawable<Color = COLOUR> + 'a,
+    T2: ImageDrawable<Color = COLOUR> + 'a,
+{
+    /// Create a new clip of a transition, with the given steps and duration.
+    pub fn new(from: &'a T1, to: &'a T2, transition: F, steps: u32, duration: Duration) -> Self {
+        Self {
+            from,
+            to,
+            transition,
+            steps,
+            clock: FrameClock::with_steps(steps, duration),
+            last_step: None,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<'a, COLOUR, T1, T2, F, DT> Playable<'a, DT> for Clip<'a, COLOUR, T1, T2, F>
+where
+    COLOUR: PixelColor + From<<COLOUR as PixelColor>::Raw>,
+    T1: ImageDrawable<Color = COLOUR> + 'a,
+    T2: ImageDrawable<Color = COLOUR> + 'a,
+    F: DrawTransition<'a, COLOUR, T1, T2, DT>,
+    DT: DrawTarget<Color = COLOUR>,
+{
+    fn step_duration(&self) -> Duration {
+        self.clock.step_duration()
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        Some(self.clock.offset(self.steps))
+    }
+
+    fn draw_at(&mut self, target: &mut DT, elapsed: Duration) -> RPiResult<bool> {
+        let step = self.clock.step_at(elapsed).min(self.steps);
+        if self.last_step == Some(step) {
+            return Ok(false);
+        }
+
+        self.transition
+            .draw_frame(target, self.from, self.to, step)?;
+        self.last_step = Some(step);
+
+        Ok(true)
+    }
+}
+
+/// A [`Playable`] played backwards.
+///
+/// Something that plays forever is played forwards instead.
+pub struct Reversed<P>(P);
+
+impl<P> Reversed<P> {
+    /// Play `playable` backwards.
+    pub fn new(playable: P) -> Self {
+        Self(playable)
+    }
+}
+
+impl<'a, P, DT> Playable<'a, DT> for Reversed<P>
+where
+    P: Playable<'a, DT>,
+{
+    fn step_duration(&self) -> Duration {
+        self.0.step_duration()
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        self.0.duration()
+    }
+
+    fn draw_at(&mut self, target: &mut DT, elapsed: Duration) -> RPiResult<bool> {
+        let elapsed = match self.0.duration() {
+            Some(duration) => duration.saturating_sub(elapsed),
+            None => elapsed,
+        };
+
+        self.0.draw_at(target, elapsed)
+    }
+}
+
+/// A [`Playable`] played forwards, then backwards.
+///
+/// Something that plays forever is only played forwards.
+pub struct PingPong<P>(P);
+
+impl<P> PingPong<P> {
+    /// Play `playable` forwards, then backwards.
+    pub fn new(playable: P) -> Self {
+        Self(playable)
+    }
+}
+
+impl<'a, P, DT> Playable<'a, DT> for PingPong<P>
+where
+    P: Playable<'a, DT>,
+{
+    fn step_duration(&self) -> Duration {
+        self.0.step_duration()
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        self.0.duration().map(|duration| duration * 2)
+    }
+
+    fn draw_at(&mut self, target: &mut DT, elapsed: Duration) -> RPiResult<bool> {
+        let elapsed = match self.0.duration() {
+            Some(duration) if elapsed > duration => (duration * 2).saturating_sub(elapsed),
+            _ => elapsed,
+        };
+
+        self.0.draw_at(target, elapsed)
+    }
+}
+
+/// The number of times to play a [`Repeated`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Repeat {
+    /// Play the given number of times; `Times(0)` plays nothing, so draws nothing.
+    Times(u32),
+    Forever,
+}
+
+/// A [`Playable`] played repeatedly.
+pub struct Repeated<P> {
+    playable: P,
+    repeat: Repeat,
+}
+
+impl<P> Repeated<P> {
+    /// Play `playable` repeatedly.
+    pub fn new(playable: P, repeat: Repeat) -> Self {
+        Self { playable, repeat }
+    }
+}
+
+impl<'a, P, DT> Playable<'a, DT> for Repeated<P>
+where
+    P: Playable<'a, DT>,
+{
+    fn step_duration(&self) -> Duration {
+        self.playable.step_duration()
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        match self.repeat {
+            Repeat::Times(count) => self
+                .playable
+                .duration()
+                .map(|duration| duration.checked_mul(count).unwrap_or(Duration::MAX)),
+            Repeat::Forever => None,
+        }
+    }
+
+    fn draw_at(&mut self, target: &mut DT, elapsed: Duration) -> RPiResult<bool> {
+        if self.repeat == Repeat::Times(0) {
+            return Ok(false);
+        }
+
+        let elapsed = match (self.playable.duration(), self.duration()) {
+            (Some(duration), _) if duration.is_zero() => elapsed,
+            (Some(duration), Some(total)) if elapsed >= total => duration,
+            (Some(duration), _) => {
+                Duration::from_nanos((elapsed.as_nanos() % duration.as_nanos()) as u64)
+            }
+            (None, _) => elapsed,
+        };
+
+        self.playable.draw_at(target, elapsed)
+    }
+}
+
+/// [`Playable`]s drawn at the same time onto disjoint areas of the target.
+///
+/// Each track is drawn into its own framebuffer the size of its area, which is then
+/// drawn onto the target whenever it has changed; the transitions of a track
+/// therefore treat the top left corner of the area as the origin.
+pub struct Parallel<'a, COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    tracks: Vec<Track<'a, COLOUR>>,
+}
+
+/// A track of a [`Parallel`], and the framebuffer it is drawn into.
+struct Track<'a, COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    area: Rectangle,
+    playable: Box<dyn Playable<'a, RasterImage<COLOUR>> + 'a>,
+    framebuffer: RasterImage<COLOUR>,
+}
+
+impl<'a, COLOUR> Parallel<'a, COLOUR>
+where
+    COLOUR: PixelColor + Default,
+{
+    /// Create an empty composition.
+    pub fn new() -> Self {
+        Self { tracks: Vec::new() }
+    }
+
+    /// Add a track playing in the given area of the target.
+    pub fn with_track(
+        mut self,
+        area: Rectangle,
+        playable: impl Playable<'a, RasterImage<COLOUR>> + 'a,
+    ) -> Self {
+        self.tracks.push(Track {
+            area,
+            playable: Box::new(playable),
+            framebuffer: RasterImage::filled(area.size, COLOUR::default()),
+        });
+        self
+    }
+}
+
+impl<'a, COLOUR> Default for Parallel<'a, COLOUR>
+where
+    COLOUR: PixelColor + Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, COLOUR, DT> Playable<'a, DT> for Parallel<'a, COLOUR>
+where
+    COLOUR: PixelColor,
+    DT: DrawTarget<Color = COLOUR>,
+    DT::Error: Into<RPiError>,
+{
+    /// The shortest interval between frames of all the tracks, ignoring tracks that
+    /// take no time.
+    fn step_duration(&self) -> Duration {
+        self.tracks
+            .iter()
+            .map(|track| track.playable.step_duration())
+            .filter(|step_duration| !step_duration.is_zero())
+            .min()
+            .unwrap_or_default()
+    }
+
+    /// The duration of the longest track.
+    fn duration(&self) -> Option<Duration> {
+        self.tracks
+            .iter()
+            .map(|track| track.playable.duration())
+            .try_fold(Duration::ZERO, |longest, duration| {
+                duration.map(|duration| longest.max(duration))
+            })
+    }
+
+    fn draw_at(&mut self, target: &mut DT, elapsed: Duration) -> RPiResult<bool> {
+        let mut drawn = false;
+
+        for track in self.tracks.iter_mut() {
+            if track.playable.draw_at(&mut track.framebuffer, elapsed)? {
+                target
+                    .fill_contiguous(&track.area, track.framebuffer.pixels().iter().copied())
+                    .into_rpi_result()?;
+                drawn = true;
+            }
+        }
+
+        Ok(drawn)
+    }
+}
+
+/// [`Playable`]s played one after another.
+///
+/// A timeline is itself [`Playable`], so it can be nested, reversed or repeated;
+/// use [`Timeline::play`] to draw it onto a target in real time.
+pub struct Timeline<'a, DT> {
+    items: Vec<Box<dyn Playable<'a, DT> + 'a>>,
+
+    /// The item drawn last, and its start time on the timeline.
+    current: usize,
+    current_start: Duration,
+}
+
+impl<'a, DT> Timeline<'a, DT> {
+    /// Create an empty timeline.
+    pub fn new() -> Self {
+        Self {
+            items: Vec::new(),
+            current: 0,
+            current_start: Duration::ZERO,
+        }
+    }
+
+    /// Add an item to the end of the timeline.
+    ///
+    /// Anything after an item that plays forever is never played.
+    pub fn then(mut self, playable: impl Playable<'a, DT> + 'a) -> Self {
+        self.items.push(Box::new(playable));
+        self
+    }
+
+    /// Draw the whole timeline onto the target in real time, skipping frames if it
+    /// cannot keep up. On failure, return a [`RPiError`] in the [`RPiResult`].
+    ///
+    /// A timeline that plays forever never returns unless it fails.
+    pub async fn play(&mut self, target: &mut DT) -> RPiResult<()> {
+        let clock = FrameClock::new(self.step_duration());
+        let duration = self.duration();
+
+        loop {
+            let step = clock.current_step();
+            let elapsed = clock.offset(step);
+
+            match duration {
+                Some(duration) if elapsed >= duration => {
+                    self.draw_at(target, duration)?;
+                    break;
+                }
+                _ => self.draw_at(target, elapsed)?,
+            };
+
+            tokio::time::sleep_until(clock.deadline(step + 1)).await;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, DT> Default for Timeline<'a, DT> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, DT> Playable<'a, DT> for Timeline<'a, DT> {
+    /// The shortest interval between frames of all the items, ignoring items that
+    /// take no time; otherwise a zero-length [`Clip`] would make every step due at
+    /// once, and [`Timeline::play`] would never move on.
+    fn step_duration(&self) -> Duration {
+        self.items
+            .iter()
+            .map(|item| item.step_duration())
+            .filter(|step_duration| !step_duration.is_zero())
+            .min()
+            .unwrap_or_default()
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        self.items.iter().try_fold(Duration::ZERO, |total, item| {
+            item.duration()
+                .map(|duration| total.saturating_add(duration))
+        })
+    }
+
+    /// Draw the item playing at `elapsed`.
+    ///
+    /// Every item between the one drawn last and this one is drawn at its end if
+    /// playing forwards, or at its start if playing backwards, so that skipped
+    /// frames never leave an item half drawn.
+    fn draw_at(&mut self, target: &mut DT, elapsed: Duration) -> RPiResult<bool> {
+        if self.items.is_empty() {
+            return Ok(false);
+        }
+
+        let mut drawn = false;
+
+        // Move forwards through the items that have finished.
+        while self.current + 1 < self.items.len() {
+            let Some(duration) = self.items[self.current].duration() else {
+                break;
+            };
+            let end = self.current_start.saturating_add(duration);
+            if elapsed < end {
+                break;
+            }
+
+            drawn |= self.items[self.current].draw_at(target, duration)?;
+            self.current += 1;
+            self.current_start = end;
+        }
+
+        // Move backwards through the items that have not started yet.
+        while self.current > 0 && elapsed < self.current_start {
+            drawn |= self.items[self.current].draw_at(target, Duration::ZERO)?;
+            self.current -= 1;
+            self.current_start = self.current_start.saturating_sub(
+                self.items[self.current]
+                    .duration()
+                    .unwrap_or(Duration::ZERO),
+            );
+        }
+
+        drawn |=
+            self.items[self.current].draw_at(target, elapsed.saturating_sub(self.current_start))?;
+
+        Ok(drawn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pixelcolor::BinaryColor;
+    use std::cell::RefCell;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    /// The item and elapsed time of every frame drawn.
+    type Frames = Vec<(usize, Duration)>;
+
+    /// An item recording every frame drawn onto a [`Frames`].
+    struct Probe {
+        id: usize,
+        duration: Option<Duration>,
+    }
+
+    impl Probe {
+        fn new(id: usize, millis: u64) -> Self {
+            Self {
+                id,
+                duration: Some(MS * millis as u32),
+            }
+        }
+
+        fn forever(id: usize) -> Self {
+            Self { id, duration: None }
+        }
+    }
+
+    impl<'a> Playable<'a, Frames> for Probe {
+        fn step_duration(&self) -> Duration {
+            MS * 10 * (self.id as u32 + 1)
+        }
+
+        fn duration(&self) -> Option<Duration> {
+            self.duration
+        }
+
+        fn draw_at(&mut self, target: &mut Frames, elapsed: Duration) -> RPiResult<bool> {
+            target.push((self.id, elapsed));
+            Ok(true)
+        }
+    }
+
+    /// Draw `playable` at `millis`, and get the frames drawn.
+    fn frames<'a>(playable: &mut impl Playable<'a, Frames>, millis: u64) -> Frames {
+        let mut frames = Frames::new();
+        playable
+            .draw_at(&mut frames, MS * millis as u32)
+            .expect("Failed to draw.");
+        frames
+    }
+
+    #[test]
+    fn clip_draws_each_step_once() {
+        let image = RasterImage::filled(Size::new(1, 1), BinaryColor::Off);
+        let steps = RefCell::new(Vec::new());
+        let record = |_: &mut RasterImage<BinaryColor>,
+                      _: &RasterImage<BinaryColor>,
+                      _: &RasterImage<BinaryColor>,
+                      step: u32| {
+            steps.borrow_mut().push(step);
+            Ok(())
+        };
+
+        let mut clip = Clip::new(&image, &image, record, 4, MS * 40);
+        let mut target = image.clone();
+        assert_eq!(
+            Playable::<RasterImage<BinaryColor>>::duration(&clip),
+            Some(MS * 40)
+        );
+
+        for (millis, drawn) in [
+            (0, true),
+            (5, false),
+            (25, true),
+            (1000, true),
+            (2000, false),
+        ] {
+            assert_eq!(clip.draw_at(&mut target, MS * millis).unwrap(), drawn);
+        }
+        assert_eq!(*steps.borrow(), [0, 2, 4]);
+    }
+
+    #[test]
+    fn reversed_plays_from_the_end() {
+        let mut reversed = Probe::new(0, 100).reversed();
+        assert_eq!(frames(&mut reversed, 30), [(0, MS * 70)]);
+        assert_eq!(frames(&mut reversed, 150), [(0, Duration::ZERO)]);
+
+        let mut forever = Probe::forever(0).reversed();
+        assert_eq!(frames(&mut forever, 30), [(0, MS * 30)]);
+    }
+
+    #[test]
+    fn ping_pong_plays_forwards_then_backwards() {
+        let mut ping_pong = Probe::new(0, 100).ping_pong();
+        assert_eq!(Playable::<Frames>::duration(&ping_pong), Some(MS * 200));
+
+        assert_eq!(frames(&mut ping_pong, 30), [(0, MS * 30)]);
+        assert_eq!(frames(&mut ping_pong, 100), [(0, MS * 100)]);
+        assert_eq!(frames(&mut ping_pong, 130), [(0, MS * 70)]);
+        assert_eq!(frames(&mut ping_pong, 300), [(0, Duration::ZERO)]);
+    }
+
+    #[test]
+    fn repeated_loops_and_ends_on_the_last_frame() {
+        let mut repeated = Probe::new(0, 100).repeat(Repeat::Times(3));
+        assert_eq!(Playable::<Frames>::duration(&repeated), Some(MS * 300));
+
+        assert_eq!(frames(&mut repeated, 150), [(0, MS * 50)]);
+        assert_eq!(frames(&mut repeated, 200), [(0, Duration::ZERO)]);
+        assert_eq!(frames(&mut repeated, 300), [(0, MS * 100)]);
+        assert_eq!(frames(&mut repeated, 1000), [(0, MS * 100)]);
+
+        let mut forever = Probe::new(0, 100).repeat(Repeat::Forever);
+        assert_eq!(Playable::<Frames>::duration(&forever), None);
+        assert_eq!(frames(&mut forever, 1250), [(0, MS * 50)]);
+    }
+
+    #[test]
+    fn repeated_no_times_draws_nothing() {
+        let mut repeated = Probe::new(0, 100).repeat(Repeat::Times(0));
+        assert_eq!(
+            Playable::<Frames>::duration(&repeated),
+            Some(Duration::ZERO)
+        );
+
+        let mut target = Frames::new();
+        assert!(!repeated.draw_at(&mut target, Duration::ZERO).unwrap());
+        assert!(!repeated.draw_at(&mut target, MS * 100).unwrap());
+        assert!(target.is_empty());
+    }
+
+    #[test]
+    fn parallel_draws_changed_tracks_into_their_areas() {
+        let image = RasterImage::filled(Size::new(2, 1), BinaryColor::Off);
+        let fill = |target: &mut RasterImage<BinaryColor>,
+                    _: &RasterImage<BinaryColor>,
+                    _: &RasterImage<BinaryColor>,
+                    step: u32| {
+            target.clear(BinaryColor::from(step >= 2)).into_rpi_result()
+        };
+
+        let area = |x| Rectangle::new(Point::new(x, 0), Size::new(2, 1));
+        let mut parallel = Parallel::new()
+            .with_track(area(0), Clip::new(&image, &image, fill, 2, MS * 20))
+            .with_track(area(2), Clip::new(&image, &image, fill, 4, MS * 80));
+        let mut target = RasterImage::filled(Size::new(4, 1), BinaryColor::On);
+
+        assert_eq!(
+            Playable::<RasterImage<BinaryColor>>::step_duration(&parallel),
+            MS * 10
+        );
+        assert_eq!(
+            Playable::<RasterImage<BinaryColor>>::duration(&parallel),
+            Some(MS * 80)
+        );
+
+        assert!(parallel.draw_at(&mut target, Duration::ZERO).unwrap());
+        assert_eq!(target.pixels(), [BinaryColor::Off; 4]);
+
+        assert!(parallel.draw_at(&mut target, MS * 20).unwrap());
+        assert_eq!(
+            target.pixels(),
+            [
+                BinaryColor::On,
+                BinaryColor::On,
+                BinaryColor::Off,
+                BinaryColor::Off
+            ]
+        );
+
+        // Neither track has changed, so nothing is drawn.
+        target.clear(BinaryColor::Off).into_rpi_result().unwrap();
+        assert!(!parallel.draw_at(&mut target, MS * 25).unwrap());
+        assert_eq!(target.pixels(), [BinaryColor::Off; 4]);
+    }
+
+    #[test]
+    fn timeline_draws_skipped_items_at_their_ends() {
+        let mut timeline = Timeline::new()
+            .then(Probe::new(0, 100))
+            .then(Probe::new(1, 50))
+            .then(Probe::new(2, 100));
+        assert_eq!(Playable::<Frames>::step_duration(&timeline), MS * 10);
+        assert_eq!(Playable::<Frames>::duration(&timeline), Some(MS * 250));
+
+        assert_eq!(frames(&mut timeline, 20), [(0, MS * 20)]);
+
+        // Forwards, the items skipped over are drawn at their end.
+        assert_eq!(
+            frames(&mut timeline, 170),
+            [(0, MS * 100), (1, MS * 50), (2, MS * 20)]
+        );
+        assert_eq!(frames(&mut timeline, 1000), [(2, MS * 850)]);
+
+        // Backwards, the items skipped over are drawn at their start.
+        assert_eq!(
+            frames(&mut timeline, 30),
+            [(2, Duration::ZERO), (1, Duration::ZERO), (0, MS * 30)]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn timeline_plays_past_zero_length_clips() {
+        let image = RasterImage::filled(Size::new(1, 1), BinaryColor::Off);
+        let steps = RefCell::new(Vec::new());
+        let record = |_: &mut RasterImage<BinaryColor>,
+                      _: &RasterImage<BinaryColor>,
+                      _: &RasterImage<BinaryColor>,
+                      step: u32| {
+            steps.borrow_mut().push(step);
+            Ok(())
+        };
+
+        let mut timeline = Timeline::new()
+            .then(Clip::new(&image, &image, record, 4, Duration::ZERO))
+            .then(Clip::new(&image, &image, record, 4, MS * 40));
+        let mut target = image.clone();
+        assert_eq!(
+            Playable::<RasterImage<BinaryColor>>::step_duration(&timeline),
+            MS * 10
+        );
+
+        let start = tokio::time::Instant::now();
+        timeline.play(&mut target).await.unwrap();
+
+        assert_eq!(start.elapsed(), MS * 40);
+        assert_eq!(*steps.borrow(), [4, 0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn timeline_stops_at_items_playing_forever() {
+        let mut timeline = Timeline::new()
+            .then(Probe::new(0, 100))
+            .then(Probe::forever(1))
+            .then(Probe::new(2, 100));
+        assert_eq!(Playable::<Frames>::duration(&timeline), None);
+
+        assert_eq!(frames(&mut timeline, 5000), [(0, MS * 100), (1, MS * 4900)]);
+
+        let mut empty = Timeline::<Frames>::new();
+        assert!(frames(&mut empty, 10).is_empty());
+    }
+}
@@ -18,13 +18,13 @@ where
     COLOUR: PixelColor + From<<COLOUR as PixelColor>::Raw>,
     T: ImageDrawable<Color = COLOUR> + 'a,
     DT: DrawTarget<Color = COLOUR> + 'a,
-    DT::Error: Into<RPiError<'a>>,
+    DT::Error: Into<RPiError>,
 {
     let delta_x = end_x as i32 - start_x as i32;
     let delta_y = end_y as i32 - start_y as i32;
 
     // The second image is not used.
-    move |target: &mut DT, from: &'a T, _: &'a T, step: u32| -> RPiResult<'a, ()> {
+    move |target: &mut DT, from: &'a T, _: &'a T, step: u32| -> RPiResult<()> {
         let ratio = step as f32 / steps as f32;
         let (dx, dy) = (
             (ratio * delta_x as f32) as i32 + start_x as i32,
@@ -32,10 +32,7 @@ where
         );
 
         #[cfg(feature = "debug")]
-        logger::trace(&format!(
-            "Transversing step: {}, dx: {}, dy: {}",
-            step, dx, dy
-        ));
+        logger::trace!("Transversing step: {}, dx: {}, dy: {}", step, dx, dy);
 
         let size = from.size();
 
@@ -0,0 +1,419 @@
+//! Wipe the incoming image over the outgoing image through a shaped mask.
+//!
+
+use std::{cell::Cell, f32::consts::TAU, fmt, sync::OnceLock};
+
+use embedded_graphics::primitives::{PointsIter, Rectangle};
+
+use super::{progress, rasterise};
+use crate::traits::DrawTransition;
+use crate::{foreign_types::*, images::RasterImage};
+
+/// A mask for a wipe, which decides when each pixel switches to the incoming
+/// image.
+///
+/// Any `Fn(Point, Size) -> f32` can be used as a mask.
+pub trait WipeMask {
+    /// Get the progress of the transition, between `0` and `1`, at which the pixel
+    /// at `point` switches to the incoming image; `point` is relative to the top
+    /// left corner of a target of the given `size`.
+    fn threshold(&self, point: Point, size: Size) -> f32;
+}
+
+impl<F> WipeMask for F
+where
+    F: Fn(Point, Size) -> f32,
+{
+    fn threshold(&self, point: Point, size: Size) -> f32 {
+        self(point, size)
+    }
+}
+
+/// The corner from which a diagonal wipe starts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// The orientation of the slats of venetian blinds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlindsOrientation {
+    /// Horizontal slats, each opening from top to bottom.
+    Horizontal,
+
+    /// Vertical slats, each opening from left to right.
+    Vertical,
+}
+
+/// The built-in shapes of a wipe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WipeShape {
+    /// A circle growing from the centre.
+    IrisOut,
+
+    /// A circle closing in from the corners towards the centre.
+    IrisIn,
+
+    /// A straight edge at 45 degrees, moving away from the corner.
+    Diagonal(Corner),
+
+    /// The given number of slats, all opening at the same time.
+    Blinds(BlindsOrientation, u32),
+
+    /// Squares of the given size, with one colour of the board wiped from left to
+    /// right before the other.
+    Checkerboard(u32),
+
+    /// A hand sweeping clockwise from 12 o'clock.
+    Clock,
+}
+
+impl fmt::Display for WipeShape {
+    /// Name the shape, such as `iris out`, in the same way as
+    /// [`SlideDirection`](super::SlideDirection) describes itself.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::IrisOut => "iris out",
+            Self::IrisIn => "iris in",
+            Self::Diagonal(_) => "diagonal",
+            Self::Blinds(..) => "blinds",
+            Self::Checkerboard(_) => "checkerboard",
+            Self::Clock => "clock",
+        })
+    }
+}
+
+impl WipeMask for WipeShape {
+    fn threshold(&self, point: Point, size: Size) -> f32 {
+        let (width, height) = (size.width.max(1) as f32, size.height.max(1) as f32);
+
+        // Measure from the centres of the pixels, so that the shapes are symmetric.
+        let (x, y) = (point.x as f32 + 0.5, point.y as f32 + 0.5);
+        let (dx, dy) = (x - width / 2., y - height / 2.);
+
+        match *self {
+            Self::IrisOut => dx.hypot(dy) / (width / 2.).hypot(height / 2.),
+            Self::IrisIn => 1. - dx.hypot(dy) / (width / 2.).hypot(height / 2.),
+            Self::Diagonal(corner) => {
+                let (x, y) = match corner {
+                    Corner::TopLeft => (x, y),
+                    Corner::TopRight => (width - x, y),
+                    Corner::BottomLeft => (x, height - y),
+                    Corner::BottomRight => (width - x, height - y),
+                };
+
+                (x + y - 1.) / (width + height - 2.).max(1.)
+            }
+            Self::Blinds(orientation, count) => {
+                let (position, length) = match orientation {
+                    BlindsOrientation::Horizontal => (point.y, size.height),
+                    BlindsOrientation::Vertical => (point.x, size.width),
+                };
+                let slat = length.div_ceil(count.max(1)).max(1);
+
+                (position as u32 % slat) as f32 / slat as f32
+            }
+            Self::Checkerboard(cell) => {
+                let cell = cell.max(1);
+                let (column, row) = (point.x as u32 / cell, point.y as u32 / cell);
+                let parity = ((column + row) % 2) as f32;
+
+                (parity + (point.x as u32 % cell) as f32 / cell as f32) / 2.
+            }
+            Self::Clock => {
+                let angle = dx.atan2(-dy);
+                (if angle < 0. { angle + TAU } else { angle }) / TAU
+            }
+        }
+        .clamp(0., 1.)
+    }
+}
+
+/// Whether a pixel with the given threshold shows the incoming image at the given
+/// progress; at the end of the transition, every pixel does.
+fn is_switched(threshold: f32, ratio: f32) -> bool {
+    threshold < ratio || ratio >= 1.
+}
+
+/// Wipe the incoming image over the outgoing image through the given mask.
+///
+/// The outgoing image is drawn in full on the first frame; after that, only the
+/// pixels switched since the last frame are drawn, one contiguous run per row, so
+/// that each run only addresses the display once. Steps can also go backwards, such
+/// as on a reversed [`Timeline`](super::Timeline), in which case the pixels are
+/// switched back to the outgoing image.
+///
+/// Both images and the mask are rasterised at the size of the target on the first
+/// frame, and kept for the rest of the transition; the returned transition should
+/// therefore only be used for a single pair of images.
+pub fn wipe<'a, COLOUR, T1, T2, DT, M>(
+    steps: u32,
+    mask: M,
+) -> impl DrawTransition<'a, COLOUR, T1, T2, DT>
+where
+    COLOUR: PixelColor + From<<COLOUR as PixelColor>::Raw> + Default,
+    T1: ImageDrawable<Color = COLOUR> + 'a,
+    T2: ImageDrawable<Color = COLOUR> + 'a,
+    DT: DrawTarget<Color = COLOUR> + 'a,
+    DT::Error: Into<RPiError>,
+    M: WipeMask + 'a,
+{
+    let rasters = OnceLock::<(RasterImage<COLOUR>, RasterImage<COLOUR>, Vec<f32>)>::new();
+
+    // The progress of the last frame drawn, so that skipped steps are still drawn.
+    let last_ratio = Cell::new(None::<f32>);
+
+    move |target: &mut DT, from: &'a T1, to: &'a T2, step: u32| -> RPiResult<()> {
+        let area = target.bounding_box();
+        let (from, to, thresholds) = rasters.get_or_init(|| {
+            let thresholds = Rectangle::new(Point::zero(), area.size)
+                .points()
+                .map(|point| mask.threshold(point, area.size))
+                .collect();
+
+            (
+                rasterise(from, area.size),
+                rasterise(to, area.size),
+                thresholds,
+            )
+        });
+
+        let ratio = progress(step, steps);
+        let last = match last_ratio.get() {
+            Some(last) => last,
+            None => {
+                target
+                    .fill_contiguous(&area, from.pixels().iter().copied())
+                    .into_rpi_result()?;
+
+                0.
+            }
+        };
+
+        #[cfg(feature = "debug")]
+        logger::trace!(
+            "Wiping step: {}, ratio: {}, last ratio: {}",
+            step,
+            ratio,
+            last
+        );
+
+        // Pixels with a threshold between the two ratios have switched, towards the
+        // incoming image if going forwards, or the outgoing image if backwards.
+        let (lower, upper, source) = if ratio >= last {
+            (last, ratio, to)
+        } else {
+            (ratio, last, from)
+        };
+        let is_due =
+            |threshold: f32| is_switched(threshold, upper) && !is_switched(threshold, lower);
+
+        let width = area.size.width as usize;
+        for (y, (pixels, thresholds)) in source
+            .pixels()
+            .chunks_exact(width.max(1))
+            .zip(thresholds.chunks_exact(width.max(1)))
+            .enumerate()
+        {
+            let mut x = 0;
+            while x < width {
+                if !is_due(thresholds[x]) {
+                    x += 1;
+                    continue;
+                }
+
+                let start = x;
+                while x < width && is_due(thresholds[x]) {
+                    x += 1;
+                }
+
+                let run = Rectangle::new(
+                    area.top_left + Point::new(start as i32, y as i32),
+                    Size::new((x - start) as u32, 1),
+                );
+                target
+                    .fill_contiguous(&run, pixels[start..x].iter().copied())
+                    .into_rpi_result()?;
+            }
+        }
+
+        last_ratio.set(Some(ratio));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use embedded_graphics::Pixel;
+    use pixelcolor::BinaryColor;
+    use std::convert::Infallible;
+
+    const SIZE: Size = Size::new(4, 4);
+
+    /// Get the thresholds of `shape` for a row of points.
+    fn thresholds(shape: WipeShape, points: &[(i32, i32)]) -> Vec<f32> {
+        points
+            .iter()
+            .map(|(x, y)| shape.threshold(Point::new(*x, *y), SIZE))
+            .collect()
+    }
+
+    #[test]
+    fn iris_grows_from_the_centre() {
+        let points = [(1, 1), (0, 1), (0, 0)];
+
+        let iris_out = thresholds(WipeShape::IrisOut, &points);
+        assert!(iris_out.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(iris_out[2], 0.75);
+
+        let iris_in = thresholds(WipeShape::IrisIn, &points);
+        for (iris_out, iris_in) in iris_out.iter().zip(iris_in) {
+            assert_eq!(iris_out + iris_in, 1.);
+        }
+    }
+
+    #[test]
+    fn diagonal_starts_at_its_corner() {
+        let points = [(0, 0), (3, 0), (3, 3)];
+
+        assert_eq!(
+            thresholds(WipeShape::Diagonal(Corner::TopLeft), &points),
+            [0., 0.5, 1.]
+        );
+        assert_eq!(
+            thresholds(WipeShape::Diagonal(Corner::BottomRight), &points),
+            [1., 0.5, 0.]
+        );
+        assert_eq!(
+            thresholds(WipeShape::Diagonal(Corner::TopRight), &points),
+            [0.5, 0., 0.5]
+        );
+    }
+
+    #[test]
+    fn blinds_and_checkerboard_repeat() {
+        assert_eq!(
+            thresholds(
+                WipeShape::Blinds(BlindsOrientation::Horizontal, 2),
+                &[(0, 0), (3, 1), (0, 2), (3, 3)]
+            ),
+            [0., 0.5, 0., 0.5]
+        );
+        assert_eq!(
+            thresholds(
+                WipeShape::Blinds(BlindsOrientation::Vertical, 4),
+                &[(0, 3), (1, 0), (3, 3)]
+            ),
+            [0., 0., 0.]
+        );
+
+        // One colour of the board is wiped from left to right, then the other.
+        assert_eq!(
+            thresholds(
+                WipeShape::Checkerboard(2),
+                &[(0, 0), (1, 0), (2, 0), (3, 0), (0, 2), (2, 2)]
+            ),
+            [0., 0.25, 0.5, 0.75, 0.5, 0.]
+        );
+    }
+
+    #[test]
+    fn clock_sweeps_clockwise_from_12() {
+        // Above, right of, below and left of the centre.
+        let sweep = thresholds(WipeShape::Clock, &[(2, 0), (3, 2), (1, 3), (0, 1)]);
+
+        assert!(sweep.windows(2).all(|pair| pair[0] < pair[1]), "{sweep:?}");
+        assert!(sweep[0] < 0.25 && sweep[3] > 0.75, "{sweep:?}");
+    }
+
+    /// A target recording the area of every contiguous fill.
+    struct Recorder {
+        image: RasterImage<BinaryColor>,
+        fills: Vec<Rectangle>,
+    }
+
+    impl Dimensions for Recorder {
+        fn bounding_box(&self) -> Rectangle {
+            self.image.bounding_box()
+        }
+    }
+
+    impl DrawTarget for Recorder {
+        type Color = BinaryColor;
+        type Error = Infallible;
+
+        fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Pixel<Self::Color>>,
+        {
+            self.image.draw_iter(pixels)
+        }
+
+        fn fill_contiguous<I>(&mut self, area: &Rectangle, colors: I) -> Result<(), Self::Error>
+        where
+            I: IntoIterator<Item = Self::Color>,
+        {
+            self.fills.push(*area);
+            self.image.fill_contiguous(area, colors)
+        }
+    }
+
+    /// A run of `width` pixels from `(x, y)`.
+    fn run(x: i32, y: i32, width: u32) -> Rectangle {
+        Rectangle::new(Point::new(x, y), Size::new(width, 1))
+    }
+
+    #[test]
+    fn only_switched_runs_are_redrawn() {
+        let size = Size::new(4, 2);
+        let from = RasterImage::filled(size, BinaryColor::Off);
+        let to = RasterImage::filled(size, BinaryColor::On);
+        let mut target = Recorder {
+            image: RasterImage::filled(size, BinaryColor::On),
+            fills: Vec::new(),
+        };
+
+        // From left to right, a column every step.
+        let transition = wipe(4, WipeShape::Blinds(BlindsOrientation::Vertical, 1));
+        let mut draw = |step| {
+            target.fills.clear();
+            transition
+                .draw_frame(&mut target, &from, &to, step)
+                .unwrap();
+
+            let switched = target
+                .image
+                .pixels()
+                .iter()
+                .map(|pixel| pixel.is_on())
+                .collect::<Vec<_>>();
+            (std::mem::take(&mut target.fills), switched)
+        };
+
+        // The first frame draws the outgoing image in full.
+        let (fills, switched) = draw(0);
+        assert_eq!(fills, [Rectangle::new(Point::zero(), size)]);
+        assert!(switched.iter().all(|on| !on));
+
+        let (fills, _) = draw(1);
+        assert_eq!(fills, [run(0, 0, 1), run(0, 1, 1)]);
+
+        // Skipping a step still draws every pixel switched since the last frame.
+        let (fills, switched) = draw(3);
+        assert_eq!(fills, [run(1, 0, 2), run(1, 1, 2)]);
+        assert_eq!(switched[..4], [true, true, true, false]);
+
+        // Going backwards switches the pixels back to the outgoing image.
+        let (fills, switched) = draw(1);
+        assert_eq!(fills, [run(1, 0, 2), run(1, 1, 2)]);
+        assert_eq!(switched[..4], [true, false, false, false]);
+
+        let (fills, switched) = draw(4);
+        assert_eq!(fills, [run(1, 0, 3), run(1, 1, 3)]);
+        assert!(switched.iter().all(|on| *on));
+    }
+}
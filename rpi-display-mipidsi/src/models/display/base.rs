@@ -5,7 +5,9 @@ use rpi_gpio::DisplayBacklight;
 // #[cfg(feature = "debug")]
 // use std::time::Instant;
 
+use super::RecoveryState;
 use crate::foreign_types::*;
+use crate::traits::PresetModel;
 
 #[allow(dead_code)]
 pub struct LcdDisplay<DI, MODEL, RST, const W: u16, const H: u16>
@@ -19,6 +21,12 @@ where
     pub backlight: DisplayBacklight,
 
     pub screen: RawDisplay<DI, MODEL, RST>,
+
+    /// The tearing effect output set at initialisation, restored after a soft reset.
+    pub(crate) tearing_effect: TearingEffect,
+
+    /// How to recover from transient errors; see [`LcdDisplay::with_recovery`].
+    pub(crate) recovery: RecoveryState,
 }
 
 impl<DI, MODEL, RST, const W: u16, const H: u16> Dimensions for LcdDisplay<DI, MODEL, RST, W, H>
@@ -64,10 +72,10 @@ where
 //             let result = self.screen.draw_iter(pixels);
 
 //             #[cfg(feature = "debug")]
-//             logger::trace(&format!(
+//             logger::trace!(
 //                 "Drawing to LcdDisplay took {}ms.",
 //                 start_time.elapsed().as_millis()
-//             ));
+//             );
 
 //             result
 //         } else {
@@ -76,12 +84,64 @@ where
 //     }
 // }
 
+impl<DI, MODEL, RST, const W: u16, const H: u16> LcdDisplay<DI, MODEL, RST, W, H>
+where
+    DI: WriteOnlyDataCommand,
+    MODEL: DisplayModel,
+    RST: OutputPinType,
+{
+    /// Create a new display unit with the given panel model.
+    ///
+    /// For the preset models, use `new` instead, which supplies the model for you.
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_model(
+        di: DI,
+        model: MODEL,
+        rst: Option<RST>,
+        mut delay: Delay,
+        orientation: Orientation,
+        colour_inversion: ColorInversion,
+        tearing_effect: TearingEffect,
+        backlight: DisplayBacklight,
+    ) -> RPiResult<Self> {
+        let mut screen = RawDisplayBuilder::with_model(di, model)
+            // width and height are switched on purpose because of the orientation
+            .with_display_size(H, W)
+            .with_orientation(orientation)
+            .with_invert_colors(colour_inversion)
+            .init(&mut delay, rst)
+            .context("initialise the display")?;
+
+        if tearing_effect != TearingEffect::Off {
+            screen
+                .set_tearing_effect(tearing_effect)
+                .context("set the tearing effect")?;
+        }
+
+        Ok(Self {
+            _delay: delay,
+            backlight,
+            screen,
+            tearing_effect,
+            recovery: RecoveryState::default(),
+        })
+    }
+}
+
 macro_rules! expand_preset_models {
     ($((
         $model:path,
         $name:literal
     )),*) => {
         $(
+            impl PresetModel for $model {
+                const NAME: &'static str = $name;
+
+                fn preset() -> Self {
+                    $model
+                }
+            }
+
             impl<DI, RST, const W: u16, const H: u16> LcdDisplay<DI, $model, RST, W, H>
             where
                 DI: WriteOnlyDataCommand,
@@ -90,38 +150,28 @@ macro_rules! expand_preset_models {
                 #[doc = "Create a new "]
                 #[doc = $name]
                 #[doc = " display unit."]
-                pub fn new<'e>(
+                pub fn new(
                     di: DI,
                     rst: Option<RST>,
-                    mut delay: Delay,
+                    delay: Delay,
                     orientation: Orientation,
                     colour_inversion: ColorInversion,
                     tearing_effect: TearingEffect,
                     backlight: DisplayBacklight,
-                ) -> RPiResult<'e, Self> {
+                ) -> RPiResult<Self> {
                     #[cfg(feature = "debug")]
-                    logger::info(&format!("Creating new {}x{} {} LcdDisplay...", W, H, $name));
-
-                    let mut screen =
-                        RawDisplayBuilder::with_model(di, $model)
-                        // width and height are switched on purpose because of the orientation
-                        .with_display_size(H, W)
-                        .with_orientation(orientation)
-                        .with_invert_colors(colour_inversion)
-                        .init(&mut delay, rst)
-                        .into_rpi_result()?
-                    ;
-
-                    if tearing_effect != TearingEffect::Off {
-                        screen.set_tearing_effect(tearing_effect)
-                            .into_rpi_result()?;
-                    }
-
-                    Ok(Self {
-                        _delay: delay,
+                    logger::info!("Creating new {}x{} {} LcdDisplay...", W, H, $name);
+
+                    Self::with_model(
+                        di,
+                        $model,
+                        rst,
+                        delay,
+                        orientation,
+                        colour_inversion,
+                        tearing_effect,
                         backlight,
-                        screen,
-                    })
+                    )
                 }
             }
         )*
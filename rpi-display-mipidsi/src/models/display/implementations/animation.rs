@@ -0,0 +1,303 @@
+//! Play [`Animation`]s on the display.
+//!
+
+use std::{future::Future, time::Duration};
+
+use embedded_graphics::draw_target::DrawTargetExt;
+use tokio::time::Instant;
+
+use crate::{foreign_types::*, record_success, traits::Animation, LcdDisplay};
+
+impl<DI, MODEL, RST, const W: u16, const H: u16> LcdDisplay<DI, MODEL, RST, W, H>
+where
+    DI: WriteOnlyDataCommand,
+    MODEL: DisplayModel,
+    RST: OutputPinType,
+{
+    /// Play an animation with its top left corner at `position`, for `loops` times,
+    /// or forever if [`None`]; pass [`Animation::play_count`] to follow the number
+    /// of loops set by the animation itself.
+    ///
+    /// After the first frame, only the area that changed from the previous frame is
+    /// redrawn, if the animation reports one.
+    ///
+    /// Playback is stopped when `stop` completes, such as
+    /// `rpi_gpio::func::termination::ctrl_c()` or the receiving end of a channel;
+    /// [`RPiError::Cancelled`] is returned in that case, unless `stop` itself
+    /// failed.
+    ///
+    /// Transient errors are recovered according to the
+    /// [`RecoveryPolicy`](crate::RecoveryPolicy), playing the animation again from
+    /// its first frame.
+    pub async fn play_animation<A, F>(
+        &mut self,
+        animation: &A,
+        position: Point,
+        loops: Option<u32>,
+        stop: F,
+    ) -> RPiResult<()>
+    where
+        A: Animation<Colour = MODEL::ColorFormat>,
+        F: Future<Output = RPiResult<()>>,
+    {
+        let frame_count = animation.frame_count();
+        if frame_count == 0 {
+            return Err(RPiError::InvalidInput(
+                "animation".into(),
+                "no frames to play".into(),
+            ));
+        }
+
+        let playback = async {
+            let mut retries = 0;
+
+            loop {
+                match play_frames(&mut self.screen, animation, position, loops).await {
+                    Ok(()) => {
+                        record_success(&mut self.recovery, retries);
+                        return Ok(());
+                    }
+                    Err(err) => {
+                        if !self.recover_after(&err, retries).await {
+                            return Err(err);
+                        }
+                        retries += 1;
+                    }
+                }
+            }
+        };
+
+        tokio::select! {
+            result = playback => result,
+            result = stop => result.and(Err(RPiError::Cancelled)),
+        }
+    }
+}
+
+/// Play the frames of an animation onto `target`, for `loops` times or forever.
+async fn play_frames<DT, A>(
+    target: &mut DT,
+    animation: &A,
+    position: Point,
+    loops: Option<u32>,
+) -> RPiResult<()>
+where
+    DT: DrawTarget<Color = A::Colour>,
+    DT::Error: Into<RPiError>,
+    A: Animation,
+{
+    let frame_count = animation.frame_count();
+    let full_frame = animation.bounding_box();
+    let mut deadline = Instant::now();
+    let mut played = 0;
+
+    while loops.map_or(true, |loops| played < loops) {
+        for index in 0..frame_count {
+            let area = animation.frame_delta(index).unwrap_or(full_frame);
+
+            animation
+                .draw_frame(index, &area, &mut target.translated(position))
+                .into_rpi_result()?;
+
+            deadline = next_deadline(deadline, Instant::now(), animation.frame_delay(index));
+            tokio::time::sleep_until(deadline).await;
+        }
+
+        played += 1;
+    }
+
+    Ok(())
+}
+
+/// Get the time to show the next frame at, given the time the current frame was
+/// due at and the time it finished drawing.
+///
+/// Frames are never rushed through to catch up: if drawing a frame overran its
+/// deadline, the rest of the schedule is shifted so that the frame is still shown
+/// for its full delay. Frames are not skipped either, as each one may only hold
+/// what changed from the frame before it.
+fn next_deadline(deadline: Instant, now: Instant, delay: Duration) -> Instant {
+    deadline.max(now) + delay
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use embedded_graphics::{pixelcolor::Rgb888, primitives::Rectangle};
+
+    use super::*;
+    use crate::images::RasterImage;
+
+    /// An animation that records the frames drawn, with the area drawn and the time
+    /// since the start of the test.
+    struct Recorder {
+        delays: Vec<u64>,
+        start: Instant,
+        drawn: RefCell<Vec<(usize, Rectangle, u64)>>,
+    }
+
+    impl Recorder {
+        fn new(delays: &[u64]) -> Self {
+            Self {
+                delays: delays.to_vec(),
+                start: Instant::now(),
+                drawn: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl OriginDimensions for Recorder {
+        fn size(&self) -> Size {
+            Size::new(4, 4)
+        }
+    }
+
+    impl Animation for Recorder {
+        type Colour = Rgb888;
+
+        fn frame_count(&self) -> usize {
+            self.delays.len()
+        }
+
+        fn frame_delay(&self, index: usize) -> Duration {
+            Duration::from_millis(self.delays[index])
+        }
+
+        fn play_count(&self) -> Option<u32> {
+            None
+        }
+
+        fn frame_delta(&self, index: usize) -> Option<Rectangle> {
+            (index > 0).then(|| Rectangle::new(Point::new(1, 1), Size::new(2, 2)))
+        }
+
+        fn draw_frame<DT>(
+            &self,
+            index: usize,
+            area: &Rectangle,
+            _: &mut DT,
+        ) -> Result<(), DT::Error>
+        where
+            DT: DrawTarget<Color = Self::Colour>,
+        {
+            let elapsed = self.start.elapsed().as_millis() as u64;
+            self.drawn.borrow_mut().push((index, *area, elapsed));
+
+            Ok(())
+        }
+    }
+
+    fn target() -> RasterImage<Rgb888> {
+        RasterImage::filled(Size::new(4, 4), Rgb888::default())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn frames_are_shown_for_their_delays() {
+        let animation = Recorder::new(&[10, 20, 30]);
+
+        play_frames(&mut target(), &animation, Point::zero(), Some(2))
+            .await
+            .unwrap();
+
+        let times: Vec<_> = animation
+            .drawn
+            .borrow()
+            .iter()
+            .map(|&(index, _, elapsed)| (index, elapsed))
+            .collect();
+        assert_eq!(times, [(0, 0), (1, 10), (2, 30), (0, 60), (1, 70), (2, 90)]);
+        assert_eq!(animation.start.elapsed(), Duration::from_millis(120));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn only_deltas_are_drawn_after_the_first_frame() {
+        let animation = Recorder::new(&[10, 10]);
+
+        play_frames(&mut target(), &animation, Point::zero(), Some(2))
+            .await
+            .unwrap();
+
+        let full = Rectangle::new(Point::zero(), Size::new(4, 4));
+        let delta = Rectangle::new(Point::new(1, 1), Size::new(2, 2));
+        let areas: Vec<_> = animation
+            .drawn
+            .borrow()
+            .iter()
+            .map(|&(_, area, _)| area)
+            .collect();
+        assert_eq!(areas, [full, delta, full, delta]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn no_loops_draw_nothing() {
+        let animation = Recorder::new(&[10]);
+
+        play_frames(&mut target(), &animation, Point::zero(), Some(0))
+            .await
+            .unwrap();
+
+        assert!(animation.drawn.borrow().is_empty());
+    }
+
+    #[test]
+    fn deadlines_keep_to_the_schedule() {
+        let start = Instant::now();
+        let delay = Duration::from_millis(10);
+
+        // Drawing finished early, so the next frame is due a delay after this one.
+        assert_eq!(
+            next_deadline(start, start - Duration::from_millis(5), delay),
+            start + delay
+        );
+        assert_eq!(next_deadline(start, start, delay), start + delay);
+    }
+
+    #[test]
+    fn overrunning_frames_shift_the_schedule() {
+        let start = Instant::now();
+        let delay = Duration::from_millis(10);
+        let late = start + Duration::from_millis(25);
+
+        // The late frame is still shown for its full delay, rather than the next
+        // frame being drawn straight away.
+        assert_eq!(next_deadline(start, late, delay), late + delay);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn drawing_errors_are_returned() {
+        struct Broken;
+
+        impl Dimensions for Broken {
+            fn bounding_box(&self) -> Rectangle {
+                Rectangle::new(Point::zero(), Size::new(4, 4))
+            }
+        }
+
+        impl DrawTarget for Broken {
+            type Color = Rgb888;
+            type Error = RPiError;
+
+            fn draw_iter<I>(&mut self, _: I) -> Result<(), Self::Error>
+            where
+                I: IntoIterator<Item = embedded_graphics::Pixel<Self::Color>>,
+            {
+                Err(RPiError::Cancelled)
+            }
+        }
+
+        let animation = crate::images::FrameAnimation::new(
+            vec![crate::images::AnimationFrame {
+                image: target(),
+                delay: Duration::from_millis(10),
+                delta: None,
+            }],
+            None,
+        )
+        .unwrap();
+
+        let result = play_frames(&mut Broken, &animation, Point::zero(), None).await;
+
+        assert!(matches!(result, Err(RPiError::Cancelled)));
+    }
+}
@@ -7,10 +7,9 @@ where
     RST: OutputPinType,
 {
     /// Clears the display.
-    pub fn fill<'e>(&mut self, colour: MODEL::ColorFormat) -> RPiResult<'e, ()> {
-        self.screen
-            .clear(colour)
-            .map_err(|_| RPiError::DisplayOutputError)
+    pub async fn fill(&mut self, colour: MODEL::ColorFormat) -> RPiResult<()> {
+        self.with_recovery(|display| display.screen.clear(colour).into_rpi_result())
+            .await
     }
 }
 
@@ -24,8 +23,8 @@ macro_rules! expand_preset_colours {
             #[doc = "Fill the display with "]
             #[doc = $name]
             #[doc = " colour."]
-            pub fn $func(&mut self) -> RPiResult<()> {
-                self.fill($colour)
+            pub async fn $func(&mut self) -> RPiResult<()> {
+                self.fill($colour).await
             }
         )*
     };
@@ -11,50 +11,53 @@ where
     MODEL::ColorFormat: From<<MODEL::ColorFormat as PixelColor>::Raw>,
 {
     /// Draw a line on the display.
-    pub fn draw_line<'e>(
+    pub async fn draw_line(
         &mut self,
         from: Point,
         to: Point,
         colour: MODEL::ColorFormat,
         stroke: u32,
-    ) -> RPiResult<'e, ()> {
-        primitives::Line::new(from, to)
-            .into_styled(PrimitiveStyle::with_stroke(colour, stroke))
-            .draw(&mut self.screen)
-            .into_rpi_result()
+    ) -> RPiResult<()> {
+        let line = primitives::Line::new(from, to)
+            .into_styled(PrimitiveStyle::with_stroke(colour, stroke));
+
+        self.with_recovery(|display| line.draw(&mut display.screen).into_rpi_result())
+            .await
     }
 
     /// Draw a line on the display from a given point to another given the offset.
-    pub fn draw_line_from<'e>(
+    pub async fn draw_line_from(
         &mut self,
         from: Point,
         dx: i32,
         dy: i32,
         colour: MODEL::ColorFormat,
         stroke: u32,
-    ) -> RPiResult<'e, ()> {
+    ) -> RPiResult<()> {
         let to = Point::new(from.x + dx, from.y + dy);
 
-        self.draw_line(from, to, colour, stroke)
+        self.draw_line(from, to, colour, stroke).await
     }
 
     /// Draw a horizontal line on the display.
-    pub fn draw_horizontal_line<'e>(
+    pub async fn draw_horizontal_line(
         &mut self,
         y: i32,
         colour: MODEL::ColorFormat,
         stroke: u32,
-    ) -> RPiResult<'e, ()> {
+    ) -> RPiResult<()> {
         self.draw_line(Point::new(0, y), Point::new(W as i32, y), colour, stroke)
+            .await
     }
 
     // Draw a vertical line on the display.
-    pub fn draw_vertical_line<'e>(
+    pub async fn draw_vertical_line(
         &mut self,
         x: i32,
         colour: MODEL::ColorFormat,
         stroke: u32,
-    ) -> RPiResult<'e, ()> {
+    ) -> RPiResult<()> {
         self.draw_line(Point::new(x, 0), Point::new(x, H as i32), colour, stroke)
+            .await
     }
 }
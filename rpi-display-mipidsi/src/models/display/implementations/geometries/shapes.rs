@@ -11,15 +11,16 @@ where
     MODEL::ColorFormat: From<<MODEL::ColorFormat as PixelColor>::Raw>,
 {
     /// Draw a rectangle on the display.
-    pub fn draw_rect<'e>(
+    pub async fn draw_rect(
         &mut self,
         position: Point,
         size: Size,
         colour: MODEL::ColorFormat,
-    ) -> RPiResult<'e, ()> {
-        primitives::Rectangle::new(position, size)
-            .into_styled(PrimitiveStyle::with_fill(colour))
-            .draw(&mut self.screen)
-            .into_rpi_result()
+    ) -> RPiResult<()> {
+        let rect = primitives::Rectangle::new(position, size)
+            .into_styled(PrimitiveStyle::with_fill(colour));
+
+        self.with_recovery(|display| rect.draw(&mut display.screen).into_rpi_result())
+            .await
     }
 }
@@ -0,0 +1,156 @@
+//! Send pixel data to the display in bulk, bypassing the per-pixel iterators of
+//! [`embedded_graphics`].
+//!
+
+use embedded_graphics::{pixelcolor::raw::RawData, primitives::Rectangle};
+
+use crate::{
+    foreign_types::*,
+    func,
+    images::{OwnedImageRaw, RasterImage},
+    BigEndian, LcdDisplay,
+};
+
+impl<DI, MODEL, RST, const W: u16, const H: u16> LcdDisplay<DI, MODEL, RST, W, H>
+where
+    DI: WriteOnlyDataCommand,
+    MODEL: DisplayModel,
+    RST: OutputPinType,
+{
+    /// Default number of bytes sent to the display interface at a time, matching the
+    /// default buffer size of the `spidev` driver.
+    pub const BLIT_CHUNK_SIZE: usize = 4096;
+
+    /// Send bytes that are already in the pixel format and byte order of the panel,
+    /// such as big endian [`Rgb565`], to the given area of the display.
+    ///
+    /// The address window is set once, then the bytes are streamed to the display
+    /// interface in chunks of [`BLIT_CHUNK_SIZE`](Self::BLIT_CHUNK_SIZE); this is
+    /// much faster than drawing an [`Image`] for large areas, such as full screen
+    /// photos. If a transient error interrupts the transfer, the whole area is
+    /// sent again through [`with_recovery`](Self::with_recovery).
+    ///
+    /// [`Rgb565`]: pixelcolor::Rgb565
+    pub async fn blit_raw(&mut self, area: &Rectangle, bytes: &[u8]) -> RPiResult<()> {
+        self.blit_raw_chunked(area, bytes, Self::BLIT_CHUNK_SIZE)
+            .await
+    }
+
+    /// Send bytes that are already in the pixel format and byte order of the panel
+    /// to the given area of the display, in chunks of `chunk_size` bytes.
+    ///
+    /// Use this instead of [`blit_raw`](Self::blit_raw) if the buffer size of the
+    /// `spidev` driver has been changed, such as with `spidev.bufsiz=65536` on the
+    /// kernel command line.
+    pub async fn blit_raw_chunked(
+        &mut self,
+        area: &Rectangle,
+        bytes: &[u8],
+        chunk_size: usize,
+    ) -> RPiResult<()> {
+        let bytes_per_pixel =
+            <<MODEL::ColorFormat as PixelColor>::Raw as RawData>::BITS_PER_PIXEL / 8;
+        let expected = (area.size.width * area.size.height) as usize * bytes_per_pixel;
+
+        if bytes.len() != expected {
+            return Err(RPiError::InvalidInput(
+                "bytes".into(),
+                format!(
+                    "{} bytes found, but a {}x{} area requires {expected}",
+                    bytes.len(),
+                    area.size.width,
+                    area.size.height
+                )
+                .into(),
+            ));
+        }
+
+        if self.bounding_box().intersection(area) != *area {
+            return Err(RPiError::InvalidInput(
+                "area".into(),
+                format!("{area:?} is outside of the display").into(),
+            ));
+        }
+
+        let Some(bottom_right) = area.bottom_right() else {
+            return Ok(());
+        };
+
+        self.with_recovery(|display| {
+            // Setting no pixels leaves the panel with the address window set, waiting
+            // for pixel data; this lets `mipidsi` apply the offsets of the panel.
+            display
+                .screen
+                .set_pixels(
+                    area.top_left.x as u16,
+                    area.top_left.y as u16,
+                    bottom_right.x as u16,
+                    bottom_right.y as u16,
+                    std::iter::empty(),
+                )
+                .into_rpi_result()?;
+
+            // SAFETY: only pixel data is sent, which continues the memory write
+            // started above without changing any state of the display that `mipidsi`
+            // tracks.
+            let dcs = unsafe { display.screen.dcs() };
+
+            bytes
+                .chunks(chunk_size.max(1))
+                .try_for_each(|chunk| dcs.di.send_data(DataFormat::U8(chunk)))
+                .into_rpi_result()
+        })
+        .await
+    }
+
+    /// Send an [`OwnedImageRaw`] that is already in the pixel format of the panel to
+    /// the display, with its top left corner at `position`.
+    pub async fn blit_image_raw<'i>(
+        &mut self,
+        image: &OwnedImageRaw<'i, MODEL::ColorFormat, BigEndian>,
+        position: Point,
+    ) -> RPiResult<()>
+    where
+        MODEL::ColorFormat: RgbColor + From<<MODEL::ColorFormat as PixelColor>::Raw>,
+        embedded_graphics::iterator::raw::RawDataSlice<
+            'i,
+            <MODEL::ColorFormat as PixelColor>::Raw,
+            BigEndian,
+        >: IntoIterator<Item = <MODEL::ColorFormat as PixelColor>::Raw>,
+    {
+        let size = image
+            .size
+            .get()
+            .copied()
+            .ok_or(RPiError::NotInitialised("OwnedImage".into()))?;
+
+        self.blit_raw(&Rectangle::new(position, size), image.bytes()?)
+            .await
+    }
+
+    /// Send a [`RasterImage`] to the display, with its top left corner at
+    /// `position`, by packing its pixels into big endian bytes first.
+    ///
+    /// The alpha channel of the image is ignored; blend it onto a background first
+    /// with [`RasterImage::composited_over`] or [`RasterImage::composite_onto`].
+    pub async fn blit_image(
+        &mut self,
+        image: &RasterImage<MODEL::ColorFormat>,
+        position: Point,
+    ) -> RPiResult<()>
+    where
+        MODEL::ColorFormat: IntoStorage<Storage = u16>,
+    {
+        let values = image
+            .pixels()
+            .iter()
+            .map(|pixel| pixel.into_storage())
+            .collect::<Vec<_>>();
+
+        let mut bytes = vec![0; values.len() * 2];
+        func::kernels::pack_be(&values, &mut bytes);
+
+        self.blit_raw(&Rectangle::new(position, image.size()), &bytes)
+            .await
+    }
+}
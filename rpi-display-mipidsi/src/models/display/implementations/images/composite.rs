@@ -0,0 +1,69 @@
+//! Display images with an alpha channel, by blending them onto a known background.
+//!
+
+use embedded_graphics::{draw_target::DrawTargetExt, primitives::Rectangle};
+
+use crate::{foreign_types::*, images::RasterImage, LcdDisplay};
+use pixelcolor::Rgb888;
+
+impl<DI, MODEL, RST, const W: u16, const H: u16> LcdDisplay<DI, MODEL, RST, W, H>
+where
+    DI: WriteOnlyDataCommand,
+    MODEL: DisplayModel,
+    RST: OutputPinType,
+    MODEL::ColorFormat: From<Rgb888> + Into<Rgb888>,
+{
+    /// Draw an image with its top left corner at `position`, blending it over a
+    /// single background colour.
+    pub async fn draw_image_over(
+        &mut self,
+        image: &RasterImage<MODEL::ColorFormat>,
+        position: Point,
+        background: MODEL::ColorFormat,
+    ) -> RPiResult<()> {
+        let composited = image.composited_over(background);
+
+        self.with_recovery(|display| {
+            Image::new(&composited, position)
+                .draw(&mut display.screen)
+                .into_rpi_result()
+        })
+        .await
+    }
+
+    /// Draw an image with its top left corner at `position`, blending it onto a
+    /// shadow framebuffer of the display, then sending the affected area of the
+    /// framebuffer to the display.
+    ///
+    /// The framebuffer is expected to hold what is currently on the display, with
+    /// its top left corner at the origin; it is updated with the blended image, so
+    /// that subsequent images can be blended on top of it.
+    pub async fn draw_image_onto(
+        &mut self,
+        image: &RasterImage<MODEL::ColorFormat>,
+        position: Point,
+        framebuffer: &mut RasterImage<MODEL::ColorFormat>,
+    ) -> RPiResult<()> {
+        image.composite_onto(framebuffer, position);
+
+        self.draw_framebuffer(framebuffer, Some(&Rectangle::new(position, image.size())))
+            .await
+    }
+
+    /// Send a shadow framebuffer to the display, with its top left corner at the
+    /// origin; only the given area is sent, or the whole framebuffer if [`None`].
+    pub async fn draw_framebuffer(
+        &mut self,
+        framebuffer: &RasterImage<MODEL::ColorFormat>,
+        area: Option<&Rectangle>,
+    ) -> RPiResult<()> {
+        let area = area.copied().unwrap_or_else(|| framebuffer.bounding_box());
+
+        self.with_recovery(|display| {
+            framebuffer
+                .draw(&mut display.screen.clipped(&area))
+                .into_rpi_result()
+        })
+        .await
+    }
+}
@@ -1,3 +1,5 @@
 //! Image implementations for the display.
 //!
+mod blit;
+mod composite;
 mod raw;
@@ -17,10 +17,11 @@ where
     ///
     /// It will be drawn from the top left corner of the display, and any pixels
     /// that are outside the display area will be ignored.
-    pub fn draw_image<'e, T>(&mut self, image: &Image<'_, T>) -> RPiResult<'e, ()>
+    pub async fn draw_image<T>(&mut self, image: &Image<'_, T>) -> RPiResult<()>
     where
         T: ImageDrawable<Color = MODEL::ColorFormat>,
     {
-        image.draw(&mut self.screen).into_rpi_result()
+        self.with_recovery(|display| image.draw(&mut display.screen).into_rpi_result())
+            .await
     }
 }
@@ -1,3 +1,4 @@
+mod animation;
 mod clear;
 mod geometries;
 mod images;
@@ -0,0 +1,1128 @@
+//! Bitmap fonts loaded from BDF and PCF files.
+//!
+
+use std::{collections::HashMap, path::Path};
+
+use embedded_graphics::{
+    mono_font::{mapping::GlyphMapping, DecorationDimensions},
+    pixelcolor::BinaryColor,
+};
+
+use super::reexports::*;
+use crate::{foreign_types::*, func};
+
+/// The number of glyphs in each row of the image of a [`BitmapFont`].
+const GLYPHS_PER_ROW: u32 = 32;
+
+/// The magic bytes at the start of a PCF file.
+const PCF_MAGIC: &[u8] = b"\x01fcp";
+
+/// The magic bytes at the start of a BDF file.
+const BDF_MAGIC: &[u8] = b"STARTFONT";
+
+/// The largest size, advance or offset of a glyph, in pixels, and ascent or descent
+/// of a font accepted from a font file.
+const MAX_METRIC: u32 = 1024;
+
+/// The largest image of all the glyphs of a font, in bytes.
+const MAX_IMAGE_BYTES: usize = 64 << 20;
+
+/// Create a [`RPiError::FontError`] with the given message.
+fn font_error(message: impl ToString) -> RPiError {
+    RPiError::FontError(message.to_string().into(), None)
+}
+
+/// Check a metric read from a font file against [`MAX_METRIC`], so that a
+/// malformed file cannot make the font allocate without bound.
+fn check_metric(name: &str, value: i64) -> RPiResult<i32> {
+    if value.unsigned_abs() > MAX_METRIC as u64 {
+        return Err(font_error(format!(
+            "{}: {} is out of range; at most {} pixels are supported.",
+            name, value, MAX_METRIC
+        )));
+    }
+
+    Ok(value as i32)
+}
+
+/// A glyph as defined in a font file, before it is placed into a character cell.
+#[derive(Clone, Debug, Default)]
+struct Glyph {
+    character: char,
+
+    /// The horizontal distance to the next glyph.
+    advance: i32,
+
+    /// The size of the bitmap.
+    size: Size,
+
+    /// The offset of the bottom left corner of the bitmap from the origin on the
+    /// baseline, with `y` pointing upwards.
+    offset: Point,
+
+    /// The pixels of the bitmap, row by row from the top.
+    pixels: Vec<bool>,
+}
+
+/// The properties of a font file needed to build a [`BitmapFont`].
+#[derive(Clone, Debug, Default)]
+struct Properties {
+    ascent: Option<i32>,
+    descent: Option<i32>,
+    charset_registry: Option<String>,
+    charset_encoding: Option<String>,
+    default_char: Option<u32>,
+}
+
+impl Properties {
+    /// Get a function converting the encodings of the font into characters.
+    ///
+    /// Only Unicode and Latin-1 fonts are supported, as their encodings are the
+    /// code points of their characters; fonts of other registries would need a
+    /// conversion table each.
+    fn decoder(&self) -> RPiResult<fn(u32) -> Option<char>> {
+        let registry = self
+            .charset_registry
+            .as_deref()
+            .unwrap_or_default()
+            .to_uppercase();
+        let encoding = self.charset_encoding.as_deref().unwrap_or_default();
+
+        match (registry.as_str(), encoding) {
+            ("" | "ISO10646" | "ASCII", _) | ("ISO8859", "1") => Ok(char::from_u32),
+            _ => Err(font_error(format!(
+                "Unsupported character set {}-{}; only ISO10646 and ISO8859-1 fonts can be loaded.",
+                registry, encoding
+            ))),
+        }
+    }
+}
+
+/// The glyph indices of the characters of a [`BitmapFont`].
+#[derive(Clone, Debug, Default)]
+struct BitmapGlyphMapping {
+    indices: HashMap<char, usize>,
+
+    /// The index of the glyph drawn for characters not in the font.
+    replacement: usize,
+}
+
+impl GlyphMapping for BitmapGlyphMapping {
+    fn index(&self, c: char) -> usize {
+        self.indices.get(&c).copied().unwrap_or(self.replacement)
+    }
+}
+
+/// A bitmap font loaded from a BDF or PCF file, such as those shipped with X11 or
+/// converted from TTF with `otf2bdf`, covering any characters it defines.
+///
+/// Glyphs are placed into character cells as wide as the widest advance of the
+/// font, so proportional fonts are drawn monospaced. Use [`mono_font`] to get a
+/// [`MonoFont`] for a [`MonoTextStyle`]; as the font is borrowed, keep the
+/// [`MonoFont`] alive for as long as the style:
+///
+/// ```ignore
+/// let font = BitmapFont::from_path("fonts/unifont.bdf").await?;
+/// let mono_font = font.mono_font();
+/// let style = MonoTextStyle::new(&mono_font, Rgb565::WHITE);
+/// ```
+///
+/// Characters not in the font are drawn as the default character of the font,
+/// `?`, or the first glyph, whichever is found first.
+///
+/// [`mono_font`]: Self::mono_font
+#[derive(Clone, Debug)]
+pub struct BitmapFont {
+    data: Vec<u8>,
+    width: u32,
+    character_size: Size,
+    baseline: u32,
+    mapping: BitmapGlyphMapping,
+}
+
+impl BitmapFont {
+    /// Load a font from the bytes of a BDF or PCF file.
+    ///
+    /// Compressed files, such as `.pcf.gz`, need to be decompressed first.
+    pub fn from_bytes(bytes: &[u8]) -> RPiResult<Self> {
+        if bytes.starts_with(PCF_MAGIC) {
+            Self::from_pcf(bytes)
+        } else if bytes.starts_with(BDF_MAGIC) {
+            Self::from_bdf(bytes)
+        } else {
+            Err(font_error("Not a BDF or PCF font."))
+        }
+    }
+
+    /// Load a font from a BDF or PCF file.
+    pub async fn from_path(path: impl AsRef<Path> + std::fmt::Debug) -> RPiResult<Self> {
+        func::fs::read_bytes_from_file(path)
+            .await
+            .and_then(|bytes| Self::from_bytes(&bytes))
+    }
+
+    /// Load a font from the bytes of a BDF file.
+    pub fn from_bdf(bytes: &[u8]) -> RPiResult<Self> {
+        let text = std::str::from_utf8(bytes).map_err(font_error)?;
+        let (glyphs, properties) = bdf::parse(text)?;
+
+        Self::from_glyphs(glyphs, properties)
+    }
+
+    /// Load a font from the bytes of a PCF file.
+    pub fn from_pcf(bytes: &[u8]) -> RPiResult<Self> {
+        let (glyphs, properties) = pcf::parse(bytes)?;
+
+        Self::from_glyphs(glyphs, properties)
+    }
+
+    /// Place the glyphs into character cells of a single image.
+    fn from_glyphs(glyphs: Vec<Glyph>, properties: Properties) -> RPiResult<Self> {
+        if glyphs.is_empty() {
+            return Err(font_error("The font contains no glyphs."));
+        }
+
+        let ascent = properties.ascent.map_or(Ok(None), |ascent| {
+            check_metric("Font ascent", ascent as i64).map(Some)
+        })?;
+        let descent = properties.descent.map_or(Ok(None), |descent| {
+            check_metric("Font descent", descent as i64).map(Some)
+        })?;
+
+        let ascent = ascent.unwrap_or_else(|| {
+            glyphs
+                .iter()
+                .map(|glyph| glyph.offset.y + glyph.size.height as i32)
+                .max()
+                .unwrap_or_default()
+        });
+        let descent = descent.unwrap_or_else(|| {
+            glyphs
+                .iter()
+                .map(|glyph| -glyph.offset.y)
+                .max()
+                .unwrap_or_default()
+        });
+        let width = glyphs
+            .iter()
+            .map(|glyph| glyph.advance)
+            .max()
+            .unwrap_or_default();
+        let height = ascent + descent;
+
+        if width <= 0 || height <= 0 {
+            return Err(font_error(format!(
+                "Invalid character size {}x{}.",
+                width, height
+            )));
+        }
+        let character_size = Size::new(width as u32, height as u32);
+
+        let columns = GLYPHS_PER_ROW.min(glyphs.len() as u32);
+        let rows = (glyphs.len() as u32).div_ceil(columns);
+        let image_width = columns * character_size.width;
+        let stride = image_width.div_ceil(8) as usize;
+        let length = stride
+            .checked_mul((rows * character_size.height) as usize)
+            .filter(|length| *length <= MAX_IMAGE_BYTES)
+            .ok_or_else(|| {
+                font_error(format!(
+                    "{} glyphs of {}x{} pixels are too large to load.",
+                    glyphs.len(),
+                    width,
+                    height
+                ))
+            })?;
+        let mut data = vec![0_u8; length];
+
+        let mut indices = HashMap::with_capacity(glyphs.len());
+        for (index, glyph) in glyphs.iter().enumerate() {
+            indices.entry(glyph.character).or_insert(index);
+
+            let cell = Point::new(
+                (index as u32 % columns * character_size.width) as i32,
+                (index as u32 / columns * character_size.height) as i32,
+            );
+            // The top of the bitmap, relative to the top of the cell.
+            let top = ascent - (glyph.offset.y + glyph.size.height as i32);
+
+            for (row, pixels) in glyph
+                .pixels
+                .chunks(glyph.size.width.max(1) as usize)
+                .enumerate()
+            {
+                let y = top + row as i32;
+                if !(0..height).contains(&y) {
+                    continue;
+                }
+
+                for (column, _) in pixels.iter().enumerate().filter(|(_, pixel)| **pixel) {
+                    let x = glyph.offset.x + column as i32;
+                    if !(0..width).contains(&x) {
+                        continue;
+                    }
+
+                    let (x, y) = ((cell.x + x) as usize, (cell.y + y) as usize);
+                    data[y * stride + x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+        }
+
+        let replacement = properties
+            .default_char
+            .and_then(char::from_u32)
+            .and_then(|character| indices.get(&character))
+            .or_else(|| indices.get(&'?'))
+            .copied()
+            .unwrap_or_default();
+
+        #[cfg(feature = "debug")]
+        logger::debug!(
+            "Loaded bitmap font of {} glyphs, {}x{} pixels each.",
+            glyphs.len(),
+            character_size.width,
+            character_size.height
+        );
+
+        Ok(Self {
+            data,
+            width: image_width,
+            character_size,
+            baseline: ascent.saturating_sub(1).max(0) as u32,
+            mapping: BitmapGlyphMapping {
+                indices,
+                replacement,
+            },
+        })
+    }
+
+    /// Get the size of each character of the font.
+    pub fn character_size(&self) -> Size {
+        self.character_size
+    }
+
+    /// Check if the font has a glyph for `character`.
+    pub fn contains(&self, character: char) -> bool {
+        self.mapping.indices.contains_key(&character)
+    }
+
+    /// Get the [`MonoFont`] of this font, for use in a [`MonoTextStyle`].
+    pub fn mono_font(&self) -> MonoFont<'_> {
+        let height = self.character_size.height;
+
+        MonoFont {
+            image: ImageRaw::<BinaryColor>::new(&self.data, self.width),
+            character_size: self.character_size,
+            character_spacing: 0,
+            baseline: self.baseline,
+            strikethrough: DecorationDimensions::new(height / 2, 1),
+            underline: DecorationDimensions::new((self.baseline + 2).min(height - 1), 1),
+            glyph_mapping: &self.mapping,
+        }
+    }
+
+    /// Leak the font to get a [`MonoFont`] that lives for the rest of the program,
+    /// such as for fonts loaded once at startup.
+    pub fn leak(self) -> MonoFont<'static> {
+        Box::leak(Box::new(self)).mono_font()
+    }
+}
+
+/// Parser of the text based Glyph Bitmap Distribution Format.
+mod bdf {
+    use super::*;
+
+    /// Parse a number in a BDF file.
+    fn number<T: std::str::FromStr>(value: Option<&str>, line: &str) -> RPiResult<T> {
+        value
+            .and_then(|value| value.parse().ok())
+            .ok_or_else(|| font_error(format!("Invalid BDF line: {:?}", line)))
+    }
+
+    /// Parse a metric in a BDF file, such as the size of a glyph.
+    fn metric(value: Option<&str>, line: &str) -> RPiResult<i32> {
+        check_metric(&format!("BDF line {:?}", line), number(value, line)?)
+    }
+
+    /// Parse the size and offset of a bounding box in a BDF file.
+    fn parse_bounding_box<'v>(
+        values: &mut impl Iterator<Item = &'v str>,
+        line: &str,
+    ) -> RPiResult<(Size, Point)> {
+        let size = Size::new(
+            metric(values.next(), line)?.max(0) as u32,
+            metric(values.next(), line)?.max(0) as u32,
+        );
+        let offset = Point::new(metric(values.next(), line)?, metric(values.next(), line)?);
+
+        Ok((size, offset))
+    }
+
+    /// Remove the quotes around a string property.
+    fn unquote(value: &str) -> String {
+        value.trim().trim_matches('"').to_owned()
+    }
+
+    /// Parse the glyphs and properties of a BDF file.
+    pub(super) fn parse(text: &str) -> RPiResult<(Vec<Glyph>, Properties)> {
+        let mut properties = Properties::default();
+        let mut bounding_box = (Size::zero(), Point::zero());
+        let mut encoded = Vec::new();
+
+        let mut lines = text.lines().map(str::trim);
+        while let Some(line) = lines.next() {
+            let (keyword, rest) = line.split_once(' ').unwrap_or((line, ""));
+            let mut values = rest.split_whitespace();
+
+            match keyword {
+                "FONTBOUNDINGBOX" => bounding_box = parse_bounding_box(&mut values, line)?,
+                "FONT_ASCENT" => properties.ascent = Some(number(values.next(), line)?),
+                "FONT_DESCENT" => properties.descent = Some(number(values.next(), line)?),
+                "CHARSET_REGISTRY" => properties.charset_registry = Some(unquote(rest)),
+                "CHARSET_ENCODING" => properties.charset_encoding = Some(unquote(rest)),
+                "DEFAULT_CHAR" => properties.default_char = Some(number(values.next(), line)?),
+                "STARTCHAR" => encoded.push(parse_char(&mut lines, bounding_box)?),
+                _ => {}
+            }
+        }
+
+        // Unlike in PCF, the bounding box stands in for missing ascent and descent.
+        let (size, offset) = bounding_box;
+        properties.ascent = properties
+            .ascent
+            .or((size.height > 0).then_some(size.height as i32 + offset.y));
+        properties.descent = properties
+            .descent
+            .or((size.height > 0).then_some(-offset.y));
+
+        let decode = properties.decoder()?;
+        let glyphs = encoded
+            .into_iter()
+            .filter_map(|(encoding, glyph)| {
+                let character = u32::try_from(encoding).ok().and_then(decode)?;
+                Some(Glyph { character, ..glyph })
+            })
+            .collect();
+
+        Ok((glyphs, properties))
+    }
+
+    /// Parse a glyph from `STARTCHAR` to `ENDCHAR`, with its encoding, which is
+    /// negative for glyphs without one.
+    fn parse_char<'l>(
+        lines: &mut impl Iterator<Item = &'l str>,
+        bounding_box: (Size, Point),
+    ) -> RPiResult<(i64, Glyph)> {
+        let mut encoding = -1;
+        let (mut size, mut offset) = bounding_box;
+        let mut glyph = Glyph {
+            advance: size.width as i32,
+            ..Default::default()
+        };
+
+        while let Some(line) = lines.next() {
+            let (keyword, rest) = line.split_once(' ').unwrap_or((line, ""));
+            let mut values = rest.split_whitespace();
+
+            match keyword {
+                "ENCODING" => encoding = number(values.next(), line)?,
+                "DWIDTH" => glyph.advance = metric(values.next(), line)?,
+                "BBX" => (size, offset) = parse_bounding_box(&mut values, line)?,
+                "BITMAP" => {
+                    glyph.pixels = Vec::with_capacity((size.width * size.height) as usize);
+
+                    for row in lines.by_ref().take(size.height as usize) {
+                        let bits = (0..row.len())
+                            .step_by(2)
+                            .filter_map(|start| row.get(start..start + 2))
+                            .map(|byte| u8::from_str_radix(byte, 16))
+                            .collect::<Result<Vec<_>, _>>()
+                            .map_err(|_| font_error(format!("Invalid BDF bitmap: {:?}", row)))?;
+
+                        glyph.pixels.extend((0..size.width as usize).map(|x| {
+                            bits.get(x / 8)
+                                .is_some_and(|byte| byte & (0x80 >> (x % 8)) != 0)
+                        }));
+                    }
+                }
+                "ENDCHAR" => {
+                    glyph.size = size;
+                    glyph.offset = offset;
+                    glyph
+                        .pixels
+                        .resize((size.width * size.height) as usize, false);
+
+                    return Ok((encoding, glyph));
+                }
+                _ => {}
+            }
+        }
+
+        Err(font_error("Unexpected end of BDF file."))
+    }
+}
+
+/// Parser of the binary Portable Compiled Format.
+mod pcf {
+    use super::*;
+
+    pub(super) const PCF_PROPERTIES: u32 = 1 << 0;
+    pub(super) const PCF_METRICS: u32 = 1 << 2;
+    pub(super) const PCF_BITMAPS: u32 = 1 << 3;
+    pub(super) const PCF_BDF_ENCODINGS: u32 = 1 << 5;
+
+    pub(super) const PCF_COMPRESSED_METRICS: u32 = 0x100;
+    pub(super) const PCF_BYTE_MASK: u32 = 1 << 2;
+    pub(super) const PCF_BIT_MASK: u32 = 1 << 3;
+
+    /// The encoding index of a character without a glyph.
+    pub(super) const NO_GLYPH: u16 = 0xFFFF;
+
+    /// A cursor over a table of a PCF file, in the byte order of the table.
+    struct Table<'b> {
+        bytes: &'b [u8],
+        position: usize,
+        format: u32,
+    }
+
+    impl<'b> Table<'b> {
+        /// Find the table of the given type, and read its format.
+        fn find(bytes: &'b [u8], table_type: u32) -> RPiResult<Self> {
+            let mut header = Self {
+                bytes,
+                position: PCF_MAGIC.len(),
+                format: 0,
+            };
+
+            let count = header.count(16)?;
+            for _ in 0..count {
+                let (entry_type, _, _, offset) =
+                    (header.u32()?, header.u32()?, header.u32()?, header.u32()?);
+
+                if entry_type == table_type {
+                    let mut table = Self {
+                        bytes,
+                        position: offset as usize,
+                        format: 0,
+                    };
+                    // The format itself is always little endian.
+                    table.format = table.u32()?;
+
+                    return Ok(table);
+                }
+            }
+
+            Err(font_error(format!(
+                "PCF table {:#x} not found.",
+                table_type
+            )))
+        }
+
+        fn is_big_endian(&self) -> bool {
+            self.format & PCF_BYTE_MASK != 0
+        }
+
+        fn take<const N: usize>(&mut self) -> RPiResult<[u8; N]> {
+            let bytes = self
+                .bytes
+                .get(self.position..self.position + N)
+                .and_then(|bytes| bytes.try_into().ok())
+                .ok_or_else(|| font_error("Unexpected end of PCF file."))?;
+            self.position += N;
+
+            Ok(bytes)
+        }
+
+        fn u8(&mut self) -> RPiResult<u8> {
+            self.take::<1>().map(|[byte]| byte)
+        }
+
+        fn u16(&mut self) -> RPiResult<u16> {
+            let bytes = self.take()?;
+
+            Ok(if self.is_big_endian() {
+                u16::from_be_bytes(bytes)
+            } else {
+                u16::from_le_bytes(bytes)
+            })
+        }
+
+        fn i16(&mut self) -> RPiResult<i16> {
+            self.u16().map(|value| value as i16)
+        }
+
+        fn u32(&mut self) -> RPiResult<u32> {
+            let bytes = self.take()?;
+
+            Ok(if self.is_big_endian() {
+                u32::from_be_bytes(bytes)
+            } else {
+                u32::from_le_bytes(bytes)
+            })
+        }
+
+        /// Read the number of records in the table, each `size` bytes long, which
+        /// must fit in the rest of the file.
+        fn count(&mut self, size: usize) -> RPiResult<usize> {
+            let count = self.u32()? as usize;
+            let remaining = self.bytes.len().saturating_sub(self.position);
+
+            match count.checked_mul(size) {
+                Some(length) if length <= remaining => Ok(count),
+                _ => Err(font_error(format!(
+                    "PCF table of {} records is longer than the file.",
+                    count
+                ))),
+            }
+        }
+
+        fn slice(&mut self, length: usize) -> RPiResult<&'b [u8]> {
+            let bytes = self
+                .bytes
+                .get(self.position..self.position + length)
+                .ok_or_else(|| font_error("Unexpected end of PCF file."))?;
+            self.position += length;
+
+            Ok(bytes)
+        }
+    }
+
+    /// The metrics of a glyph.
+    struct Metrics {
+        left_bearing: i32,
+        right_bearing: i32,
+        advance: i32,
+        ascent: i32,
+        descent: i32,
+    }
+
+    impl Metrics {
+        /// Create the metrics of a glyph, checking each against [`MAX_METRIC`].
+        fn new(values: [i32; 5]) -> RPiResult<Self> {
+            for value in values {
+                check_metric("PCF glyph metric", value as i64)?;
+            }
+            let [left_bearing, right_bearing, advance, ascent, descent] = values;
+
+            Ok(Self {
+                left_bearing,
+                right_bearing,
+                advance,
+                ascent,
+                descent,
+            })
+        }
+    }
+
+    /// Parse the glyphs and properties of a PCF file.
+    pub(super) fn parse(bytes: &[u8]) -> RPiResult<(Vec<Glyph>, Properties)> {
+        let properties = parse_properties(bytes)?;
+        let metrics = parse_metrics(bytes)?;
+        let bitmaps = parse_bitmaps(bytes, &metrics)?;
+
+        let decode = properties.decoder()?;
+        let mut glyphs = Vec::new();
+
+        let mut table = Table::find(bytes, PCF_BDF_ENCODINGS)?;
+        let (min_byte2, max_byte2) = (table.i16()? as i32, table.i16()? as i32);
+        let (min_byte1, max_byte1) = (table.i16()? as i32, table.i16()? as i32);
+        let default_char = table.i16()? as u16;
+
+        for byte1 in min_byte1..=max_byte1 {
+            for byte2 in min_byte2..=max_byte2 {
+                let index = table.u16()?;
+                if index == NO_GLYPH {
+                    continue;
+                }
+
+                let Some(character) = decode(((byte1 << 8) | byte2) as u32) else {
+                    continue;
+                };
+                let (Some(metrics), Some(pixels)) =
+                    (metrics.get(index as usize), bitmaps.get(index as usize))
+                else {
+                    continue;
+                };
+
+                glyphs.push(Glyph {
+                    character,
+                    advance: metrics.advance,
+                    size: Size::new(
+                        (metrics.right_bearing - metrics.left_bearing).max(0) as u32,
+                        (metrics.ascent + metrics.descent).max(0) as u32,
+                    ),
+                    offset: Point::new(metrics.left_bearing, -metrics.descent),
+                    pixels: pixels.clone(),
+                });
+            }
+        }
+
+        Ok((
+            glyphs,
+            Properties {
+                default_char: properties
+                    .default_char
+                    .or((default_char != NO_GLYPH).then_some(default_char as u32)),
+                ..properties
+            },
+        ))
+    }
+
+    /// Parse the properties of the font, which are optional in PCF.
+    fn parse_properties(bytes: &[u8]) -> RPiResult<Properties> {
+        let Ok(mut table) = Table::find(bytes, PCF_PROPERTIES) else {
+            return Ok(Properties::default());
+        };
+
+        let count = table.count(9)?;
+        let entries = (0..count)
+            .map(|_| Ok((table.u32()?, table.u8()? != 0, table.u32()?)))
+            .collect::<RPiResult<Vec<_>>>()?;
+
+        // The entries are padded to 4 bytes.
+        if count % 4 != 0 {
+            table.slice(4 - count % 4)?;
+        }
+        let size = table.u32()?;
+        let strings = table.slice(size as usize)?;
+        let string = |offset: u32| {
+            strings
+                .get(offset as usize..)
+                .and_then(|bytes| bytes.split(|byte| *byte == 0).next())
+                .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+                .unwrap_or_default()
+        };
+
+        let mut properties = Properties::default();
+        for (name, is_string, value) in entries {
+            match (string(name).as_str(), is_string) {
+                ("FONT_ASCENT", false) => properties.ascent = Some(value as i32),
+                ("FONT_DESCENT", false) => properties.descent = Some(value as i32),
+                ("DEFAULT_CHAR", false) => properties.default_char = Some(value),
+                ("CHARSET_REGISTRY", true) => properties.charset_registry = Some(string(value)),
+                ("CHARSET_ENCODING", true) => properties.charset_encoding = Some(string(value)),
+                _ => {}
+            }
+        }
+
+        Ok(properties)
+    }
+
+    /// Parse the metrics of each glyph.
+    fn parse_metrics(bytes: &[u8]) -> RPiResult<Vec<Metrics>> {
+        let mut table = Table::find(bytes, PCF_METRICS)?;
+
+        if table.format & PCF_COMPRESSED_METRICS != 0 {
+            let count = table.u16()?;
+            let mut value = || table.u8().map(|value| value as i32 - 0x80);
+
+            (0..count)
+                .map(|_| Metrics::new([value()?, value()?, value()?, value()?, value()?]))
+                .collect()
+        } else {
+            let count = table.count(12)?;
+            let mut value = || table.i16().map(|value| value as i32);
+
+            (0..count)
+                .map(|_| {
+                    let metrics = [value()?, value()?, value()?, value()?, value()?];
+                    // The attributes are not needed.
+                    value()?;
+
+                    Metrics::new(metrics)
+                })
+                .collect()
+        }
+    }
+
+    /// Parse the bitmap of each glyph into rows of pixels.
+    fn parse_bitmaps(bytes: &[u8], metrics: &[Metrics]) -> RPiResult<Vec<Vec<bool>>> {
+        let mut table = Table::find(bytes, PCF_BITMAPS)?;
+
+        let count = table.count(4)?;
+        let offsets = (0..count)
+            .map(|_| table.u32().map(|offset| offset as usize))
+            .collect::<RPiResult<Vec<_>>>()?;
+        let sizes = [table.u32()?, table.u32()?, table.u32()?, table.u32()?];
+
+        let format = table.format;
+        let data = table.slice(sizes[(format & 3) as usize] as usize)?;
+
+        // Rows are padded to a whole number of `pad` bytes, and are stored in units
+        // of `unit` bytes, which are swapped if their byte and bit orders differ.
+        let pad = 1 << (format & 3);
+        let unit = 1 << ((format >> 4) & 3);
+        let big_endian = format & PCF_BYTE_MASK != 0;
+        let msb_first = format & PCF_BIT_MASK != 0;
+
+        let bit = |index: usize| -> bool {
+            let byte = if big_endian != msb_first {
+                index / 8 / unit * unit + (unit - 1 - index / 8 % unit)
+            } else {
+                index / 8
+            };
+            let shift = if msb_first { 7 - index % 8 } else { index % 8 };
+
+            data.get(byte).is_some_and(|byte| (byte >> shift) & 1 != 0)
+        };
+
+        Ok(metrics
+            .iter()
+            .zip(offsets)
+            .map(|(metrics, offset)| {
+                let width = (metrics.right_bearing - metrics.left_bearing).max(0) as usize;
+                let height = (metrics.ascent + metrics.descent).max(0) as usize;
+                let stride = width.div_ceil(pad * 8) * pad;
+
+                (0..height)
+                    .flat_map(|y| (0..width).map(move |x| (y, x)))
+                    .map(|(y, x)| bit((offset + y * stride) * 8 + x))
+                    .collect()
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::images::RasterImage;
+    use embedded_graphics::text::{Baseline, Text};
+
+    const BDF: &str = "\
+STARTFONT 2.1
+FONT -test-fixed
+FONTBOUNDINGBOX 4 4 0 -1
+STARTPROPERTIES 2
+FONT_ASCENT 3
+FONT_DESCENT 1
+ENDPROPERTIES
+CHARS 2
+STARTCHAR A
+ENCODING 65
+DWIDTH 5 0
+BBX 4 3 0 0
+BITMAP
+90
+60
+F0
+ENDCHAR
+STARTCHAR degree
+ENCODING 176
+DWIDTH 5 0
+BBX 2 2 1 1
+BITMAP
+C0
+C0
+ENDCHAR
+ENDFONT
+";
+
+    /// The cells of `A` and `°` in the fixtures.
+    const A: [&str; 4] = ["#..#.", ".##..", "####.", "....."];
+    const DEGREE: [&str; 4] = [".##..", ".##..", ".....", "....."];
+
+    /// Draw `character` in a cell of `font`, as rows of `#` and `.`.
+    fn cell(font: &BitmapFont, character: char) -> Vec<String> {
+        let size = font.character_size();
+        let mut image = RasterImage::filled(size, BinaryColor::Off);
+
+        let mono_font = font.mono_font();
+        let style = MonoTextStyle::new(&mono_font, BinaryColor::On);
+        Text::with_baseline(&character.to_string(), Point::zero(), style, Baseline::Top)
+            .draw(&mut image)
+            .unwrap();
+
+        image
+            .pixels()
+            .chunks(size.width as usize)
+            .map(|row| {
+                row.iter()
+                    .map(|pixel| if pixel.is_on() { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn replace(from: &str, to: &str) -> RPiResult<BitmapFont> {
+        assert!(BDF.contains(from));
+        BitmapFont::from_bytes(BDF.replacen(from, to, 1).as_bytes())
+    }
+
+    #[test]
+    fn bdf_glyphs_are_placed_on_the_baseline() {
+        let font = BitmapFont::from_bytes(BDF.as_bytes()).unwrap();
+
+        assert_eq!(font.character_size(), Size::new(5, 4));
+        assert!(font.contains('A') && font.contains('°') && !font.contains('B'));
+        assert_eq!(cell(&font, 'A'), A);
+        assert_eq!(cell(&font, '°'), DEGREE);
+
+        // Characters not in the font are drawn as the first glyph.
+        assert_eq!(cell(&font, 'B'), A);
+    }
+
+    #[test]
+    fn bdf_without_properties_uses_the_bounding_box() {
+        let font = replace("FONT_ASCENT 3\nFONT_DESCENT 1\n", "").unwrap();
+
+        assert_eq!(font.character_size(), Size::new(5, 4));
+        assert_eq!(cell(&font, 'A'), A);
+    }
+
+    #[test]
+    fn malformed_bdf_is_rejected() {
+        assert!(BitmapFont::from_bytes(b"STARTFONT 2.1\nENDFONT\n").is_err());
+        assert!(BitmapFont::from_bytes(b"\x00\x01\x02").is_err());
+        assert!(BitmapFont::from_bytes(&BDF.as_bytes()[..BDF.len() - 20]).is_err());
+
+        assert!(replace("90\n", "ZZ\n").is_err());
+        assert!(replace("ENCODING 65", "ENCODING A").is_err());
+        assert!(replace("ENDPROPERTIES", "CHARSET_REGISTRY \"JISX0208.1983\"").is_err());
+    }
+
+    #[test]
+    fn oversized_bdf_metrics_are_rejected() {
+        assert!(replace("BBX 4 3 0 0", "BBX 4 4000000000 0 0").is_err());
+        assert!(replace("BBX 4 3 0 0", "BBX 100000 3 0 0").is_err());
+        assert!(replace("BBX 4 3 0 0", "BBX 4 3 0 -2147483648").is_err());
+        assert!(replace("DWIDTH 5 0", "DWIDTH 2000000000 0").is_err());
+        assert!(replace("FONT_ASCENT 3", "FONT_ASCENT 2147483647").is_err());
+        assert!(replace(
+            "FONTBOUNDINGBOX 4 4 0 -1",
+            "FONTBOUNDINGBOX 70000 70000 0 0"
+        )
+        .is_err());
+    }
+
+    /// The byte and bit order, padding and scan unit of the bitmaps of a PCF file,
+    /// and whether its metrics are compressed.
+    #[derive(Clone, Copy, Debug)]
+    struct PcfFormat {
+        big_endian: bool,
+        msb_first: bool,
+        pad: usize,
+        unit: usize,
+        compressed: bool,
+    }
+
+    /// Build a PCF file with the glyphs of [`BDF`], optionally changing the metrics
+    /// of `A` with `metrics`.
+    fn pcf(format: PcfFormat, metrics: impl Fn(&mut [i16; 5])) -> Vec<u8> {
+        let PcfFormat {
+            big_endian,
+            msb_first,
+            pad,
+            unit,
+            compressed,
+        } = format;
+
+        let byte_order = if big_endian { pcf::PCF_BYTE_MASK } else { 0 };
+        let u16 = |value: u16| match big_endian {
+            true => value.to_be_bytes(),
+            false => value.to_le_bytes(),
+        };
+        let u32 = |value: u32| match big_endian {
+            true => value.to_be_bytes(),
+            false => value.to_le_bytes(),
+        };
+
+        // Left and right bearing, advance, ascent and descent, and rows.
+        let mut a_metrics = [0, 4, 5, 3, 0];
+        metrics(&mut a_metrics);
+        let glyphs: [([i16; 5], &[&str]); 2] = [
+            (a_metrics, &["#..#", ".##.", "####"]),
+            ([1, 3, 5, 3, -1], &["##", "##"]),
+        ];
+
+        let mut properties = u32(2).to_vec();
+        properties.extend(u32(0));
+        properties.push(0);
+        properties.extend(u32(3));
+        properties.extend(u32(12));
+        properties.push(0);
+        properties.extend(u32(1));
+        properties.extend([0, 0]);
+        let strings = b"FONT_ASCENT\0FONT_DESCENT\0";
+        properties.extend(u32(strings.len() as u32));
+        properties.extend(strings);
+
+        let mut metrics = Vec::new();
+        if compressed {
+            metrics.extend(u16(glyphs.len() as u16));
+            for (values, _) in glyphs {
+                metrics.extend(values.map(|value| (value + 0x80) as u8));
+            }
+        } else {
+            metrics.extend(u32(glyphs.len() as u32));
+            for (values, _) in glyphs {
+                for value in values.into_iter().chain([0]) {
+                    metrics.extend(u16(value as u16));
+                }
+            }
+        }
+
+        let mut data = Vec::new();
+        let mut offsets = Vec::new();
+        for (values, rows) in glyphs {
+            offsets.push(data.len() as u32);
+
+            let width = (values[1] - values[0]).max(0) as usize;
+            let stride = width.div_ceil(pad * 8) * pad;
+            for row in rows {
+                let mut bytes = vec![0_u8; stride];
+                for (x, _) in row.chars().enumerate().filter(|(_, pixel)| *pixel == '#') {
+                    bytes[x / 8] |= if msb_first {
+                        0x80 >> (x % 8)
+                    } else {
+                        1 << (x % 8)
+                    };
+                }
+                if big_endian != msb_first {
+                    bytes.chunks_mut(unit).for_each(<[u8]>::reverse);
+                }
+                data.extend(bytes);
+            }
+        }
+        let mut bitmaps = u32(glyphs.len() as u32).to_vec();
+        offsets
+            .into_iter()
+            .for_each(|offset| bitmaps.extend(u32(offset)));
+        (0..4).for_each(|_| bitmaps.extend(u32(data.len() as u32)));
+        bitmaps.extend(data);
+
+        // `A` and `°`, with no glyph for the characters between them.
+        let mut encodings = Vec::new();
+        for value in [65, 176, 0, 0, pcf::NO_GLYPH] {
+            encodings.extend(u16(value));
+        }
+        for encoding in 65..=176 {
+            encodings.extend(u16(match encoding {
+                65 => 0,
+                176 => 1,
+                _ => pcf::NO_GLYPH,
+            }));
+        }
+
+        let bitmap_format = byte_order
+            | if msb_first { pcf::PCF_BIT_MASK } else { 0 }
+            | pad.trailing_zeros()
+            | unit.trailing_zeros() << 4;
+        let metrics_format = byte_order
+            | if compressed {
+                pcf::PCF_COMPRESSED_METRICS
+            } else {
+                0
+            };
+        let tables = [
+            (pcf::PCF_PROPERTIES, byte_order, properties),
+            (pcf::PCF_METRICS, metrics_format, metrics),
+            (pcf::PCF_BITMAPS, bitmap_format, bitmaps),
+            (pcf::PCF_BDF_ENCODINGS, byte_order, encodings),
+        ];
+
+        let mut header = PCF_MAGIC.to_vec();
+        let mut body = Vec::new();
+        header.extend((tables.len() as u32).to_le_bytes());
+        let start = header.len() + tables.len() * 16;
+        for (table_type, format, table) in tables {
+            let offset = (start + body.len()) as u32;
+            for value in [table_type, format, table.len() as u32 + 4, offset] {
+                header.extend(value.to_le_bytes());
+            }
+            body.extend(format.to_le_bytes());
+            body.extend(table);
+        }
+        header.extend(body);
+
+        header
+    }
+
+    const FORMATS: [PcfFormat; 6] = [
+        PcfFormat {
+            big_endian: false,
+            msb_first: false,
+            pad: 1,
+            unit: 1,
+            compressed: false,
+        },
+        PcfFormat {
+            big_endian: true,
+            msb_first: true,
+            pad: 1,
+            unit: 1,
+            compressed: true,
+        },
+        PcfFormat {
+            big_endian: false,
+            msb_first: true,
+            pad: 4,
+            unit: 1,
+            compressed: false,
+        },
+        PcfFormat {
+            big_endian: false,
+            msb_first: true,
+            pad: 4,
+            unit: 4,
+            compressed: true,
+        },
+        PcfFormat {
+            big_endian: true,
+            msb_first: false,
+            pad: 2,
+            unit: 2,
+            compressed: false,
+        },
+        PcfFormat {
+            big_endian: true,
+            msb_first: true,
+            pad: 8,
+            unit: 1,
+            compressed: false,
+        },
+    ];
+
+    #[test]
+    fn pcf_matches_bdf_in_every_format() {
+        for format in FORMATS {
+            let font = BitmapFont::from_bytes(&pcf(format, |_| {}))
+                .unwrap_or_else(|err| panic!("{:?}: {}", format, err));
+
+            assert_eq!(font.character_size(), Size::new(5, 4), "{:?}", format);
+            assert_eq!(cell(&font, 'A'), A, "{:?}", format);
+            assert_eq!(cell(&font, '°'), DEGREE, "{:?}", format);
+            assert!(!font.contains('B'), "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn truncated_pcf_is_rejected() {
+        for format in FORMATS {
+            let bytes = pcf(format, |_| {});
+
+            for length in 0..bytes.len() {
+                assert!(
+                    BitmapFont::from_pcf(&bytes[..length]).is_err(),
+                    "{:?} truncated to {} bytes",
+                    format,
+                    length
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn oversized_pcf_is_rejected() {
+        let format = FORMATS[0];
+
+        assert!(BitmapFont::from_pcf(&pcf(format, |metrics| metrics[1] = i16::MAX)).is_err());
+        assert!(BitmapFont::from_pcf(&pcf(format, |metrics| metrics[2] = 5000)).is_err());
+        assert!(BitmapFont::from_pcf(&pcf(format, |metrics| metrics[4] = i16::MIN)).is_err());
+
+        // A table count far beyond the length of the file.
+        let mut bytes = pcf(format, |_| {});
+        bytes[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(BitmapFont::from_pcf(&bytes).is_err());
+    }
+}
@@ -0,0 +1,292 @@
+//! A scrolling console of log lines, fed by [`rpi_logger`].
+//!
+
+use std::{collections::VecDeque, sync::Mutex};
+
+use tokio::sync::{futures::Notified, Notify};
+
+use super::{measure::measure_text, reexports::*};
+use crate::{foreign_types::*, LcdDisplay};
+use logger::{LogLevel, LogSink, Record};
+
+/// The last log lines received, to be drawn with
+/// [`draw_console`](LcdDisplay::draw_console).
+///
+/// Register the console with [`rpi_logger::add_sink`] to mirror the log on the
+/// display:
+///
+/// ```ignore
+/// let console = Arc::new(LogConsole::new(64));
+/// rpi_logger::add_sink(console.clone());
+/// ```
+///
+/// Lines below [`LogLevel::Info`] are ignored by default, as drawing the console
+/// logs at the trace level itself when the `debug` feature is enabled.
+#[derive(Debug)]
+pub struct LogConsole {
+    lines: Mutex<VecDeque<(LogLevel, String)>>,
+    capacity: usize,
+    level: LogLevel,
+    changed: Notify,
+}
+
+impl LogConsole {
+    /// Create a new console keeping the last `capacity` lines.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            lines: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            level: LogLevel::Info,
+            changed: Notify::new(),
+        }
+    }
+
+    /// Only keep lines at or above the given level.
+    pub fn with_level(mut self, level: LogLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Add a log line to the console, dropping the oldest lines if it is full.
+    ///
+    /// Messages spanning multiple lines are split, so each line is colour-coded.
+    pub fn push(&self, level: LogLevel, message: &str) {
+        if level < self.level || self.capacity == 0 {
+            return;
+        }
+
+        {
+            let mut lines = self
+                .lines
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+            for line in message.lines() {
+                if lines.len() >= self.capacity {
+                    lines.pop_front();
+                }
+                lines.push_back((level, line.to_owned()));
+            }
+        }
+
+        self.changed.notify_waiters();
+    }
+
+    /// Remove all the lines from the console.
+    pub fn clear(&self) {
+        self.lines
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clear();
+
+        self.changed.notify_waiters();
+    }
+
+    /// Get the number of lines in the console.
+    pub fn len(&self) -> usize {
+        self.lines
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .len()
+    }
+
+    /// Check if the console has no lines.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Wait until a line is added or the console is cleared.
+    ///
+    /// The future is notified of changes from the moment it is created, so create
+    /// it before drawing the console to not miss any lines added meanwhile.
+    pub fn changed(&self) -> Notified<'_> {
+        self.changed.notified()
+    }
+
+    /// Render the lines as ANSI text, each coloured by its level and prefixed with
+    /// its initial.
+    pub fn render_lines(&self) -> Vec<String> {
+        let lines = self
+            .lines
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        lines
+            .iter()
+            .map(|(level, line)| {
+                format!(
+                    "\u{1b}[38;5;{}m{} {}\u{1b}[39m",
+                    level.ansi_code(),
+                    level.name()[..1].to_uppercase(),
+                    line
+                )
+            })
+            .collect()
+    }
+}
+
+impl LogSink for LogConsole {
+    fn log(&self, record: &Record) {
+        self.push(record.level, &record.message)
+    }
+}
+
+/// Get the index of the first of the last `lines` that fit in `height`, measuring
+/// each line on its own from the last, and stopping at the first that does not
+/// fit; the very last line is always kept.
+///
+/// With the default [`TextBoxStyle`], there is no spacing between paragraphs, so
+/// the height of the lines drawn together is the sum of their heights.
+fn first_visible(lines: &[String], height: u32, measure: impl Fn(&str) -> u32) -> usize {
+    let Some(last) = lines.last() else {
+        return 0;
+    };
+
+    let mut start = lines.len() - 1;
+    let mut used = measure(last);
+    while start > 0 {
+        used += measure(&lines[start - 1]);
+        if used > height {
+            break;
+        }
+        start -= 1;
+    }
+
+    start
+}
+
+impl<DI, MODEL, RST, const W: u16, const H: u16> LcdDisplay<DI, MODEL, RST, W, H>
+where
+    DI: WriteOnlyDataCommand,
+    MODEL: DisplayModel,
+    RST: OutputPinType,
+    MODEL::ColorFormat: From<<MODEL::ColorFormat as PixelColor>::Raw>,
+{
+    /// Draw the last lines of a [`LogConsole`] that fit in the given area, so that
+    /// the console scrolls as lines are added.
+    ///
+    /// The area is not cleared first, so fill it or give the character style a
+    /// background colour to replace the lines drawn before.
+    pub async fn draw_console<S>(
+        &mut self,
+        console: &LogConsole,
+        character_style: S,
+        position: Option<Point>,
+        size: Option<Size>,
+    ) -> RPiResult<String>
+    where
+        MODEL::ColorFormat: Default + From<pixelcolor::Rgb888>,
+        S: TextRenderer<Color = MODEL::ColorFormat> + CharacterStyle<Color = MODEL::ColorFormat>,
+    {
+        let position = position.unwrap_or_else(Point::zero);
+        let screen_size = self.screen.size();
+        let size = size.unwrap_or_else(|| {
+            Size::new(
+                (screen_size.width as i32 - position.x) as u32,
+                (screen_size.height as i32 - position.y) as u32,
+            )
+        });
+
+        // The console is rendered before drawing, as drawing can log itself.
+        let lines = console.render_lines();
+        let textbox_style = TextBoxStyle::default();
+
+        let start = first_visible(&lines, size.height, |line| {
+            measure_text(line, &character_style, &textbox_style, size.width).height
+        });
+
+        self.draw_ansi_text(
+            &lines[start..].join("\n"),
+            character_style,
+            textbox_style,
+            Some(position),
+            Some(size),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    fn lines(console: &LogConsole) -> Vec<(LogLevel, String)> {
+        console.lines.lock().unwrap().iter().cloned().collect()
+    }
+
+    #[test]
+    fn oldest_lines_are_dropped_when_full() {
+        let console = LogConsole::new(3);
+
+        console.push(LogLevel::Info, "one");
+        console.push(LogLevel::Warning, "two\nthree");
+        console.push(LogLevel::Error, "four");
+
+        assert_eq!(
+            lines(&console),
+            [
+                (LogLevel::Warning, "two".to_owned()),
+                (LogLevel::Warning, "three".to_owned()),
+                (LogLevel::Error, "four".to_owned())
+            ]
+        );
+
+        console.clear();
+        assert!(console.is_empty());
+    }
+
+    #[test]
+    fn lines_below_the_level_are_ignored() {
+        let console = LogConsole::new(4).with_level(LogLevel::Warning);
+
+        console.push(LogLevel::Info, "ignored");
+        console.push(LogLevel::Critical, "kept");
+        assert_eq!(console.len(), 1);
+
+        let empty = LogConsole::new(0);
+        empty.push(LogLevel::Critical, "ignored");
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn lines_are_rendered_with_their_level() {
+        let console = LogConsole::new(2);
+        console.push(LogLevel::Error, "Lost the display.");
+
+        assert_eq!(
+            console.render_lines(),
+            ["\u{1b}[38;5;9mE Lost the display.\u{1b}[39m"]
+        );
+    }
+
+    #[test]
+    fn only_the_last_lines_that_fit_are_kept() {
+        let lines: Vec<String> = ["a", "bb", "c", "dd"].map(str::to_owned).into();
+        // Each character is a line of 10 pixels, as if wrapped.
+        let measure = |line: &str| line.len() as u32 * 10;
+
+        assert_eq!(first_visible(&lines, 60, measure), 0);
+        assert_eq!(first_visible(&lines, 59, measure), 1);
+        assert_eq!(first_visible(&lines, 30, measure), 2);
+        assert_eq!(first_visible(&lines, 29, measure), 3);
+        // The last line is kept even if it does not fit.
+        assert_eq!(first_visible(&lines, 0, measure), 3);
+        assert_eq!(first_visible(&[], 100, measure), 0);
+    }
+
+    #[test]
+    fn only_the_visible_lines_are_measured() {
+        let lines = vec!["line".to_owned(); 1000];
+        let measured = Cell::new(0);
+
+        let start = first_visible(&lines, 30, |_| {
+            measured.set(measured.get() + 1);
+            10
+        });
+
+        assert_eq!(start, 997);
+        assert_eq!(measured.get(), 4);
+    }
+}
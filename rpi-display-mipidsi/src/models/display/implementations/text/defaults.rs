@@ -1,11 +1,17 @@
 //! Default [`CharacterStyle`]s
 //!
 //!
+use std::marker::PhantomData;
+
 use super::reexports::*;
 use crate::foreign_types::*;
 
-/// A simple collection of [`CharacterStyle`]s for commonly used text heights.
-pub struct DefaultStyle<const H: u8>;
+/// A simple collection of [`CharacterStyle`]s for commonly used text heights, in
+/// the given [`CharacterSet`].
+///
+/// The character set defaults to [`Latin1`], which covers ASCII with the same
+/// glyphs, as well as accented letters and symbols such as `°`.
+pub struct DefaultStyle<const H: u8, CS: CharacterSet = Latin1>(PhantomData<CS>);
 
 /// A marker trait for [`CharacterStyle`]s that can be used as a default.
 pub trait ValidStyle {
@@ -14,35 +20,111 @@ pub trait ValidStyle {
         COLOUR: PixelColor + From<<COLOUR as PixelColor>::Raw>;
 }
 
-macro_rules! expand_font_heights {
+/// A marker trait for the character sets of the built-in fonts.
+///
+/// Characters outside of the set are drawn as a placeholder; use a
+/// [`BitmapFont`](super::BitmapFont) for anything else.
+pub trait CharacterSet {
+    /// The name of the character set.
+    fn name() -> &'static str;
+}
+
+macro_rules! expand_character_sets {
     ($((
+        $charset:ident, $name:literal, $description:literal
+    )),*$(,)?) => {
+        $(
+            #[doc = concat!("The ", $name, " character set: ", $description, ".")]
+            pub struct $charset;
+
+            impl CharacterSet for $charset {
+                fn name() -> &'static str {
+                    $name
+                }
+            }
+        )*
+    };
+}
+
+expand_character_sets!(
+    (Ascii, "ASCII", "basic Latin only"),
+    (Latin1, "ISO/IEC 8859-1", "Western European"),
+    (Latin2, "ISO/IEC 8859-2", "Central European"),
+    (Latin3, "ISO/IEC 8859-3", "South European"),
+    (Latin4, "ISO/IEC 8859-4", "North European"),
+    (Cyrillic, "ISO/IEC 8859-5", "Latin and Cyrillic"),
+    (Greek, "ISO/IEC 8859-7", "Latin and Greek"),
+    (Latin5, "ISO/IEC 8859-9", "Turkish"),
+    (Latin6, "ISO/IEC 8859-10", "Nordic"),
+    (Latin7, "ISO/IEC 8859-13", "Baltic"),
+    (Latin8, "ISO/IEC 8859-14", "Celtic"),
+    (
+        Latin9,
+        "ISO/IEC 8859-15",
+        "Western European with the euro sign"
+    ),
+    (Latin10, "ISO/IEC 8859-16", "South-Eastern European"),
+    (JisX0201, "JIS X 0201", "Latin and half-width Katakana"),
+);
+
+macro_rules! expand_font_heights {
+    ($charset:ty, $module:ident: $((
         $height:literal, $font:ident
     )),*$(,)?) => {
         $(
-            impl ValidStyle for DefaultStyle<$height>
+            impl ValidStyle for DefaultStyle<$height, $charset>
             {
                 fn default_style<'s, COLOUR>(colour: COLOUR) -> MonoTextStyle<'s, COLOUR>
                 where
                     COLOUR: PixelColor + From<<COLOUR as PixelColor>::Raw>,
                 {
                     MonoTextStyleBuilder::new()
-                        .font(&$font)
+                        .font(&$module::$font)
                         .text_color(colour)
                         .build()
                 }
             }
         )*
     };
+    ($(($charset:ty, $module:ident)),*$(,)?) => {
+        $(
+            expand_font_heights!(
+                $charset, $module:
+                (6, FONT_4X6),
+                (7, FONT_5X7),
+                (8, FONT_5X8),
+                (9, FONT_6X9),
+                (10, FONT_6X10),
+                (12, FONT_6X12),
+                (13, FONT_7X13),
+                (15, FONT_9X15),
+                (18, FONT_9X18),
+                (20, FONT_10X20),
+            );
+        )*
+    };
 }
 
 expand_font_heights!(
-    (6, FONT_4X6),
-    (7, FONT_5X7),
-    (8, FONT_5X8),
-    (9, FONT_6X9),
-    (10, FONT_6X10),
-    (12, FONT_6X12),
-    (13, FONT_7X13),
+    (Ascii, ascii),
+    (Latin1, iso_8859_1),
+    (Latin2, iso_8859_2),
+    (Latin3, iso_8859_3),
+    (Latin4, iso_8859_4),
+    (Cyrillic, iso_8859_5),
+    (Greek, iso_8859_7),
+    (Latin5, iso_8859_9),
+    (Latin6, iso_8859_10),
+    (Latin7, iso_8859_13),
+    (Latin8, iso_8859_14),
+    (Latin9, iso_8859_15),
+    (Latin10, iso_8859_16),
+);
+
+// JIS X 0201 is only available in the larger sizes.
+expand_font_heights!(
+    JisX0201, jis_x0201:
+    (13, FONT_6X13),
     (15, FONT_9X15),
     (18, FONT_9X18),
     (20, FONT_10X20),
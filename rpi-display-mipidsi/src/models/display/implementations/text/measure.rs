@@ -0,0 +1,308 @@
+//! Measure and paginate text before drawing it.
+//!
+
+use std::{borrow::Cow, ops::Range};
+
+use embedded_graphics::text::Baseline;
+
+use super::reexports::*;
+use crate::foreign_types::*;
+
+/// The number of spaces a tab is measured as.
+const TAB_SPACES: u32 = 4;
+
+/// The size of a piece of text wrapped to a given width.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TextMeasurement {
+    /// The number of lines after wrapping.
+    pub lines: u32,
+
+    /// The width of the widest line, excluding trailing whitespace.
+    pub width: u32,
+
+    /// The height of all the lines, including line and paragraph spacing.
+    pub height: u32,
+}
+
+impl TextMeasurement {
+    /// Check if the text fits in a box of the given size.
+    pub fn fits_in(&self, size: Size) -> bool {
+        self.width <= size.width && self.height <= size.height
+    }
+}
+
+/// A line of text after wrapping.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Line {
+    /// The byte range of the line in the text, excluding trailing whitespace.
+    range: Range<usize>,
+
+    width: u32,
+}
+
+/// Remove ANSI escape sequences, which take no space when drawn.
+fn strip_ansi(text: &str) -> Cow<'_, str> {
+    if !text.contains('\x1b') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut stripped = String::with_capacity(text.len());
+    let mut characters = text.chars();
+    while let Some(character) = characters.next() {
+        if character == '\x1b' {
+            // Skip the control sequence up to and including its final byte.
+            if characters.next() == Some('[') {
+                characters.by_ref().find(|c| ('@'..='~').contains(c));
+            }
+        } else {
+            stripped.push(character);
+        }
+    }
+
+    Cow::Owned(stripped)
+}
+
+/// Measure the width of a piece of text on a single line.
+fn string_width<S>(text: &str, character_style: &S) -> u32
+where
+    S: TextRenderer,
+{
+    character_style
+        .measure_string(&strip_ansi(text), Point::zero(), Baseline::Top)
+        .next_position
+        .x
+        .max(0) as u32
+}
+
+/// Wrap `text` into lines no wider than `max_width`, breaking at whitespace where
+/// possible, and within words that are too long for a line of their own.
+fn wrap<S>(
+    text: &str,
+    character_style: &S,
+    textbox_style: &TextBoxStyle,
+    max_width: u32,
+) -> Vec<Line>
+where
+    S: TextRenderer,
+{
+    let space = string_width(" ", character_style);
+    let whitespace_width = |whitespace: &str| {
+        whitespace
+            .chars()
+            .map(|c| if c == '\t' { space * TAB_SPACES } else { space })
+            .sum::<u32>()
+    };
+
+    let mut lines = Vec::new();
+    let mut paragraph_start = 0;
+
+    for paragraph in text.split('\n') {
+        let offset = paragraph_start;
+        // Step over the whole paragraph, including any `\r` stripped below.
+        paragraph_start += paragraph.len() + 1;
+        let paragraph = paragraph.strip_suffix('\r').unwrap_or(paragraph);
+
+        let mut line: Option<Line> = None;
+        let mut whitespace = 0..0;
+        let mut word_start = None;
+
+        // Walk through the words of the paragraph, with the whitespace before each.
+        let boundaries = paragraph
+            .char_indices()
+            .map(|(position, c)| (position, c == ' ' || c == '\t'))
+            .chain(std::iter::once((paragraph.len(), true)));
+
+        for (position, is_whitespace) in boundaries {
+            match (is_whitespace, word_start) {
+                (false, None) => word_start = Some(position),
+                (true, Some(start)) => {
+                    let gap = whitespace_width(&paragraph[whitespace.clone()]);
+                    let width = string_width(&paragraph[start..position], character_style);
+
+                    let wrapped = match line.as_mut() {
+                        Some(current) if current.width + gap + width <= max_width => {
+                            current.width += gap + width;
+                            current.range.end = offset + position;
+                            None
+                        }
+                        // Whitespace at a line break is dropped.
+                        Some(_) => Some((offset + start..offset + position, width)),
+                        // Whitespace at the start of a paragraph is kept if drawn.
+                        None if textbox_style.leading_spaces => {
+                            Some((offset + whitespace.start..offset + position, gap + width))
+                        }
+                        None => Some((offset + start..offset + position, width)),
+                    };
+
+                    if let Some((mut range, mut width)) = wrapped {
+                        lines.extend(line.take());
+
+                        // A word too long for a line of its own is broken up.
+                        while width > max_width {
+                            let Some(split) = text[range.clone()]
+                                .char_indices()
+                                .skip(1)
+                                .map(|(position, _)| range.start + position)
+                                .take_while(|end| {
+                                    string_width(&text[range.start..*end], character_style)
+                                        <= max_width
+                                })
+                                .last()
+                            else {
+                                break;
+                            };
+
+                            lines.push(Line {
+                                range: range.start..split,
+                                width: string_width(&text[range.start..split], character_style),
+                            });
+                            range.start = split;
+                            width = string_width(&text[range.clone()], character_style);
+                        }
+
+                        line = Some(Line { range, width });
+                    }
+
+                    word_start = None;
+                    whitespace = position..position;
+                }
+                (true, None) => whitespace.end = position + 1,
+                (false, Some(_)) => {}
+            }
+        }
+
+        // Empty paragraphs still take up a line.
+        lines.push(line.unwrap_or(Line {
+            range: offset..offset,
+            width: 0,
+        }));
+    }
+
+    lines
+}
+
+/// Measure `text` wrapped to `max_width` with the given styles, as it would be
+/// drawn by [`draw_raw_text`](crate::LcdDisplay::draw_raw_text).
+///
+/// The height is measured by [`TextBoxStyle`] itself, so it follows the same line
+/// breaks as the drawn text; the number of lines and the widest line break at
+/// whitespace in the same way. ANSI escape sequences are ignored, and tabs are
+/// measured as four spaces.
+pub fn measure_text<S>(
+    text: &str,
+    character_style: &S,
+    textbox_style: &TextBoxStyle,
+    max_width: u32,
+) -> TextMeasurement
+where
+    S: TextRenderer,
+{
+    let lines = wrap(text, character_style, textbox_style, max_width);
+
+    TextMeasurement {
+        lines: lines.len() as u32,
+        width: lines
+            .iter()
+            .map(|line| line.width)
+            .max()
+            .unwrap_or_default(),
+        height: textbox_style.measure_text_height(character_style, text, max_width),
+    }
+}
+
+/// Split `text` into pages that each fit in a box of `size` with the given styles.
+///
+/// Every page is a slice of `text` starting at the beginning of a line, so that
+/// drawing the pages one after another shows all of the text; a single line too
+/// tall for the box still gets a page of its own. Whether a page fits is measured
+/// by [`TextBoxStyle`], so no page is cut off when drawn in a [`TextBox`].
+pub fn paginate<'t, S>(
+    text: &'t str,
+    character_style: &S,
+    textbox_style: &TextBoxStyle,
+    size: Size,
+) -> Vec<&'t str>
+where
+    S: TextRenderer,
+{
+    let fits = |page: &str| {
+        textbox_style.measure_text_height(character_style, page, size.width) <= size.height
+    };
+
+    let mut pages = Vec::new();
+    let mut page: Option<Range<usize>> = None;
+
+    for line in wrap(text, character_style, textbox_style, size.width) {
+        page = match page {
+            Some(range) if fits(&text[range.start..line.range.end]) => {
+                Some(range.start..line.range.end)
+            }
+            Some(range) => {
+                pages.push(&text[range]);
+                Some(line.range)
+            }
+            None => Some(line.range),
+        };
+    }
+
+    pages.extend(page.map(|range| &text[range]));
+
+    #[cfg(feature = "debug")]
+    logger::debug!("Paginated text into {} pages.", pages.len());
+
+    pages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pixelcolor::BinaryColor;
+
+    fn style() -> MonoTextStyle<'static, BinaryColor> {
+        MonoTextStyle::new(&FONT_6X10, BinaryColor::On)
+    }
+
+    fn wrapped<'t>(text: &'t str, max_width: u32) -> Vec<&'t str> {
+        wrap(text, &style(), &TextBoxStyle::default(), max_width)
+            .into_iter()
+            .map(|line| &text[line.range])
+            .collect()
+    }
+
+    #[test]
+    fn wrap_crlf_and_multibyte() {
+        assert_eq!(
+            wrapped("ab\r\ncd\r\nef\r\ngh", 60),
+            vec!["ab", "cd", "ef", "gh"]
+        );
+        assert_eq!(wrapped("a\r\nb°\r\n", 60), vec!["a", "b°", ""]);
+        assert_eq!(wrapped("°°° °°°", 24), vec!["°°°", "°°°"]);
+        assert_eq!(wrapped("abcdefgh", 24), vec!["abcd", "efgh"]);
+    }
+
+    #[test]
+    fn measure_text_crlf() {
+        let measurement = measure_text("ab\r\ncd°\r\n", &style(), &TextBoxStyle::default(), 60);
+
+        assert_eq!(measurement.lines, 3);
+        assert_eq!(measurement.width, 18);
+        assert!(measurement.fits_in(Size::new(18, measurement.height)));
+    }
+
+    #[test]
+    fn paginate_crlf_keeps_all_text() {
+        let textbox_style = TextBoxStyle::default();
+        let size = Size::new(60, 20);
+        let pages = paginate("ab\r\ncd\r\nef\r\ngh", &style(), &textbox_style, size);
+
+        assert_eq!(pages, vec!["ab\r\ncd", "ef\r\ngh"]);
+        assert!(pages.iter().all(|page| {
+            textbox_style.measure_text_height(&style(), page, size.width) <= size.height
+        }));
+
+        assert_eq!(
+            paginate("a\r\nb°", &style(), &textbox_style, Size::new(60, 10)),
+            vec!["a", "b°"]
+        );
+    }
+}
@@ -5,3 +5,19 @@ mod reexports;
 pub use reexports::*;
 
 mod monotype;
+
+mod bitmap;
+pub use bitmap::*;
+
+mod measure;
+pub use measure::*;
+
+#[cfg(feature = "console")]
+mod console;
+#[cfg(feature = "console")]
+pub use console::*;
+
+#[cfg(feature = "ttf")]
+mod ttf;
+#[cfg(feature = "ttf")]
+pub use ttf::*;
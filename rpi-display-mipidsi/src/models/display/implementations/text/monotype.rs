@@ -11,22 +11,19 @@ where
     MODEL::ColorFormat: From<<MODEL::ColorFormat as PixelColor>::Raw>,
 {
     /// Draw a already defined text box on the display.
-    pub fn draw_textbox<'e, 't, S, M>(
-        &mut self,
-        textbox: &TextBox<'t, S, M>,
-    ) -> RPiResult<'e, String>
+    pub async fn draw_textbox<'t, S, M>(&mut self, textbox: TextBox<'t, S, M>) -> RPiResult<String>
     where
         MODEL::ColorFormat: Default,
         S: TextRenderer<Color = MODEL::ColorFormat> + CharacterStyle<Color = MODEL::ColorFormat>,
         M: plugin::PluginMarker<'t, MODEL::ColorFormat>,
     {
-        textbox
-            .draw(&mut self.screen)
-            .into_rpi_result()
+        // The text box is moved into the retried closure, as its plugins are not `Sync`.
+        self.with_recovery(move |display| textbox.draw(&mut display.screen).into_rpi_result())
+            .await
             .and_then(|string| {
                 #[cfg(feature = "debug")]
                 if string.len() > 0 {
-                    logger::trace(&format!("Skipped string: {}", string.trim()));
+                    logger::trace!("Skipped string: {}", string.trim());
                 }
 
                 Ok(string.trim().to_owned())
@@ -34,7 +31,7 @@ where
     }
 
     /// Draw a piece of text on the display.
-    pub fn draw_raw_text<'e, 't, S, M>(
+    pub async fn draw_raw_text<'t, S, M>(
         &mut self,
         text: &'t str,
         character_style: S,
@@ -42,7 +39,7 @@ where
         position: Option<Point>,
         size: Option<Size>,
         plugin: Option<M>,
-    ) -> RPiResult<'e, String>
+    ) -> RPiResult<String>
     where
         MODEL::ColorFormat: Default,
         S: TextRenderer<Color = MODEL::ColorFormat> + CharacterStyle<Color = MODEL::ColorFormat>,
@@ -64,21 +61,21 @@ where
             TextBox::with_textbox_style(text, bounding_box, character_style, textbox_style);
 
         if let Some(plugin) = plugin {
-            self.draw_textbox(&textbox.add_plugin(plugin))
+            self.draw_textbox(textbox.add_plugin(plugin)).await
         } else {
-            self.draw_textbox(&textbox)
+            self.draw_textbox(textbox).await
         }
     }
 
     /// Draw a piece of ANSI text on the display.
-    pub fn draw_ansi_text<'e, 't, S>(
+    pub async fn draw_ansi_text<'t, S>(
         &mut self,
         text: &'t str,
         character_style: S,
         textbox_style: TextBoxStyle,
         position: Option<Point>,
         size: Option<Size>,
-    ) -> RPiResult<'e, String>
+    ) -> RPiResult<String>
     where
         MODEL::ColorFormat: Default + From<pixelcolor::Rgb888>,
         S: TextRenderer<Color = MODEL::ColorFormat> + CharacterStyle<Color = MODEL::ColorFormat>,
@@ -91,16 +88,17 @@ where
             size,
             Some(plugin::ansi::Ansi::new()),
         )
+        .await
     }
 
     /// Draw a piece of ANSI capable text using all the default settings.
-    pub fn draw_text<'e, 't, const FS: u8>(
+    pub async fn draw_text<'t, const FS: u8>(
         &mut self,
         text: &'t str,
         colour: MODEL::ColorFormat,
         position: Option<Point>,
         size: Option<Size>,
-    ) -> RPiResult<'e, String>
+    ) -> RPiResult<String>
     where
         DefaultStyle<FS>: ValidStyle,
         MODEL::ColorFormat: Default + From<pixelcolor::Rgb888>,
@@ -112,16 +110,17 @@ where
             position,
             size,
         )
+        .await
     }
 
     /// Draw a piece of ANSI capable text at the centre of the bounding box.
-    pub fn draw_title<'e, 't, const FS: u8>(
+    pub async fn draw_title<'t, const FS: u8>(
         &mut self,
         text: &'t str,
         colour: MODEL::ColorFormat,
         position: Option<Point>,
         size: Option<Size>,
-    ) -> RPiResult<'e, String>
+    ) -> RPiResult<String>
     where
         DefaultStyle<FS>: ValidStyle,
         MODEL::ColorFormat: Default + From<pixelcolor::Rgb888>,
@@ -138,5 +137,6 @@ where
             position,
             size,
         )
+        .await
     }
 }
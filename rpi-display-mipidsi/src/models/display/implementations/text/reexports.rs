@@ -2,7 +2,11 @@
 //!
 
 pub use embedded_graphics::{
-    mono_font::{ascii::*, MonoFont, MonoTextStyle, MonoTextStyleBuilder},
+    mono_font::{
+        ascii, ascii::*, iso_8859_1, iso_8859_10, iso_8859_13, iso_8859_14, iso_8859_15,
+        iso_8859_16, iso_8859_2, iso_8859_3, iso_8859_4, iso_8859_5, iso_8859_7, iso_8859_9,
+        jis_x0201, MonoFont, MonoTextStyle, MonoTextStyleBuilder,
+    },
     text::renderer::{CharacterStyle, TextRenderer},
 };
 
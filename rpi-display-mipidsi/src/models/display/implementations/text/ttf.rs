@@ -0,0 +1,587 @@
+//! TrueType and OpenType fonts, rasterised at any pixel size.
+//!
+
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use ab_glyph::{Font, FontArc, PxScale, ScaleFont};
+use embedded_graphics::{
+    primitives::{PointsIter, Rectangle},
+    text::{
+        renderer::{CharacterStyle, TextMetrics, TextRenderer},
+        Baseline, DecorationColor,
+    },
+    Pixel,
+};
+
+use crate::{foreign_types::*, func};
+use pixelcolor::Rgb888;
+
+/// Glyphs with a coverage below this are skipped when drawing without a background
+/// colour; anything above is drawn fully opaque.
+pub const COVERAGE_THRESHOLD: u8 = 128;
+
+/// The number of rasterised glyphs kept by a style; the least recently used glyph
+/// is dropped to make room for a new one.
+pub const GLYPH_CACHE_CAPACITY: usize = 256;
+
+/// A TrueType or OpenType font.
+///
+/// This is cheap to clone, as the font data is shared.
+#[derive(Clone, Debug)]
+pub struct TtfFont(FontArc);
+
+impl TtfFont {
+    /// Load a font from the bytes of a TTF or OTF file.
+    pub fn from_bytes(bytes: Vec<u8>) -> RPiResult<Self> {
+        FontArc::try_from_vec(bytes).map(Self).into_rpi_result()
+    }
+
+    /// Load a font from a TTF or OTF file.
+    pub async fn from_path(path: impl AsRef<Path> + std::fmt::Debug) -> RPiResult<Self> {
+        func::fs::read_bytes_from_file(path)
+            .await
+            .and_then(Self::from_bytes)
+    }
+
+    /// Create a [`TtfTextStyle`] drawing this font at the given pixel size.
+    pub fn style<COLOUR>(&self, size: f32, text_color: COLOUR) -> TtfTextStyle<COLOUR>
+    where
+        COLOUR: PixelColor,
+    {
+        TtfTextStyle::new(self.clone(), size, text_color)
+    }
+}
+
+/// The coverage of a rasterised glyph.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct CachedGlyph {
+    /// The top left corner of the coverage, relative to the pen position on the
+    /// baseline.
+    offset: Point,
+    size: Size,
+    coverage: Vec<u8>,
+}
+
+/// Glyphs already rasterised at the size of a style, with when each was last used;
+/// [`None`] for characters without an outline, such as spaces.
+///
+/// At most [`GLYPH_CACHE_CAPACITY`] glyphs are kept, so that text in a large
+/// script cannot grow the cache without bound.
+#[derive(Debug, Default)]
+struct GlyphCache {
+    glyphs: HashMap<char, (Option<Arc<CachedGlyph>>, u64)>,
+
+    /// Incremented on every lookup, to order the glyphs by when they were used.
+    uses: u64,
+}
+
+impl GlyphCache {
+    /// Get the glyph of a character, rasterising it with `rasterise` if it is not
+    /// cached yet; the least recently used glyph is dropped if the cache is full.
+    fn get_or_insert_with<F>(&mut self, character: char, rasterise: F) -> Option<Arc<CachedGlyph>>
+    where
+        F: FnOnce() -> Option<Arc<CachedGlyph>>,
+    {
+        self.uses += 1;
+
+        if let Some((glyph, used)) = self.glyphs.get_mut(&character) {
+            *used = self.uses;
+            return glyph.clone();
+        }
+
+        if self.glyphs.len() >= GLYPH_CACHE_CAPACITY {
+            let oldest = self
+                .glyphs
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(character, _)| *character);
+
+            if let Some(oldest) = oldest {
+                self.glyphs.remove(&oldest);
+            }
+        }
+
+        let glyph = rasterise();
+        self.glyphs.insert(character, (glyph.clone(), self.uses));
+
+        glyph
+    }
+}
+
+/// A character style for a [`TtfFont`], implementing [`TextRenderer`] and
+/// [`CharacterStyle`] so that it can be used anywhere a [`MonoTextStyle`] can,
+/// including a [`TextBox`].
+///
+/// With a background colour, glyphs are anti-aliased against it and their whole
+/// line height is filled, with the coverage of overlapping glyphs combined so that
+/// a glyph never erases its neighbours; without one, the display cannot be read
+/// back, so every pixel is either skipped or drawn fully opaque according to
+/// [`COVERAGE_THRESHOLD`].
+///
+/// Up to [`GLYPH_CACHE_CAPACITY`] rasterised glyphs are cached, and the cache is
+/// shared between clones of the style; changing the colours keeps the cache, but a
+/// different size needs a new style.
+///
+/// [`MonoTextStyle`]: super::MonoTextStyle
+/// [`TextBox`]: super::TextBox
+#[derive(Clone, Debug)]
+pub struct TtfTextStyle<COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    font: TtfFont,
+    scale: PxScale,
+
+    pub text_color: Option<COLOUR>,
+    pub background_color: Option<COLOUR>,
+    pub underline_color: DecorationColor<COLOUR>,
+    pub strikethrough_color: DecorationColor<COLOUR>,
+
+    cache: Arc<Mutex<GlyphCache>>,
+}
+
+impl<COLOUR> TtfTextStyle<COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    /// Create a new style drawing `font` at the given pixel size.
+    pub fn new(font: TtfFont, size: f32, text_color: COLOUR) -> Self {
+        Self {
+            font,
+            scale: PxScale::from(size),
+            text_color: Some(text_color),
+            background_color: None,
+            underline_color: DecorationColor::None,
+            strikethrough_color: DecorationColor::None,
+            cache: Arc::new(Mutex::new(GlyphCache::default())),
+        }
+    }
+
+    /// Set the background colour, which glyphs are anti-aliased against.
+    pub fn with_background(mut self, colour: COLOUR) -> Self {
+        self.background_color = Some(colour);
+        self
+    }
+
+    /// Get the font of the style.
+    pub fn font(&self) -> &TtfFont {
+        &self.font
+    }
+
+    /// Get the pixel size of the style.
+    pub fn size(&self) -> f32 {
+        self.scale.y
+    }
+
+    /// The distance from the top of the line to the baseline, in pixels.
+    fn ascent(&self) -> i32 {
+        self.font.0.as_scaled(self.scale).ascent().round() as i32
+    }
+
+    /// The distance from the baseline to the bottom of the line, in pixels.
+    fn descent(&self) -> i32 {
+        -self.font.0.as_scaled(self.scale).descent().round() as i32
+    }
+
+    /// Get the y coordinate of the baseline for text positioned at `y`.
+    fn baseline_y(&self, y: i32, baseline: Baseline) -> i32 {
+        let height = self.ascent() + self.descent();
+
+        match baseline {
+            Baseline::Top => y + self.ascent(),
+            Baseline::Bottom => y - (height - 1) + self.ascent(),
+            Baseline::Middle => y - (height - 1) / 2 + self.ascent(),
+            Baseline::Alphabetic => y,
+        }
+    }
+
+    /// Get the rasterised glyph of a character, from the cache if possible.
+    fn glyph(&self, character: char) -> Option<Arc<CachedGlyph>> {
+        let mut cache = self
+            .cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        cache.get_or_insert_with(character, || {
+            let glyph = self.font.0.glyph_id(character).with_scale(self.scale);
+            let outline = self.font.0.outline_glyph(glyph)?;
+            let bounds = outline.px_bounds();
+
+            let size = Size::new(bounds.width() as u32, bounds.height() as u32);
+            let mut coverage = vec![0; (size.width * size.height) as usize];
+            outline.draw(|x, y, value| {
+                if let Some(pixel) = coverage.get_mut((y * size.width + x) as usize) {
+                    *pixel = (value.clamp(0., 1.) * u8::MAX as f32).round() as u8;
+                }
+            });
+
+            Some(Arc::new(CachedGlyph {
+                offset: Point::new(bounds.min.x as i32, bounds.min.y as i32),
+                size,
+                coverage,
+            }))
+        })
+    }
+
+    /// Get the coverage of every pixel of the glyphs laid out by
+    /// [`layout`](Self::layout), with the pen starting at `pen` on the baseline.
+    fn coverage<'a>(
+        &'a self,
+        positions: &'a [(char, i32)],
+        pen: Point,
+    ) -> impl Iterator<Item = (Point, u8)> + 'a {
+        positions
+            .iter()
+            .filter_map(move |&(character, x)| {
+                self.glyph(character)
+                    .map(|glyph| (glyph, pen + Point::new(x, 0)))
+            })
+            .flat_map(|(glyph, pen)| {
+                Rectangle::new(pen + glyph.offset, glyph.size)
+                    .points()
+                    .enumerate()
+                    .map(move |(index, point)| (point, glyph.coverage[index]))
+            })
+    }
+
+    /// Get the pen positions of each character of `text` relative to the start,
+    /// and the total advance width, including kerning.
+    fn layout(&self, text: &str) -> (Vec<(char, i32)>, u32) {
+        let font = self.font.0.as_scaled(self.scale);
+        let mut pen = 0_f32;
+        let mut previous = None;
+
+        let positions = text
+            .chars()
+            .map(|character| {
+                let id = font.glyph_id(character);
+                if let Some(previous) = previous {
+                    pen += font.kern(previous, id);
+                }
+                previous = Some(id);
+
+                let position = pen.round() as i32;
+                pen += font.h_advance(id);
+
+                (character, position)
+            })
+            .collect();
+
+        (positions, pen.round().max(0.) as u32)
+    }
+
+    /// Draw a horizontal line for a text decoration across `width`.
+    fn draw_decoration<D>(
+        &self,
+        decoration: DecorationColor<COLOUR>,
+        x: i32,
+        y: i32,
+        width: u32,
+        target: &mut D,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = COLOUR>,
+    {
+        let colour = match decoration {
+            DecorationColor::None => return Ok(()),
+            DecorationColor::TextColor => match self.text_color {
+                Some(colour) => colour,
+                None => return Ok(()),
+            },
+            DecorationColor::Custom(colour) => colour,
+        };
+        let thickness = (self.scale.y / 16.).round().max(1.) as u32;
+
+        target.fill_solid(
+            &Rectangle::new(Point::new(x, y), Size::new(width, thickness)),
+            colour,
+        )
+    }
+}
+
+/// Draw a run of glyphs anti-aliased against a background, from the coverage of
+/// their pixels.
+///
+/// Glyphs can overlap, such as with kerning or italics, so the coverage of each
+/// pixel is combined before the whole run is drawn in one go, background included,
+/// which is also faster over SPI than drawing each pixel; the parts of glyphs that
+/// reach outside of the run are drawn pixel by pixel.
+fn draw_run<COLOUR, D>(
+    run: &Rectangle,
+    coverage: impl IntoIterator<Item = (Point, u8)>,
+    text_color: COLOUR,
+    background: COLOUR,
+    target: &mut D,
+) -> Result<(), D::Error>
+where
+    COLOUR: PixelColor + From<Rgb888> + Into<Rgb888>,
+    D: DrawTarget<Color = COLOUR>,
+{
+    let mut combined = vec![0; (run.size.width * run.size.height) as usize];
+    let mut outside = Vec::new();
+
+    for (point, value) in coverage.into_iter().filter(|(_, value)| *value > 0) {
+        if run.contains(point) {
+            let offset = point - run.top_left;
+            let pixel =
+                &mut combined[(offset.y as u32 * run.size.width + offset.x as u32) as usize];
+            *pixel = (*pixel).max(value);
+        } else {
+            outside.push(Pixel(
+                point,
+                func::blending::blend_colour(text_color, background, value),
+            ));
+        }
+    }
+
+    target.fill_contiguous(
+        run,
+        combined
+            .iter()
+            .map(|value| func::blending::blend_colour(text_color, background, *value)),
+    )?;
+    target.draw_iter(outside)
+}
+
+impl<COLOUR> TextRenderer for TtfTextStyle<COLOUR>
+where
+    COLOUR: PixelColor + From<Rgb888> + Into<Rgb888>,
+{
+    type Color = COLOUR;
+
+    fn draw_string<D>(
+        &self,
+        text: &str,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let (positions, width) = self.layout(text);
+        let baseline_y = self.baseline_y(position.y, baseline);
+        let top = baseline_y - self.ascent();
+
+        let run = Rectangle::new(
+            Point::new(position.x, top),
+            Size::new(width, self.line_height()),
+        );
+        let pen = Point::new(position.x, baseline_y);
+
+        match (self.text_color, self.background_color) {
+            (Some(text_color), Some(background)) => draw_run(
+                &run,
+                self.coverage(&positions, pen),
+                text_color,
+                background,
+                target,
+            )?,
+            (Some(text_color), None) => target.draw_iter(
+                self.coverage(&positions, pen)
+                    .filter(|(_, coverage)| *coverage >= COVERAGE_THRESHOLD)
+                    .map(|(point, _)| Pixel(point, text_color)),
+            )?,
+            (None, Some(background)) => target.fill_solid(&run, background)?,
+            (None, None) => {}
+        }
+
+        self.draw_decoration(
+            self.underline_color,
+            position.x,
+            baseline_y + (self.descent() / 2).max(1),
+            width,
+            target,
+        )?;
+        self.draw_decoration(
+            self.strikethrough_color,
+            position.x,
+            baseline_y - self.ascent() / 3,
+            width,
+            target,
+        )?;
+
+        Ok(position + Point::new(width as i32, 0))
+    }
+
+    fn draw_whitespace<D>(
+        &self,
+        width: u32,
+        position: Point,
+        baseline: Baseline,
+        target: &mut D,
+    ) -> Result<Point, D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        let baseline_y = self.baseline_y(position.y, baseline);
+
+        if let Some(background) = self.background_color {
+            target.fill_solid(
+                &Rectangle::new(
+                    Point::new(position.x, baseline_y - self.ascent()),
+                    Size::new(width, self.line_height()),
+                ),
+                background,
+            )?;
+        }
+
+        self.draw_decoration(
+            self.underline_color,
+            position.x,
+            baseline_y + (self.descent() / 2).max(1),
+            width,
+            target,
+        )?;
+        self.draw_decoration(
+            self.strikethrough_color,
+            position.x,
+            baseline_y - self.ascent() / 3,
+            width,
+            target,
+        )?;
+
+        Ok(position + Point::new(width as i32, 0))
+    }
+
+    fn measure_string(&self, text: &str, position: Point, baseline: Baseline) -> TextMetrics {
+        let (_, width) = self.layout(text);
+        let top = self.baseline_y(position.y, baseline) - self.ascent();
+
+        TextMetrics {
+            bounding_box: Rectangle::new(
+                Point::new(position.x, top),
+                Size::new(width, self.line_height()),
+            ),
+            next_position: position + Point::new(width as i32, 0),
+        }
+    }
+
+    fn line_height(&self) -> u32 {
+        let font = self.font.0.as_scaled(self.scale);
+
+        (font.ascent() - font.descent() + font.line_gap())
+            .ceil()
+            .max(1.) as u32
+    }
+}
+
+impl<COLOUR> CharacterStyle for TtfTextStyle<COLOUR>
+where
+    COLOUR: PixelColor + From<Rgb888> + Into<Rgb888>,
+{
+    type Color = COLOUR;
+
+    fn set_text_color(&mut self, text_color: Option<Self::Color>) {
+        self.text_color = text_color;
+    }
+
+    fn set_background_color(&mut self, background_color: Option<Self::Color>) {
+        self.background_color = background_color;
+    }
+
+    fn set_underline_color(&mut self, underline_color: DecorationColor<Self::Color>) {
+        self.underline_color = underline_color;
+    }
+
+    fn set_strikethrough_color(&mut self, strikethrough_color: DecorationColor<Self::Color>) {
+        self.strikethrough_color = strikethrough_color;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::images::RasterImage;
+
+    fn glyph(coverage: u8) -> Option<Arc<CachedGlyph>> {
+        Some(Arc::new(CachedGlyph {
+            offset: Point::zero(),
+            size: Size::new(1, 1),
+            coverage: vec![coverage],
+        }))
+    }
+
+    #[test]
+    fn cached_glyphs_are_not_rasterised_again() {
+        let mut cache = GlyphCache::default();
+
+        assert_eq!(cache.get_or_insert_with('a', || glyph(1)), glyph(1));
+        assert_eq!(
+            cache.get_or_insert_with('a', || panic!("rasterised again")),
+            glyph(1)
+        );
+        assert_eq!(cache.get_or_insert_with(' ', || None), None);
+        assert_eq!(
+            cache.get_or_insert_with(' ', || panic!("rasterised again")),
+            None
+        );
+    }
+
+    #[test]
+    fn least_recently_used_glyphs_are_dropped() {
+        let mut cache = GlyphCache::default();
+        let characters =
+            || (0..GLYPH_CACHE_CAPACITY as u32).filter_map(|code| char::from_u32(0x4e00 + code));
+
+        for character in characters() {
+            cache.get_or_insert_with(character, || glyph(1));
+        }
+        // Use the first glyph again, so that the second is the least recently used.
+        cache.get_or_insert_with('\u{4e00}', || panic!("rasterised again"));
+        cache.get_or_insert_with('a', || glyph(2));
+
+        assert_eq!(cache.glyphs.len(), GLYPH_CACHE_CAPACITY);
+        assert!(cache.glyphs.contains_key(&'\u{4e00}'));
+        assert!(!cache.glyphs.contains_key(&'\u{4e01}'));
+        assert!(cache.glyphs.contains_key(&'a'));
+    }
+
+    #[test]
+    fn overlapping_glyphs_keep_each_other() {
+        let run = Rectangle::new(Point::new(1, 1), Size::new(3, 1));
+        let mut target = RasterImage::filled(Size::new(5, 3), Rgb888::RED);
+
+        // Two glyphs overlapping at x = 2, the second one blank there.
+        let coverage = [
+            (Point::new(1, 1), 255),
+            (Point::new(2, 1), 255),
+            (Point::new(2, 1), 0),
+            (Point::new(3, 1), 128),
+        ];
+        draw_run(&run, coverage, Rgb888::WHITE, Rgb888::BLACK, &mut target).unwrap();
+
+        let row: Vec<_> = (0..5)
+            .map(|x| target.pixel(Point::new(x, 1)).unwrap())
+            .collect();
+        assert_eq!(
+            row,
+            [
+                Rgb888::RED,
+                Rgb888::WHITE,
+                Rgb888::WHITE,
+                Rgb888::new(128, 128, 128),
+                Rgb888::RED
+            ]
+        );
+    }
+
+    #[test]
+    fn glyphs_outside_of_the_run_are_drawn_without_background() {
+        let run = Rectangle::new(Point::new(0, 1), Size::new(2, 1));
+        let mut target = RasterImage::filled(Size::new(2, 3), Rgb888::RED);
+
+        // An accent reaching above the line.
+        let coverage = [(Point::new(1, 0), 255), (Point::new(0, 0), 0)];
+        draw_run(&run, coverage, Rgb888::WHITE, Rgb888::BLACK, &mut target).unwrap();
+
+        assert_eq!(target.pixel(Point::new(0, 0)), Some(Rgb888::RED));
+        assert_eq!(target.pixel(Point::new(1, 0)), Some(Rgb888::WHITE));
+        assert_eq!(target.pixel(Point::new(0, 1)), Some(Rgb888::BLACK));
+        assert_eq!(target.pixel(Point::new(1, 2)), Some(Rgb888::RED));
+    }
+}
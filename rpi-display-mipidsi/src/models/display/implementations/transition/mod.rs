@@ -1,7 +1,13 @@
 //! Implementations of the `Transition` struct.
 //!
 
-use crate::{foreign_types::*, func::transitions, traits::DrawTransition, LcdDisplay};
+use crate::{
+    foreign_types::*,
+    func::transitions::{Timeline, Transition, TransitionStats},
+    record_success, recover_screen,
+    traits::DrawTransition,
+    LcdDisplay,
+};
 use std::time::Duration;
 
 impl<DI, MODEL, RST, const W: u16, const H: u16> LcdDisplay<DI, MODEL, RST, W, H>
@@ -11,8 +17,35 @@ where
     RST: OutputPinType,
     MODEL::ColorFormat: From<<MODEL::ColorFormat as PixelColor>::Raw>,
 {
+    /// Prepare a transition from one image to another using the supplied
+    /// [`DrawTransition`], and the given steps and duration, without starting it.
+    ///
+    /// This allows progress to be reported with [`Transition::with_progress`], and
+    /// the transition to be cancelled with [`Transition::start_until`]; unlike
+    /// [`draw_transition`](Self::draw_transition), failed frames are not recovered.
+    pub fn transition<'a, 'e, T1, T2, F>(
+        &'a mut self,
+        from: &'e T1,
+        to: &'e T2,
+        transition: F,
+        steps: u32,
+        duration: Duration,
+    ) -> Transition<'e, MODEL::ColorFormat, T1, T2, F, mipidsi::Display<DI, MODEL, RST>>
+    where
+        'a: 'e,
+        T1: ImageDrawable<Color = MODEL::ColorFormat> + 'e,
+        T2: ImageDrawable<Color = MODEL::ColorFormat> + 'e,
+        F: DrawTransition<'e, MODEL::ColorFormat, T1, T2, mipidsi::Display<DI, MODEL, RST>>,
+    {
+        Transition::new(&mut self.screen, from, to, transition, steps, duration)
+    }
+
     /// Transition from one image to another using the supplied
     /// [`DrawTransition`], and the given steps and duration.
+    ///
+    /// Transient errors are recovered according to the
+    /// [`RecoveryPolicy`](crate::RecoveryPolicy); the transition then carries on
+    /// from where its clock is, skipping the frames missed.
     pub async fn draw_transition<'a, 'e, T1, T2, F>(
         &'a mut self,
         from: &'e T1,
@@ -20,7 +53,7 @@ where
         transition: F,
         steps: u32,
         duration: Duration,
-    ) -> RPiResult<'e, ()>
+    ) -> RPiResult<TransitionStats>
     where
         'a: 'e,
         MODEL::ColorFormat: Default,
@@ -28,10 +61,32 @@ where
         T2: ImageDrawable<Color = MODEL::ColorFormat> + 'e,
         F: DrawTransition<'e, MODEL::ColorFormat, T1, T2, mipidsi::Display<DI, MODEL, RST>>,
     {
-        let mut handler =
-            transitions::Transition::new(&mut self.screen, from, to, transition, steps, duration);
+        let mut transition =
+            Transition::new(&mut self.screen, from, to, transition, steps, duration);
+        let mut retries = 0;
 
-        handler.start().await
+        loop {
+            match transition.start().await {
+                Ok(stats) => {
+                    record_success(&mut self.recovery, retries);
+                    return Ok(stats);
+                }
+                Err(err) => {
+                    if !recover_screen(
+                        &mut self.recovery,
+                        transition.target_mut(),
+                        self.tearing_effect,
+                        &err,
+                        retries,
+                    )
+                    .await
+                    {
+                        return Err(err);
+                    }
+                    retries += 1;
+                }
+            }
+        }
     }
 
     /// Transition to a new image using the supplied [`DrawTransition`], and the
@@ -42,7 +97,7 @@ where
         transition: F,
         steps: u32,
         duration: Duration,
-    ) -> RPiResult<'e, ()>
+    ) -> RPiResult<TransitionStats>
     where
         'a: 'e,
         MODEL::ColorFormat: Default,
@@ -52,4 +107,32 @@ where
         self.draw_transition(frame, frame, transition, steps, duration)
             .await
     }
+
+    /// Play a [`Timeline`] of transitions in real time, skipping frames if it
+    /// cannot keep up.
+    ///
+    /// Transient errors are recovered according to the
+    /// [`RecoveryPolicy`](crate::RecoveryPolicy), playing the timeline again from
+    /// the start. A timeline that plays forever never returns unless it fails.
+    pub async fn play_timeline<'e>(
+        &mut self,
+        timeline: &mut Timeline<'e, mipidsi::Display<DI, MODEL, RST>>,
+    ) -> RPiResult<()> {
+        let mut retries = 0;
+
+        loop {
+            match timeline.play(&mut self.screen).await {
+                Ok(()) => {
+                    record_success(&mut self.recovery, retries);
+                    return Ok(());
+                }
+                Err(err) => {
+                    if !self.recover_after(&err, retries).await {
+                        return Err(err);
+                    }
+                    retries += 1;
+                }
+            }
+        }
+    }
 }
@@ -3,5 +3,9 @@ pub use base::*;
 
 mod implementations;
 
+mod recovery;
+pub(crate) use recovery::{record_success, recover_screen};
+pub use recovery::{RecoveryAction, RecoveryPolicy, RecoveryState, RecoveryStats};
+
 #[cfg(feature = "text")]
 pub use implementations::*;
@@ -0,0 +1,530 @@
+//! Recovery from transient errors while drawing to a [`LcdDisplay`].
+//!
+//! A hiccup on the SPI bus should not take down a whole user interface; instead
+//! the failed operation is retried with an exponential backoff, and if the
+//! failures keep coming, the panel is soft reset before trying again.
+
+use std::time::Duration;
+
+use crate::foreign_types::*;
+use crate::LcdDisplay;
+
+/// How a [`LcdDisplay`] recovers from transient errors, as reported by
+/// [`RPiError::is_transient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecoveryPolicy {
+    /// How many times a failed operation is retried before giving up.
+    pub max_retries: u32,
+
+    /// The wait before the first retry; each further retry waits twice as long.
+    pub initial_backoff: Duration,
+
+    /// The longest wait between two retries.
+    pub max_backoff: Duration,
+
+    /// Soft reset the panel after this many consecutive failures; `0` never resets.
+    pub reset_after: u32,
+}
+
+impl Default for RecoveryPolicy {
+    /// Retry 3 times starting at 5ms, and soft reset after 2 consecutive failures.
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(5),
+            max_backoff: Duration::from_millis(100),
+            reset_after: 2,
+        }
+    }
+}
+
+impl RecoveryPolicy {
+    /// A policy that never retries nor resets; every error is returned as is.
+    pub fn disabled() -> Self {
+        Self {
+            max_retries: 0,
+            reset_after: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Set the number of retries.
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the first and the longest wait between retries.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Set the number of consecutive failures before a soft reset; `0` never resets.
+    pub fn with_reset_after(mut self, reset_after: u32) -> Self {
+        self.reset_after = reset_after;
+        self
+    }
+
+    /// The wait before the given retry, counting from `1`.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1_u32
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+
+    /// Whether the panel should be soft reset after this many consecutive failures.
+    pub fn should_reset(&self, consecutive_failures: u32) -> bool {
+        self.reset_after > 0 && consecutive_failures >= self.reset_after
+    }
+}
+
+/// Counters of the recovery events of a [`LcdDisplay`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecoveryStats {
+    /// Operations retried after a transient error.
+    pub retries: u64,
+
+    /// Operations that succeeded after at least one retry.
+    pub recovered: u64,
+
+    /// Soft resets of the panel, successful or not.
+    pub soft_resets: u64,
+
+    /// Operations that failed despite the recovery, and were returned as errors.
+    pub failures: u64,
+}
+
+/// What to do after an operation on the display failed, as decided by a
+/// [`RecoveryState`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryAction {
+    /// Wait for the backoff, then retry the operation.
+    Retry(Duration),
+
+    /// Soft reset the panel, then retry the operation.
+    Reset,
+
+    /// Return the error.
+    GiveUp,
+}
+
+/// The recovery state of a display: its [`RecoveryPolicy`], the failures since the
+/// last success, and the [`RecoveryStats`] so far.
+///
+/// This only decides what to do after each failure; the waits and soft resets are
+/// carried out by the [`LcdDisplay`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecoveryState {
+    policy: RecoveryPolicy,
+    stats: RecoveryStats,
+
+    /// Failures since the last successful operation or soft reset.
+    consecutive_failures: u32,
+}
+
+impl RecoveryState {
+    /// Create a new state following `policy`.
+    pub fn new(policy: RecoveryPolicy) -> Self {
+        Self {
+            policy,
+            ..Default::default()
+        }
+    }
+
+    /// Get the [`RecoveryPolicy`] followed.
+    pub fn policy(&self) -> &RecoveryPolicy {
+        &self.policy
+    }
+
+    /// Replace the [`RecoveryPolicy`] followed.
+    pub fn set_policy(&mut self, policy: RecoveryPolicy) {
+        self.policy = policy;
+    }
+
+    /// Get the counters of the recovery events so far.
+    pub fn stats(&self) -> RecoveryStats {
+        self.stats
+    }
+
+    /// Reset the counters of the recovery events to zero.
+    pub fn reset_stats(&mut self) {
+        self.stats = RecoveryStats::default();
+    }
+
+    /// Get the number of failures since the last successful operation or soft reset.
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive_failures
+    }
+
+    /// Record that an operation succeeded after `retries` retries.
+    pub fn on_success(&mut self, retries: u32) {
+        if retries > 0 {
+            self.stats.recovered += 1;
+        }
+
+        self.consecutive_failures = 0;
+    }
+
+    /// Decide what to do after an operation failed with `error`, having already
+    /// been retried `retries` times.
+    ///
+    /// Errors that are not [transient](RPiError::is_transient) are given up on
+    /// straight away, and so is any error after [`RecoveryPolicy::max_retries`]
+    /// retries. Otherwise, the panel is reset once [`RecoveryPolicy::reset_after`]
+    /// failures have happened in a row, across operations; or the operation is
+    /// retried after the [`backoff`](RecoveryPolicy::backoff).
+    pub fn on_failure(&mut self, error: &RPiError, retries: u32) -> RecoveryAction {
+        if !error.is_transient() {
+            self.stats.failures += 1;
+            return RecoveryAction::GiveUp;
+        }
+
+        self.consecutive_failures += 1;
+
+        if retries >= self.policy.max_retries {
+            self.stats.failures += 1;
+            return RecoveryAction::GiveUp;
+        }
+
+        self.stats.retries += 1;
+
+        if self.policy.should_reset(self.consecutive_failures) {
+            RecoveryAction::Reset
+        } else {
+            RecoveryAction::Retry(self.policy.backoff(retries + 1))
+        }
+    }
+
+    /// Record that the panel has been soft reset.
+    pub fn on_reset(&mut self) {
+        self.stats.soft_resets += 1;
+        self.consecutive_failures = 0;
+    }
+}
+
+/// A [`DelayUs`] that adds up the delays asked for instead of blocking, so that
+/// they can be awaited.
+#[derive(Default)]
+struct DeferredDelay(Duration);
+
+impl DelayUs<u32> for DeferredDelay {
+    fn delay_us(&mut self, us: u32) {
+        self.0 += Duration::from_micros(us.into());
+    }
+}
+
+impl DeferredDelay {
+    /// Wait for all the delays asked for so far.
+    async fn wait(&mut self) {
+        tokio::time::sleep(std::mem::take(&mut self.0)).await;
+    }
+}
+
+/// Soft reset the panel without touching the reset pin or blocking the thread.
+///
+/// The panel is put to sleep and woken up again, which re-runs its power-on
+/// sequence; the orientation and tearing effect are then written again, in case
+/// a corrupted command has changed them.
+async fn soft_reset_screen<DI, MODEL, RST>(
+    screen: &mut RawDisplay<DI, MODEL, RST>,
+    tearing_effect: TearingEffect,
+) -> RPiResult<()>
+where
+    DI: WriteOnlyDataCommand,
+    MODEL: DisplayModel,
+    RST: OutputPinType,
+{
+    let mut delay = DeferredDelay::default();
+    let orientation = screen.orientation();
+
+    screen.sleep(&mut delay).into_rpi_result()?;
+    delay.wait().await;
+    screen.wake(&mut delay).into_rpi_result()?;
+    delay.wait().await;
+
+    screen.set_orientation(orientation).into_rpi_result()?;
+
+    if tearing_effect != TearingEffect::Off {
+        screen
+            .set_tearing_effect(tearing_effect)
+            .into_rpi_result()?;
+    }
+
+    Ok(())
+}
+
+/// Record that an operation on the display succeeded after `retries` retries.
+pub(crate) fn record_success(state: &mut RecoveryState, retries: u32) {
+    state.on_success(retries);
+
+    if retries > 0 {
+        logger::info!("Display recovered after {} retries.", retries; retries = retries);
+    }
+}
+
+/// Recover `screen` from `error` after `retries` retries, as `state` decides:
+/// wait for the backoff or soft reset the panel and return `true` to retry the
+/// operation, or return `false` to give up on it.
+pub(crate) async fn recover_screen<DI, MODEL, RST>(
+    state: &mut RecoveryState,
+    screen: &mut RawDisplay<DI, MODEL, RST>,
+    tearing_effect: TearingEffect,
+    error: &RPiError,
+    retries: u32,
+) -> bool
+where
+    DI: WriteOnlyDataCommand,
+    MODEL: DisplayModel,
+    RST: OutputPinType,
+{
+    match state.on_failure(error, retries) {
+        RecoveryAction::Retry(backoff) => {
+            logger::warning!(
+                "Transient display error, retrying in {:?}: {}", backoff, error.report();
+                code = error.code(),
+                attempt = retries + 1,
+                consecutive_failures = state.consecutive_failures()
+            );
+
+            tokio::time::sleep(backoff).await;
+            true
+        }
+        RecoveryAction::Reset => {
+            logger::warning!(
+                "Repeated display errors, soft resetting the panel: {}", error.report();
+                code = error.code(),
+                attempt = retries + 1,
+                consecutive_failures = state.consecutive_failures()
+            );
+
+            state.on_reset();
+            if let Err(reset_error) = soft_reset_screen(screen, tearing_effect).await {
+                // Retry regardless; if the panel is still failing, the retries run out.
+                logger::error!("Failed to soft reset the display: {}", reset_error.report());
+            }
+            true
+        }
+        RecoveryAction::GiveUp => {
+            if error.is_transient() {
+                logger::error!(
+                    "Display failed after {} retries: {}", retries, error.report();
+                    code = error.code(),
+                    consecutive_failures = state.consecutive_failures()
+                );
+            }
+            false
+        }
+    }
+}
+
+impl<DI, MODEL, RST, const W: u16, const H: u16> LcdDisplay<DI, MODEL, RST, W, H>
+where
+    DI: WriteOnlyDataCommand,
+    MODEL: DisplayModel,
+    RST: OutputPinType,
+{
+    /// Replace the [`RecoveryPolicy`] of this display.
+    pub fn with_recovery_policy(mut self, policy: RecoveryPolicy) -> Self {
+        self.set_recovery_policy(policy);
+        self
+    }
+
+    /// Get the [`RecoveryPolicy`] of this display.
+    pub fn recovery_policy(&self) -> &RecoveryPolicy {
+        self.recovery.policy()
+    }
+
+    /// Replace the [`RecoveryPolicy`] of this display.
+    pub fn set_recovery_policy(&mut self, policy: RecoveryPolicy) {
+        self.recovery.set_policy(policy);
+    }
+
+    /// Get the counters of the recovery events so far.
+    pub fn recovery_stats(&self) -> RecoveryStats {
+        self.recovery.stats()
+    }
+
+    /// Reset the counters of the recovery events to zero.
+    pub fn reset_recovery_stats(&mut self) {
+        self.recovery.reset_stats();
+    }
+
+    /// Soft reset the panel without touching the reset pin.
+    ///
+    /// The panel is put to sleep and woken up again, which re-runs its power-on
+    /// sequence; the orientation and tearing effect are then written again, in case
+    /// a corrupted command has changed them.
+    pub async fn soft_reset(&mut self) -> RPiResult<()> {
+        logger::warning!("Soft resetting the display...");
+
+        self.recovery.on_reset();
+        soft_reset_screen(&mut self.screen, self.tearing_effect).await
+    }
+
+    /// Run an operation on the display, recovering from transient errors according
+    /// to the [`RecoveryPolicy`]; all the `draw_*` methods go through this.
+    ///
+    /// The whole operation is retried, so it should redraw everything it needs to.
+    /// Errors that are not transient are returned straight away.
+    pub async fn with_recovery<T>(
+        &mut self,
+        mut operation: impl FnMut(&mut Self) -> RPiResult<T>,
+    ) -> RPiResult<T> {
+        let mut retries = 0;
+
+        loop {
+            match operation(self) {
+                Ok(value) => {
+                    record_success(&mut self.recovery, retries);
+                    return Ok(value);
+                }
+                Err(err) => {
+                    if !self.recover_after(&err, retries).await {
+                        return Err(err);
+                    }
+                    retries += 1;
+                }
+            }
+        }
+    }
+
+    /// Recover from `error` after `retries` retries of an operation, returning
+    /// whether to retry it.
+    pub(crate) async fn recover_after(&mut self, error: &RPiError, retries: u32) -> bool {
+        recover_screen(
+            &mut self.recovery,
+            &mut self.screen,
+            self.tearing_effect,
+            error,
+            retries,
+        )
+        .await
+    }
+
+    /// Try to recover the display after `error` ended an operation outside of
+    /// [`with_recovery`](Self::with_recovery), such as a whole user interface,
+    /// counting `attempt` from `1`.
+    ///
+    /// The same [`RecoveryPolicy`] applies: returns `true` after waiting for the
+    /// backoff or soft resetting the panel if the operation should be run again,
+    /// or `false` if the error should be returned instead.
+    ///
+    /// Such an operation may also wait on buttons or read files, so only errors
+    /// [from the display](RPiError::is_display_transient) are recovered; any other
+    /// error, such as a button timing out, is returned as is.
+    pub async fn recover(&mut self, error: &RPiError, attempt: u32) -> bool {
+        error.is_display_transient() && self.recover_after(error, attempt.saturating_sub(1)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    fn transient() -> RPiError {
+        io::Error::from(io::ErrorKind::Interrupted).into()
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_max() {
+        let policy = RecoveryPolicy::default()
+            .with_backoff(Duration::from_millis(5), Duration::from_millis(30));
+
+        let waits: Vec<_> = (1..=5)
+            .map(|attempt| policy.backoff(attempt).as_millis())
+            .collect();
+
+        assert_eq!(waits, vec![5, 10, 20, 30, 30]);
+        assert_eq!(policy.backoff(u32::MAX), Duration::from_millis(30));
+
+        assert!(!RecoveryPolicy::disabled().should_reset(u32::MAX));
+        assert!(policy.should_reset(2));
+        assert!(!policy.should_reset(1));
+    }
+
+    #[test]
+    fn resets_after_consecutive_failures_across_operations() {
+        let mut state = RecoveryState::new(
+            RecoveryPolicy::default()
+                .with_max_retries(2)
+                .with_reset_after(3),
+        );
+        let backoff = |attempt| state.policy().backoff(attempt);
+        let (first, second) = (backoff(1), backoff(2));
+
+        // The first operation fails three times and gives up.
+        assert_eq!(
+            state.on_failure(&transient(), 0),
+            RecoveryAction::Retry(first)
+        );
+        assert_eq!(
+            state.on_failure(&transient(), 1),
+            RecoveryAction::Retry(second)
+        );
+        assert_eq!(state.on_failure(&transient(), 2), RecoveryAction::GiveUp);
+        assert_eq!(state.consecutive_failures(), 3);
+
+        // The next failure in a row resets the panel.
+        assert_eq!(state.on_failure(&transient(), 0), RecoveryAction::Reset);
+        state.on_reset();
+        assert_eq!(state.consecutive_failures(), 0);
+
+        state.on_success(1);
+
+        assert_eq!(
+            state.stats(),
+            RecoveryStats {
+                retries: 3,
+                recovered: 1,
+                soft_resets: 1,
+                failures: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn success_clears_consecutive_failures() {
+        let mut state = RecoveryState::default();
+
+        assert!(matches!(
+            state.on_failure(&transient(), 0),
+            RecoveryAction::Retry(_)
+        ));
+        state.on_success(1);
+        assert_eq!(state.consecutive_failures(), 0);
+
+        // Without a failure in a row, the next one only waits.
+        assert!(matches!(
+            state.on_failure(&transient(), 0),
+            RecoveryAction::Retry(_)
+        ));
+        assert_eq!(state.stats().soft_resets, 0);
+        assert_eq!(state.stats().recovered, 1);
+    }
+
+    #[test]
+    fn gives_up_on_permanent_errors() {
+        let mut state = RecoveryState::default();
+
+        let missing: RPiError = io::Error::from(io::ErrorKind::NotFound).into();
+        assert_eq!(state.on_failure(&missing, 0), RecoveryAction::GiveUp);
+        assert_eq!(
+            state.on_failure(&RPiError::Cancelled, 0),
+            RecoveryAction::GiveUp
+        );
+
+        let mut disabled = RecoveryState::new(RecoveryPolicy::disabled());
+        assert_eq!(disabled.on_failure(&transient(), 0), RecoveryAction::GiveUp);
+
+        assert_eq!(state.consecutive_failures(), 0);
+        assert_eq!(state.stats().failures, 2);
+        assert_eq!(state.stats().retries, 0);
+    }
+}
@@ -0,0 +1,293 @@
+//! Animations made of decoded frames.
+//!
+
+use std::time::Duration;
+
+#[cfg(feature = "gif")]
+use std::path::Path;
+
+use embedded_graphics::draw_target::DrawTargetExt;
+
+use super::RasterImage;
+use crate::{foreign_types::*, traits::Animation};
+use primitives::Rectangle;
+
+#[cfg(feature = "gif")]
+use super::{DecodedAnimation, Dithering, NoDithering};
+#[cfg(feature = "gif")]
+use crate::func;
+#[cfg(feature = "gif")]
+use pixelcolor::Rgb888;
+
+/// A single frame of a [`FrameAnimation`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AnimationFrame<COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    /// The pixels of the frame within [`delta`](Self::delta), or of the whole frame
+    /// if there is none.
+    pub image: RasterImage<COLOUR>,
+    pub delay: Duration,
+
+    /// The area of the frame that differs from the previous frame, or [`None`] if
+    /// unknown.
+    pub delta: Option<Rectangle>,
+}
+
+/// An animation made of frames that are fully decoded in memory.
+///
+/// Every frame after the first with a [`delta`](AnimationFrame::delta) only holds
+/// the pixels within it, so a GIF with a small moving part takes little more memory
+/// than its first frame.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrameAnimation<COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    size: Size,
+    frames: Vec<AnimationFrame<COLOUR>>,
+    play_count: Option<u32>,
+}
+
+impl<COLOUR> FrameAnimation<COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    /// Create a new animation from its frames.
+    ///
+    /// The first frame is the size of the animation; every other frame must be
+    /// either the same size, or the size of its delta, which must be within the
+    /// first frame.
+    pub fn new(frames: Vec<AnimationFrame<COLOUR>>, play_count: Option<u32>) -> RPiResult<Self> {
+        let size = frames
+            .first()
+            .map(|frame| frame.image.size())
+            .ok_or_else(|| {
+                RPiError::InvalidInput("frames".into(), "at least one frame is required".into())
+            })?;
+        let area = Rectangle::new(Point::zero(), size);
+
+        for (index, frame) in frames.iter().enumerate().skip(1) {
+            let expected = frame.delta.map_or(size, |delta| delta.size);
+            if frame.image.size() != expected {
+                return Err(RPiError::InvalidInput(
+                    "frames".into(),
+                    format!(
+                        "frame {index} is {:?}, but {expected:?} is expected",
+                        frame.image.size()
+                    )
+                    .into(),
+                ));
+            }
+
+            if let Some(delta) = frame.delta.filter(|delta| {
+                delta
+                    .bottom_right()
+                    .is_some_and(|end| !area.contains(delta.top_left) || !area.contains(end))
+            }) {
+                return Err(RPiError::InvalidInput(
+                    "frames".into(),
+                    format!("the delta of frame {index}, {delta:?}, is outside of {size:?}").into(),
+                ));
+            }
+        }
+
+        Ok(Self {
+            size,
+            frames,
+            play_count,
+        })
+    }
+
+    /// Get the frames of the animation.
+    pub fn frames(&self) -> &[AnimationFrame<COLOUR>] {
+        &self.frames
+    }
+}
+
+#[cfg(feature = "gif")]
+impl<COLOUR> FrameAnimation<COLOUR>
+where
+    COLOUR: PixelColor + From<Rgb888> + Into<Rgb888>,
+{
+    /// Convert decoded frames into the colour format of the display, using the
+    /// dithering strategy `D`.
+    fn from_decoded<D>(decoded: DecodedAnimation) -> RPiResult<Self>
+    where
+        D: Dithering,
+    {
+        let frames = decoded
+            .frames
+            .into_iter()
+            .map(|frame| {
+                Ok(AnimationFrame {
+                    image: RasterImage::new(
+                        frame.pixels.size,
+                        D::convert::<COLOUR>(&frame.pixels),
+                    )?,
+                    delay: frame.delay,
+                    delta: Some(frame.delta),
+                })
+            })
+            .collect::<RPiResult<Vec<_>>>()?;
+
+        Self::new(frames, decoded.play_count)
+    }
+
+    /// Decode an animation from the bytes of a GIF file, without dithering.
+    pub fn from_gif(bytes: &[u8]) -> RPiResult<Self> {
+        Self::from_gif_dithered::<NoDithering>(bytes)
+    }
+
+    /// Decode an animation from the bytes of a GIF file, using the dithering
+    /// strategy `D`.
+    pub fn from_gif_dithered<D>(bytes: &[u8]) -> RPiResult<Self>
+    where
+        D: Dithering,
+    {
+        Self::from_decoded::<D>(func::gif::decode_gif(bytes)?)
+    }
+
+    /// Decode an animation by reading the GIF file at the given path, without
+    /// dithering.
+    pub async fn from_gif_path(path: impl AsRef<Path> + std::fmt::Debug) -> RPiResult<Self> {
+        let bytes = func::fs::read_bytes_from_file(&path).await?;
+
+        Self::from_gif(&bytes)
+    }
+}
+
+impl<COLOUR> OriginDimensions for FrameAnimation<COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<COLOUR> Animation for FrameAnimation<COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    type Colour = COLOUR;
+
+    fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    fn frame_delay(&self, index: usize) -> Duration {
+        self.frames[index].delay
+    }
+
+    fn frame_delta(&self, index: usize) -> Option<Rectangle> {
+        if index == 0 {
+            None
+        } else {
+            self.frames[index].delta
+        }
+    }
+
+    fn play_count(&self) -> Option<u32> {
+        self.play_count
+    }
+
+    /// Draw the given area of a frame; a frame after the first only holds the
+    /// pixels of its delta, if any, which are drawn at their place in the frame.
+    fn draw_frame<DT>(
+        &self,
+        index: usize,
+        area: &Rectangle,
+        target: &mut DT,
+    ) -> Result<(), DT::Error>
+    where
+        DT: DrawTarget<Color = Self::Colour>,
+    {
+        let frame = &self.frames[index];
+        let offset = match (index, frame.delta) {
+            (0, _) | (_, None) => Point::zero(),
+            (_, Some(delta)) => delta.top_left,
+        };
+
+        frame
+            .image
+            .draw(&mut target.clipped(area).translated(offset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_graphics::pixelcolor::Rgb888;
+
+    use super::*;
+
+    fn frame(size: Size, colour: Rgb888, delta: Option<Rectangle>) -> AnimationFrame<Rgb888> {
+        AnimationFrame {
+            image: RasterImage::filled(size, colour),
+            delay: Duration::from_millis(10),
+            delta,
+        }
+    }
+
+    #[test]
+    fn deltas_are_drawn_at_their_place_in_the_frame() {
+        let delta = Rectangle::new(Point::new(1, 1), Size::new(2, 1));
+        let animation = FrameAnimation::new(
+            vec![
+                frame(Size::new(4, 3), Rgb888::BLACK, None),
+                frame(delta.size, Rgb888::WHITE, Some(delta)),
+            ],
+            None,
+        )
+        .unwrap();
+        let mut target = RasterImage::filled(Size::new(4, 3), Rgb888::RED);
+
+        animation.draw_frame(1, &delta, &mut target).unwrap();
+
+        for y in 0..3 {
+            for x in 0..4 {
+                let expected = if y == 1 && (1..3).contains(&x) {
+                    Rgb888::WHITE
+                } else {
+                    Rgb888::RED
+                };
+                assert_eq!(target.pixel(Point::new(x, y)), Some(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn frames_must_match_their_deltas() {
+        let delta = Rectangle::new(Point::new(1, 1), Size::new(2, 1));
+
+        let full = frame(Size::new(4, 3), Rgb888::BLACK, None);
+        assert!(FrameAnimation::new(vec![full.clone(), full.clone()], None).is_ok());
+        assert!(FrameAnimation::new(
+            vec![
+                full.clone(),
+                frame(Size::new(4, 3), Rgb888::WHITE, Some(delta))
+            ],
+            None
+        )
+        .is_err());
+        assert!(FrameAnimation::new(
+            vec![full.clone(), frame(Size::new(2, 2), Rgb888::WHITE, None)],
+            None
+        )
+        .is_err());
+
+        let outside = Rectangle::new(Point::new(3, 2), Size::new(2, 1));
+        let err = FrameAnimation::new(
+            vec![full, frame(outside.size, Rgb888::WHITE, Some(outside))],
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err.code(), 1);
+    }
+
+    #[test]
+    fn at_least_one_frame_is_required() {
+        assert!(FrameAnimation::<Rgb888>::new(Vec::new(), None).is_err());
+    }
+}
@@ -0,0 +1,308 @@
+//! Images decoded from compressed file formats, such as PNG.
+//!
+
+use std::{marker::PhantomData, ops::Deref, time::Duration};
+
+use embedded_graphics::primitives::Rectangle;
+
+use super::{Dithering, NoDithering, OwnedImage, OwnsImage, RasterImage};
+use crate::{foreign_types::*, func};
+use pixelcolor::Rgb888;
+
+/// Pixels decoded from an image file, before they are converted into the colour
+/// format of the display.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedPixels {
+    pub size: Size,
+    pub pixels: Vec<Rgb888>,
+
+    /// The alpha channel of the pixels, if the file has one.
+    pub alpha: Option<Vec<u8>>,
+}
+
+impl DecodedPixels {
+    /// Create the pixels from interleaved 8-bit RGB channels.
+    pub fn from_rgb(size: Size, channels: &[u8]) -> Self {
+        Self {
+            size,
+            pixels: channels
+                .chunks_exact(3)
+                .map(|pixel| Rgb888::new(pixel[0], pixel[1], pixel[2]))
+                .collect(),
+            alpha: None,
+        }
+    }
+
+    /// Create the pixels from interleaved 8-bit RGBA channels.
+    pub fn from_rgba(size: Size, channels: &[u8]) -> Self {
+        Self {
+            size,
+            pixels: channels
+                .chunks_exact(4)
+                .map(|pixel| Rgb888::new(pixel[0], pixel[1], pixel[2]))
+                .collect(),
+            alpha: Some(channels.chunks_exact(4).map(|pixel| pixel[3]).collect()),
+        }
+    }
+
+    /// Blend the pixels onto black according to their alpha channel, if any.
+    pub fn premultiplied(mut self) -> Self {
+        if let Some(alpha) = self.alpha.take() {
+            self.pixels
+                .iter_mut()
+                .zip(alpha)
+                .for_each(|(pixel, alpha)| {
+                    *pixel = func::blending::blend(*pixel, Rgb888::BLACK, alpha);
+                });
+        }
+
+        self
+    }
+
+    /// Make every pixel that converts to `key` in the colour format of the display
+    /// fully transparent, in addition to any existing alpha channel.
+    ///
+    /// Comparing after the conversion allows a key to match colours that were
+    /// rounded by the image editor, such as a 16-bit magenta saved as 24-bit.
+    pub fn with_colour_key<COLOUR>(mut self, key: COLOUR) -> Self
+    where
+        COLOUR: PixelColor + From<Rgb888>,
+    {
+        let mut alpha = self
+            .alpha
+            .take()
+            .unwrap_or_else(|| vec![u8::MAX; self.pixels.len()]);
+
+        self.pixels
+            .iter()
+            .zip(alpha.iter_mut())
+            .filter(|(pixel, _)| COLOUR::from(**pixel) == key)
+            .for_each(|(_, alpha)| *alpha = 0);
+
+        self.alpha = Some(alpha);
+        self
+    }
+}
+
+/// A single frame decoded from an animated image file, already composited onto
+/// the frames before it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedFrame {
+    /// The pixels of the frame within [`delta`](Self::delta).
+    pub pixels: DecodedPixels,
+    pub delay: Duration,
+
+    /// The area of the frame that differs from the previous frame; the whole frame
+    /// for the first one.
+    pub delta: Rectangle,
+}
+
+/// Frames decoded from an animated image file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedAnimation {
+    pub size: Size,
+    pub frames: Vec<DecodedFrame>,
+
+    /// The number of times the animation should be played, or [`None`] to loop
+    /// forever.
+    pub play_count: Option<u32>,
+}
+
+/// A decoder for an image file format.
+pub trait ImageDecoder {
+    /// The name of the file format, such as `PNG`.
+    const NAME: &'static str;
+
+    /// Decode the bytes of an image file.
+    fn decode(bytes: &[u8]) -> RPiResult<DecodedPixels>;
+}
+
+/// Decoder for BMP files, which keeps the alpha channel of 32-bit images.
+#[cfg(feature = "bmp")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BmpDecoder;
+
+#[cfg(feature = "bmp")]
+impl ImageDecoder for BmpDecoder {
+    const NAME: &'static str = "BMP";
+
+    fn decode(bytes: &[u8]) -> RPiResult<DecodedPixels> {
+        crate::func::bmp::decode_bmp(bytes)
+    }
+}
+
+/// Decoder for PNG files.
+#[cfg(feature = "png")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PngDecoder;
+
+#[cfg(feature = "png")]
+impl ImageDecoder for PngDecoder {
+    const NAME: &'static str = "PNG";
+
+    fn decode(bytes: &[u8]) -> RPiResult<DecodedPixels> {
+        crate::func::png::decode_png(bytes)
+    }
+}
+
+/// Decoder for QOI files.
+#[cfg(feature = "qoi")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct QoiDecoder;
+
+#[cfg(feature = "qoi")]
+impl ImageDecoder for QoiDecoder {
+    const NAME: &'static str = "QOI";
+
+    fn decode(bytes: &[u8]) -> RPiResult<DecodedPixels> {
+        crate::func::qoi::decode_qoi(bytes)
+    }
+}
+
+/// An image decoded by `F` into the colour format of the display, using the
+/// dithering strategy `D`.
+///
+/// The alpha channel, if any, is kept in the decoded [`RasterImage`], to be blended
+/// when it is drawn or composited.
+///
+/// Images used to be blended onto black as they were decoded; to keep that, decode
+/// them with [`ImageDecoder::decode`] and [`DecodedPixels::premultiplied`], or draw
+/// them [`composited_over`](RasterImage::composited_over) black.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedImage<COLOUR, F, D = NoDithering>
+where
+    COLOUR: PixelColor,
+{
+    image: RasterImage<COLOUR>,
+    _phantom: PhantomData<(F, D)>,
+}
+
+impl<COLOUR, F, D> DecodedImage<COLOUR, F, D>
+where
+    COLOUR: PixelColor + From<Rgb888> + Into<Rgb888>,
+    F: ImageDecoder,
+    D: Dithering,
+{
+    /// Decode an image from the bytes of its file.
+    pub fn from_bytes(bytes: &[u8]) -> RPiResult<Self> {
+        Self::from_bytes_with_colour_key(bytes, None)
+    }
+
+    /// Decode an image from the bytes of its file, making every pixel of the
+    /// colour `key` fully transparent.
+    pub fn from_bytes_with_colour_key(bytes: &[u8], key: Option<COLOUR>) -> RPiResult<Self> {
+        let mut decoded = F::decode(bytes)?;
+        if let Some(key) = key {
+            decoded = decoded.with_colour_key(key);
+        }
+
+        let image = RasterImage::new(decoded.size, D::convert::<COLOUR>(&decoded))?;
+
+        Ok(Self {
+            image: match decoded.alpha {
+                Some(alpha) => image.with_alpha(alpha)?,
+                None => image,
+            },
+            _phantom: PhantomData,
+        })
+    }
+}
+
+impl<COLOUR, F, D> DecodedImage<COLOUR, F, D>
+where
+    COLOUR: PixelColor,
+{
+    /// Get the decoded image.
+    pub fn image(&self) -> &RasterImage<COLOUR> {
+        &self.image
+    }
+
+    /// Take the decoded image.
+    pub fn into_image(self) -> RasterImage<COLOUR> {
+        self.image
+    }
+}
+
+impl<COLOUR, F, D> Deref for DecodedImage<COLOUR, F, D>
+where
+    COLOUR: PixelColor,
+{
+    type Target = RasterImage<COLOUR>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.image
+    }
+}
+
+impl<COLOUR, F, D> OriginDimensions for DecodedImage<COLOUR, F, D>
+where
+    COLOUR: PixelColor,
+{
+    fn size(&self) -> Size {
+        self.image.size()
+    }
+}
+
+impl<COLOUR, F, D> ImageDrawable for DecodedImage<COLOUR, F, D>
+where
+    COLOUR: PixelColor,
+{
+    type Color = COLOUR;
+
+    fn draw<DT>(&self, target: &mut DT) -> Result<(), DT::Error>
+    where
+        DT: DrawTarget<Color = Self::Color>,
+    {
+        self.image.draw(target)
+    }
+
+    fn draw_sub_image<DT>(&self, target: &mut DT, area: &Rectangle) -> Result<(), DT::Error>
+    where
+        DT: DrawTarget<Color = Self::Color>,
+    {
+        self.image.draw_sub_image(target, area)
+    }
+}
+
+/// An image decoded by `F` that owns its own data.
+pub type OwnedDecodedImage<'i, COLOUR, F, D = NoDithering> =
+    OwnedImage<'i, DecodedImage<COLOUR, F, D>, COLOUR>;
+
+#[cfg(feature = "bmp")]
+/// A BMP image that owns its own data, decoded using the dithering strategy `D`.
+///
+/// Unlike an [`OwnedBmp`](super::OwnedBmp), this keeps the alpha channel of 32-bit
+/// images, at the cost of holding the decoded pixels in memory.
+pub type OwnedAlphaBmp<'i, COLOUR, D = NoDithering> = OwnedDecodedImage<'i, COLOUR, BmpDecoder, D>;
+
+#[cfg(feature = "png")]
+/// A PNG image that owns its own data, decoded using the dithering strategy `D`.
+pub type OwnedPng<'i, COLOUR, D = NoDithering> = OwnedDecodedImage<'i, COLOUR, PngDecoder, D>;
+
+#[cfg(feature = "qoi")]
+/// A QOI image that owns its own data, decoded using the dithering strategy `D`.
+pub type OwnedQoi<'i, COLOUR, D = NoDithering> = OwnedDecodedImage<'i, COLOUR, QoiDecoder, D>;
+
+impl<'i, COLOUR, F, D> OwnsImage<'i, DecodedImage<COLOUR, F, D>>
+    for OwnedDecodedImage<'i, COLOUR, F, D>
+where
+    COLOUR: PixelColor + From<Rgb888> + Into<Rgb888> + From<<COLOUR as PixelColor>::Raw> + 'i,
+    F: ImageDecoder + 'i,
+    D: Dithering + 'i,
+{
+    /// Initialise the instance by decoding the given bytes.
+    ///
+    /// A decoded image contains its own size, so this requires the size of the image
+    /// to NOT be set. The colour key of the instance, if any, is applied.
+    fn init_from_bytes(&'i self) -> RPiResult<()> {
+        let bytes = self.bytes()?;
+
+        let raw = DecodedImage::<COLOUR, F, D>::from_bytes_with_colour_key(
+            bytes,
+            self.colour_key.get().copied(),
+        )?;
+        let size = raw.size();
+
+        self.put_raw(raw).and_then(|_| self.put_size(size))
+    }
+}
@@ -0,0 +1,53 @@
+//! Strategies for reducing true colour images to the colour format of the display.
+//!
+
+use super::DecodedPixels;
+use crate::{foreign_types::*, func};
+use pixelcolor::Rgb888;
+
+/// A strategy for converting [`Rgb888`] pixels into the colour format of the
+/// display.
+pub trait Dithering {
+    /// Convert decoded pixels, in row-major order; their alpha channel, if any,
+    /// is left to the caller.
+    fn convert<COLOUR>(decoded: &DecodedPixels) -> Vec<COLOUR>
+    where
+        COLOUR: PixelColor + From<Rgb888> + Into<Rgb888>;
+}
+
+/// Convert each pixel to the nearest colour, without dithering.
+///
+/// This is the fastest, but gradients reduced to 16-bit colour show visible bands.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NoDithering;
+
+impl Dithering for NoDithering {
+    fn convert<COLOUR>(decoded: &DecodedPixels) -> Vec<COLOUR>
+    where
+        COLOUR: PixelColor + From<Rgb888> + Into<Rgb888>,
+    {
+        decoded
+            .pixels
+            .iter()
+            .map(|pixel| COLOUR::from(*pixel))
+            .collect()
+    }
+}
+
+/// Diffuse the rounding error of each pixel onto its neighbours, using the
+/// Floyd–Steinberg matrix.
+///
+/// This hides the banding of gradients reduced to 16-bit colour, at the cost of a
+/// slower decode. Fully transparent pixels are never seen, so they neither take nor
+/// spread any error.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FloydSteinberg;
+
+impl Dithering for FloydSteinberg {
+    fn convert<COLOUR>(decoded: &DecodedPixels) -> Vec<COLOUR>
+    where
+        COLOUR: PixelColor + From<Rgb888> + Into<Rgb888>,
+    {
+        func::dithering::floyd_steinberg(decoded.size, &decoded.pixels, decoded.alpha.as_deref())
+    }
+}
@@ -3,3 +3,18 @@
 
 mod owned;
 pub use owned::*;
+
+mod raster;
+pub use raster::*;
+
+mod dithering;
+pub use dithering::*;
+
+mod decoded;
+pub use decoded::*;
+
+mod animation;
+pub use animation::*;
+
+mod sprite_sheet;
+pub use sprite_sheet::*;
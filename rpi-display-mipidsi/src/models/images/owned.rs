@@ -28,6 +28,7 @@ where
     pub raw: OnceLock<IR>,
     pub image: OnceLock<Image<'i, IR>>,
     pub size: OnceLock<Size>,
+    pub colour_key: OnceLock<COLOUR>,
 
     _phantom: PhantomData<&'i ()>,
 }
@@ -45,6 +46,7 @@ where
             raw: OnceLock::new(),
             image: OnceLock::new(),
             size: OnceLock::new(),
+            colour_key: OnceLock::new(),
 
             _phantom: PhantomData,
         }
@@ -90,20 +92,33 @@ where
         Self::with_size(Size::new(width, height))
     }
 
+    /// Create an empty new instance with a colour key, which makes every pixel of
+    /// that colour fully transparent.
+    ///
+    /// Only images that are decoded into a [`RasterImage`](super::RasterImage),
+    /// such as an [`OwnedDecodedImage`](super::OwnedDecodedImage), support colour
+    /// keys; other images ignore it.
+    pub fn with_colour_key(colour: COLOUR) -> Self {
+        let instance = Self::new();
+        instance.put_colour_key(colour).unwrap();
+
+        instance
+    }
+
     /// Create a new instance from the given bytes.
-    pub fn from_bytes<'e>(bytes: impl Into<Box<[u8]>>) -> RPiResult<'e, Self> {
+    pub fn from_bytes(bytes: impl Into<Box<[u8]>>) -> RPiResult<Self> {
         let instance = Self::new();
         instance.put_bytes(bytes).and(Ok(instance))
     }
 
     /// Create a new instance from the given bytes and size.
-    pub fn from_bytes_size<'e>(bytes: impl Into<Box<[u8]>>, size: Size) -> RPiResult<'e, Self> {
+    pub fn from_bytes_size(bytes: impl Into<Box<[u8]>>, size: Size) -> RPiResult<Self> {
         let instance = Self::with_size(size);
         instance.put_bytes(bytes).and(Ok(instance))
     }
 
     /// Create a new instance by reading the file at the given path.
-    pub async fn from_path<'e>(path: impl AsRef<Path> + std::fmt::Debug) -> RPiResult<'e, Self> {
+    pub async fn from_path(path: impl AsRef<Path> + std::fmt::Debug) -> RPiResult<Self> {
         let instance = Self::new();
         instance.put_file(path).await?;
 
@@ -112,10 +127,10 @@ where
 
     /// Create a new instance by reading the file at the given path, and with the
     /// given size.
-    pub async fn from_path_size<'e>(
+    pub async fn from_path_size(
         path: impl AsRef<Path> + std::fmt::Debug,
         size: Size,
-    ) -> RPiResult<'e, Self> {
+    ) -> RPiResult<Self> {
         let instance = Self::with_size(size);
         instance.put_file(path).await?;
 
@@ -126,15 +141,28 @@ where
     ///
     /// Only use this if the size cannot be determined by the image data, such as
     /// an [`ImageRaw`].
-    pub fn put_size<'e>(&self, size: Size) -> RPiResult<'e, ()> {
+    pub fn put_size(&self, size: Size) -> RPiResult<()> {
         match self.size.set(size) {
             Ok(_) => Ok(()),
             Err(_) => Err(RPiError::AlreadyInitialised("OwnedImage".into())),
         }
     }
 
+    /// Add the colour key to the instance; this must be done before the image is
+    /// first used.
+    pub fn put_colour_key(&self, colour: COLOUR) -> RPiResult<()> {
+        if self.raw.get().is_some() {
+            return Err(RPiError::AlreadyInitialised("OwnedImage".into()));
+        }
+
+        match self.colour_key.set(colour) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(RPiError::AlreadyInitialised("OwnedImage".into())),
+        }
+    }
+
     /// Add the image data to the instance.
-    pub fn put_bytes<'e>(&self, value: impl Into<Box<[u8]>>) -> RPiResult<'e, ()> {
+    pub fn put_bytes(&self, value: impl Into<Box<[u8]>>) -> RPiResult<()> {
         match self.bytes.set(value.into()) {
             Ok(_) => Ok(()),
             Err(_) => Err(RPiError::AlreadyInitialised("OwnedImage".into())),
@@ -142,7 +170,7 @@ where
     }
 
     /// Get bytes from the instance.
-    pub fn bytes<'e>(&self) -> RPiResult<'e, &[u8]> {
+    pub fn bytes(&self) -> RPiResult<&[u8]> {
         self.bytes
             .get()
             .map(|bytes| bytes.as_ref())
@@ -150,16 +178,13 @@ where
     }
 
     /// Create a new image from the given bytes.
-    pub async fn put_file<'e>(
-        &self,
-        path: impl AsRef<Path> + std::fmt::Debug,
-    ) -> RPiResult<'e, ()> {
+    pub async fn put_file(&self, path: impl AsRef<Path> + std::fmt::Debug) -> RPiResult<()> {
         let bytes = func::fs::read_bytes_from_file(&path).await?;
         self.put_bytes(bytes)
     }
 
     /// Put the raw image into the instance.
-    pub fn put_raw<'e>(&self, value: IR) -> RPiResult<'e, ()> {
+    pub fn put_raw(&self, value: IR) -> RPiResult<()> {
         match self.raw.set(value) {
             Ok(_) => Ok(()),
             Err(_) => Err(RPiError::AlreadyInitialised("OwnedImage".into())),
@@ -167,7 +192,7 @@ where
     }
 
     /// Get the raw image from the instance.
-    pub fn raw<'e>(&'i self) -> RPiResult<'e, &IR> {
+    pub fn raw(&'i self) -> RPiResult<&IR> {
         match self.raw.get() {
             Some(raw) => Ok(raw),
             None => {
@@ -180,7 +205,7 @@ where
     }
 
     /// Put the image into the instance.
-    pub fn put_image<'e>(&self, value: Image<'i, IR>) -> RPiResult<'e, ()> {
+    pub fn put_image(&self, value: Image<'i, IR>) -> RPiResult<()> {
         match self.image.set(value) {
             Ok(_) => Ok(()),
             Err(_) => Err(RPiError::AlreadyInitialised("OwnedImage".into())),
@@ -188,13 +213,13 @@ where
     }
 
     /// Create a new image from the given instance.
-    pub fn init_image_at<'e>(&'i self, position: Point) -> RPiResult<'e, ()> {
+    pub fn init_image_at(&'i self, position: Point) -> RPiResult<()> {
         let raw = self.raw()?;
         self.put_image(Image::new(raw, position))
     }
 
     /// Get the image from the instance. If it does not exist, create it.
-    pub fn image<'e>(&'i self) -> RPiResult<'e, &Image<'i, IR>> {
+    pub fn image(&'i self) -> RPiResult<&Image<'i, IR>> {
         match self.image.get() {
             Some(image) => Ok(image),
             None => {
@@ -213,14 +238,14 @@ where
     ///
     /// Note that this returns an image that is owned by the caller, albeit bound
     /// to the lifetime of the instance.
-    pub fn image_at<'e>(&'i self, position: Point) -> RPiResult<'e, Image<'i, IR>> {
+    pub fn image_at(&'i self, position: Point) -> RPiResult<Image<'i, IR>> {
         let base_image = self.image()?;
 
         Ok(base_image.translate(position))
     }
 
     /// Create a [`SubImage`] from the instance.
-    pub fn subimage<'e>(&'i self, position: Point, size: Size) -> RPiResult<'e, SubImage<'i, IR>> {
+    pub fn subimage(&'i self, position: Point, size: Size) -> RPiResult<SubImage<'i, IR>> {
         let raw = self.raw()?;
 
         Ok(func::crop::crop_raw(
@@ -239,7 +264,7 @@ where
     Self: Sized + 'i,
 {
     /// Create a new instance from the given bytes.
-    fn init_from_bytes<'e>(&'i self) -> RPiResult<'e, ()>;
+    fn init_from_bytes(&'i self) -> RPiResult<()>;
 }
 
 /// An [`ImageRaw`] that owns its own data.
@@ -256,7 +281,7 @@ where
     ///
     /// A raw image requires a width to be specified, along with the colour space
     /// and byte ordering of the incoming bytes.
-    fn init_from_bytes<'e>(&'i self) -> RPiResult<'e, ()> {
+    fn init_from_bytes(&'i self) -> RPiResult<()> {
         let bytes = self.bytes()?;
         let width = match self.size.get() {
             Some(size) => size.width,
@@ -293,7 +318,7 @@ where
     ///
     /// A BMP image contains its own size, so this requires the size of the image
     /// to NOT be set.
-    fn init_from_bytes<'e>(&'i self) -> RPiResult<'e, ()>
+    fn init_from_bytes(&'i self) -> RPiResult<()>
     where
         Self: Sized,
     {
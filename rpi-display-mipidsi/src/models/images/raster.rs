@@ -0,0 +1,469 @@
+//! An image made of owned, already decoded pixels.
+//!
+
+use std::convert::Infallible;
+
+use embedded_graphics::{
+    draw_target::DrawTargetExt,
+    primitives::{PointsIter, Rectangle},
+    Pixel,
+};
+
+use crate::{foreign_types::*, func};
+use pixelcolor::Rgb888;
+
+/// Pixels with an opacity below this are skipped when drawing an image with an
+/// alpha channel directly; anything above is drawn fully opaque.
+pub const ALPHA_THRESHOLD: u8 = 128;
+
+/// An image made of owned pixels in the colour format of the display, with an
+/// optional alpha channel.
+///
+/// Unlike an [`ImageRaw`], this does not borrow its data, and can be drawn without
+/// decoding every pixel again; this is the target of all the decoders that cannot
+/// be drawn directly from their file bytes, such as PNG.
+///
+/// As the display cannot be read back, an image with an alpha channel can only be
+/// blended onto a known background: either a single colour with
+/// [`composited_over`](Self::composited_over), or another [`RasterImage`] acting as
+/// a shadow framebuffer with [`composite_onto`](Self::composite_onto). A
+/// [`RasterImage`] is also a [`DrawTarget`], so the framebuffer can hold gradients
+/// or photos drawn by anything else.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RasterImage<COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    size: Size,
+    pixels: Box<[COLOUR]>,
+    alpha: Option<Box<[u8]>>,
+}
+
+impl<COLOUR> RasterImage<COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    /// Create a new image from pixels in row-major order.
+    pub fn new(size: Size, pixels: impl Into<Box<[COLOUR]>>) -> RPiResult<Self> {
+        let pixels = pixels.into();
+        check_length("pixels", pixels.len(), size)?;
+
+        Ok(Self {
+            size,
+            pixels,
+            alpha: None,
+        })
+    }
+
+    /// Create a new image from its parts, which must have one value for each pixel.
+    pub(crate) fn from_parts(size: Size, pixels: Box<[COLOUR]>, alpha: Option<Box<[u8]>>) -> Self {
+        debug_assert_eq!(pixels.len(), (size.width * size.height) as usize);
+
+        Self {
+            size,
+            pixels,
+            alpha,
+        }
+    }
+
+    /// Create a new image by drawing any other image.
+    ///
+    /// Pixels that the image does not draw, such as the transparent pixels of
+    /// another [`RasterImage`], are left as [`Default`].
+    pub fn from_image<T>(image: &T) -> Self
+    where
+        T: ImageDrawable<Color = COLOUR>,
+        COLOUR: Default,
+    {
+        let mut raster = Self::filled(image.size(), COLOUR::default());
+
+        match image.draw(&mut raster) {
+            Ok(()) => raster,
+            Err(never) => match never {},
+        }
+    }
+
+    /// Create a new image filled with a single colour.
+    pub fn filled(size: Size, colour: COLOUR) -> Self {
+        Self {
+            size,
+            pixels: vec![colour; (size.width * size.height) as usize].into(),
+            alpha: None,
+        }
+    }
+
+    /// Add an alpha channel to the image, in the same order as the pixels; `0` is
+    /// fully transparent and `255` is fully opaque.
+    pub fn with_alpha(mut self, alpha: impl Into<Box<[u8]>>) -> RPiResult<Self> {
+        let alpha = alpha.into();
+        check_length("alpha", alpha.len(), self.size)?;
+
+        self.alpha = Some(alpha);
+        Ok(self)
+    }
+
+    /// Make every pixel of the given colour fully transparent, in addition to any
+    /// existing alpha channel.
+    pub fn with_colour_key(mut self, key: COLOUR) -> Self {
+        let mut alpha = self
+            .alpha
+            .take()
+            .unwrap_or_else(|| vec![u8::MAX; self.pixels.len()].into());
+
+        self.pixels
+            .iter()
+            .zip(alpha.iter_mut())
+            .filter(|(pixel, _)| **pixel == key)
+            .for_each(|(_, alpha)| *alpha = 0);
+
+        self.alpha = Some(alpha);
+        self
+    }
+
+    /// Remove the alpha channel from the image, making every pixel fully opaque.
+    pub fn without_alpha(mut self) -> Self {
+        self.alpha = None;
+        self
+    }
+
+    /// Get the pixels of the image in row-major order.
+    pub fn pixels(&self) -> &[COLOUR] {
+        &self.pixels
+    }
+
+    /// Get the pixels of the image in row-major order, mutably.
+    pub fn pixels_mut(&mut self) -> &mut [COLOUR] {
+        &mut self.pixels
+    }
+
+    /// Get the alpha channel of the image, if any.
+    pub fn alpha(&self) -> Option<&[u8]> {
+        self.alpha.as_deref()
+    }
+
+    /// Whether every pixel of the image is fully opaque.
+    pub fn is_opaque(&self) -> bool {
+        self.alpha
+            .as_ref()
+            .map_or(true, |alpha| alpha.iter().all(|value| *value == u8::MAX))
+    }
+
+    /// Get the pixel at a point, if it is within the image.
+    pub fn pixel(&self, point: Point) -> Option<COLOUR> {
+        self.index_of(point).map(|index| self.pixels[index])
+    }
+
+    /// Get the opacity at a point, if it is within the image.
+    pub fn alpha_at(&self, point: Point) -> Option<u8> {
+        self.index_of(point).map(|index| self.alpha_of(index))
+    }
+
+    /// Set the pixel at a point; points outside of the image are ignored.
+    pub fn set_pixel(&mut self, point: Point, colour: COLOUR) {
+        if let Some(index) = self.index_of(point) {
+            self.pixels[index] = colour;
+        }
+    }
+
+    /// Get the index of a point in the pixels, if it is within the image.
+    fn index_of(&self, point: Point) -> Option<usize> {
+        if point.x < 0
+            || point.y < 0
+            || point.x as u32 >= self.size.width
+            || point.y as u32 >= self.size.height
+        {
+            None
+        } else {
+            Some(point.y as usize * self.size.width as usize + point.x as usize)
+        }
+    }
+
+    /// Get the opacity of the pixel at an index.
+    fn alpha_of(&self, index: usize) -> u8 {
+        self.alpha.as_ref().map_or(u8::MAX, |alpha| alpha[index])
+    }
+}
+
+impl<COLOUR> RasterImage<COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    /// Rotate the image clockwise into a new image.
+    pub fn rotated(&self, rotation: func::transform::Rotation) -> Self {
+        func::transform::rotate(self, rotation)
+    }
+
+    /// Flip the image across an axis into a new image.
+    pub fn flipped(&self, flip: func::transform::Flip) -> Self {
+        func::transform::flip(self, flip)
+    }
+
+    /// Replace every pixel of the colour `from` with `to` in a new image.
+    pub fn recoloured(&self, from: COLOUR, to: COLOUR) -> Self {
+        func::transform::recolour(self, from, to)
+    }
+}
+
+impl<COLOUR> RasterImage<COLOUR>
+where
+    COLOUR: PixelColor + From<Rgb888> + Into<Rgb888>,
+{
+    /// Scale the image to the given size into a new image.
+    pub fn scaled(&self, size: Size, filter: func::transform::ScalingFilter) -> Self {
+        func::transform::scale(self, size, filter)
+    }
+
+    /// Tint the image by multiplying each of its pixels with `colour` into a new
+    /// image.
+    pub fn tinted(&self, colour: COLOUR) -> Self {
+        func::transform::tint(self, colour)
+    }
+
+    /// Blend the image over a single background colour, returning an opaque image.
+    pub fn composited_over(&self, background: COLOUR) -> Self {
+        let Some(alpha) = self.alpha.as_deref() else {
+            return self.clone();
+        };
+
+        let foreground = self
+            .pixels
+            .iter()
+            .map(|pixel| (*pixel).into())
+            .collect::<Vec<_>>();
+        let background = vec![background.into(); foreground.len()];
+        let mut blended = vec![Rgb888::BLACK; foreground.len()];
+        func::kernels::blend(&foreground, &background, alpha, &mut blended);
+
+        Self {
+            size: self.size,
+            pixels: blended.into_iter().map(COLOUR::from).collect(),
+            alpha: None,
+        }
+    }
+
+    /// Blend the image onto another image, such as a shadow framebuffer, with its
+    /// top left corner at `position`; pixels outside of `target` are ignored.
+    ///
+    /// The alpha channel of `target`, if any, is left untouched.
+    pub fn composite_onto(&self, target: &mut Self, position: Point) {
+        let area = Rectangle::new(position, self.size).intersection(&target.bounding_box());
+        let width = area.size.width as usize;
+
+        let mut foreground = Vec::with_capacity(width);
+        let mut background = Vec::with_capacity(width);
+        let mut blended = vec![Rgb888::BLACK; width];
+
+        for y in area.rows() {
+            let row_start = Point::new(area.top_left.x, y);
+            let (Some(source), Some(destination)) = (
+                self.index_of(row_start - position),
+                target.index_of(row_start),
+            ) else {
+                continue;
+            };
+
+            let source = source..source + width;
+            let destination = destination..destination + width;
+
+            let Some(alpha) = self.alpha.as_deref() else {
+                target.pixels[destination].copy_from_slice(&self.pixels[source]);
+                continue;
+            };
+
+            foreground.clear();
+            foreground.extend(
+                self.pixels[source.clone()]
+                    .iter()
+                    .map(|pixel| (*pixel).into()),
+            );
+            background.clear();
+            background.extend(
+                target.pixels[destination.clone()]
+                    .iter()
+                    .map(|pixel| (*pixel).into()),
+            );
+
+            func::kernels::blend(&foreground, &background, &alpha[source], &mut blended);
+
+            target.pixels[destination]
+                .iter_mut()
+                .zip(&blended)
+                .for_each(|(pixel, colour)| *pixel = COLOUR::from(*colour));
+        }
+    }
+}
+
+/// Check that a channel has exactly one value for each pixel of an image.
+fn check_length(name: &str, length: usize, size: Size) -> RPiResult<()> {
+    if length as u64 != size.width as u64 * size.height as u64 {
+        return Err(RPiError::InvalidInput(
+            name.to_owned().into(),
+            format!(
+                "{} values found, but a {}x{} image requires {}",
+                length,
+                size.width,
+                size.height,
+                size.width as u64 * size.height as u64
+            )
+            .into(),
+        ));
+    }
+
+    Ok(())
+}
+
+impl<COLOUR> OriginDimensions for RasterImage<COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    fn size(&self) -> Size {
+        self.size
+    }
+}
+
+impl<COLOUR> ImageDrawable for RasterImage<COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    type Color = COLOUR;
+
+    /// Draw the image onto the target.
+    ///
+    /// As the target cannot be read back, pixels of an image with an alpha channel
+    /// are either skipped or drawn fully opaque, depending on [`ALPHA_THRESHOLD`].
+    fn draw<D>(&self, target: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        match &self.alpha {
+            None => target.fill_contiguous(&self.bounding_box(), self.pixels.iter().copied()),
+            Some(alpha) => target.draw_iter(
+                self.bounding_box()
+                    .points()
+                    .zip(self.pixels.iter().zip(alpha.iter()))
+                    .filter(|(_, (_, alpha))| **alpha >= ALPHA_THRESHOLD)
+                    .map(|(point, (pixel, _))| Pixel(point, *pixel)),
+            ),
+        }
+    }
+
+    fn draw_sub_image<D>(&self, target: &mut D, area: &Rectangle) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Color = Self::Color>,
+    {
+        self.draw(&mut target.translated(-area.top_left).clipped(area))
+    }
+}
+
+impl<COLOUR> DrawTarget for RasterImage<COLOUR>
+where
+    COLOUR: PixelColor,
+{
+    type Color = COLOUR;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        for Pixel(point, colour) in pixels {
+            self.set_pixel(point, colour);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image(alpha: &[u8]) -> RasterImage<Rgb888> {
+        RasterImage::new(
+            Size::new(alpha.len() as u32, 1),
+            vec![Rgb888::new(200, 100, 0); alpha.len()],
+        )
+        .unwrap()
+        .with_alpha(alpha)
+        .unwrap()
+    }
+
+    #[test]
+    fn channels_must_match_the_size() {
+        let err = RasterImage::new(Size::new(2, 2), vec![Rgb888::BLACK; 3]).unwrap_err();
+        assert_eq!(err.code(), 1);
+
+        let err = image(&[255; 2]).with_alpha([255; 3]).unwrap_err();
+        assert_eq!(err.code(), 1);
+
+        assert!(RasterImage::new(Size::new(u32::MAX, 2), Vec::<Rgb888>::new()).is_err());
+    }
+
+    #[test]
+    fn composited_over_a_colour() {
+        let composited = image(&[0, 51, 255]).composited_over(Rgb888::new(0, 255, 100));
+
+        assert!(composited.alpha().is_none());
+        assert_eq!(
+            composited.pixels(),
+            [
+                Rgb888::new(0, 255, 100),
+                Rgb888::new(40, 224, 80),
+                Rgb888::new(200, 100, 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn composite_onto_is_clipped_to_the_target() {
+        let background = Rgb888::new(0, 0, 100);
+        let mut target = RasterImage::filled(Size::new(3, 1), background);
+
+        image(&[255, 0, 51]).composite_onto(&mut target, Point::new(1, 0));
+        assert_eq!(
+            target.pixels(),
+            [background, Rgb888::new(200, 100, 0), background]
+        );
+
+        image(&[51, 255]).composite_onto(&mut target, Point::new(-1, 0));
+        assert_eq!(target.pixels()[0], Rgb888::new(200, 100, 0));
+
+        // Entirely outside of the target.
+        image(&[255]).composite_onto(&mut target, Point::new(0, 1));
+        assert!(target.alpha().is_none());
+    }
+
+    #[test]
+    fn opaque_images_are_copied_onto_the_target() {
+        let mut target = RasterImage::filled(Size::new(2, 2), Rgb888::BLACK);
+        let opaque = RasterImage::filled(Size::new(1, 1), Rgb888::WHITE);
+
+        opaque.composite_onto(&mut target, Point::new(1, 1));
+        assert_eq!(target.pixel(Point::new(1, 1)), Some(Rgb888::WHITE));
+        assert_eq!(target.pixel(Point::new(0, 0)), Some(Rgb888::BLACK));
+    }
+
+    #[test]
+    fn drawing_skips_mostly_transparent_pixels() {
+        let mut target = RasterImage::filled(Size::new(2, 1), Rgb888::BLACK);
+
+        image(&[ALPHA_THRESHOLD - 1, ALPHA_THRESHOLD])
+            .draw(&mut target)
+            .unwrap();
+        assert_eq!(target.pixels(), [Rgb888::BLACK, Rgb888::new(200, 100, 0)]);
+    }
+
+    #[test]
+    fn colour_key_adds_to_the_alpha() {
+        let keyed = RasterImage::new(
+            Size::new(3, 1),
+            vec![Rgb888::RED, Rgb888::GREEN, Rgb888::RED],
+        )
+        .unwrap()
+        .with_alpha([255, 255, 128])
+        .unwrap()
+        .with_colour_key(Rgb888::RED);
+
+        assert_eq!(keyed.alpha(), Some(&[0, 255, 0][..]));
+    }
+}
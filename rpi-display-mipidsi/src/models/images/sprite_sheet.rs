@@ -0,0 +1,130 @@
+//! Animations sliced out of a single image.
+//!
+
+use std::time::Duration;
+
+use embedded_graphics::draw_target::DrawTargetExt;
+
+use crate::{foreign_types::*, func, traits::Animation};
+use primitives::Rectangle;
+
+/// An animation made of equally sized frames laid out on a single image, such as
+/// an `OwnedBmp`, in row-major order.
+///
+/// Every frame is shown for the same delay, and is always drawn in full.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SpriteSheet<'s, T> {
+    sheet: &'s T,
+    frame_size: Size,
+    columns: u32,
+    frame_count: usize,
+    delay: Duration,
+}
+
+impl<'s, T, COLOUR> SpriteSheet<'s, T>
+where
+    T: ImageDrawable<Color = COLOUR>,
+    COLOUR: PixelColor + From<<COLOUR as PixelColor>::Raw>,
+{
+    /// Slice the sheet into as many frames of `frame_size` as fit in it.
+    pub fn new(sheet: &'s T, frame_size: Size, delay: Duration) -> RPiResult<Self> {
+        let sheet_size = sheet.size();
+
+        if frame_size.width == 0
+            || frame_size.height == 0
+            || sheet_size.width % frame_size.width != 0
+            || sheet_size.height % frame_size.height != 0
+        {
+            return Err(RPiError::InvalidInput(
+                "frame_size".into(),
+                format!(
+                    "a {}x{} sheet cannot be sliced into {}x{} frames",
+                    sheet_size.width, sheet_size.height, frame_size.width, frame_size.height
+                )
+                .into(),
+            ));
+        }
+
+        let columns = sheet_size.width / frame_size.width;
+        let rows = sheet_size.height / frame_size.height;
+
+        Ok(Self {
+            sheet,
+            frame_size,
+            columns,
+            frame_count: (columns * rows) as usize,
+            delay,
+        })
+    }
+
+    /// Only use the first `frame_count` frames of the sheet, for sheets with empty
+    /// cells at the end.
+    pub fn with_frame_count(mut self, frame_count: usize) -> RPiResult<Self> {
+        if frame_count == 0 || frame_count > self.frame_count {
+            return Err(RPiError::InvalidInput(
+                "frame_count".into(),
+                format!(
+                    "{frame_count} frames requested, but the sheet has {}",
+                    self.frame_count
+                )
+                .into(),
+            ));
+        }
+
+        self.frame_count = frame_count;
+        Ok(self)
+    }
+
+    /// Get the position of the frame at `index` on the sheet.
+    fn frame_position(&self, index: usize) -> Point {
+        let index = index as u32;
+
+        Point::new(
+            ((index % self.columns) * self.frame_size.width) as i32,
+            ((index / self.columns) * self.frame_size.height) as i32,
+        )
+    }
+}
+
+impl<T> OriginDimensions for SpriteSheet<'_, T> {
+    fn size(&self) -> Size {
+        self.frame_size
+    }
+}
+
+impl<'s, T, COLOUR> Animation for SpriteSheet<'s, T>
+where
+    T: ImageDrawable<Color = COLOUR>,
+    COLOUR: PixelColor + From<<COLOUR as PixelColor>::Raw>,
+{
+    type Colour = COLOUR;
+
+    fn frame_count(&self) -> usize {
+        self.frame_count
+    }
+
+    fn frame_delay(&self, _index: usize) -> Duration {
+        self.delay
+    }
+
+    fn draw_frame<DT>(
+        &self,
+        index: usize,
+        area: &Rectangle,
+        target: &mut DT,
+    ) -> Result<(), DT::Error>
+    where
+        DT: DrawTarget<Color = Self::Colour>,
+    {
+        let position = self.frame_position(index);
+        let frame = func::crop::crop_raw(
+            self.sheet,
+            position.x,
+            position.y,
+            self.frame_size.width,
+            self.frame_size.height,
+        );
+
+        frame.draw(&mut target.clipped(area))
+    }
+}
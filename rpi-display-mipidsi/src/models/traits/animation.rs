@@ -0,0 +1,47 @@
+//! Trait for images made of a sequence of frames.
+//!
+
+use std::time::Duration;
+
+use crate::foreign_types::*;
+use primitives::Rectangle;
+
+/// An image made of a sequence of equally sized frames, each shown for its own
+/// delay.
+pub trait Animation: OriginDimensions {
+    /// The colour format of the frames.
+    type Colour: PixelColor;
+
+    /// The number of frames in the animation.
+    fn frame_count(&self) -> usize;
+
+    /// How long the frame at `index` should be shown for.
+    fn frame_delay(&self, index: usize) -> Duration;
+
+    /// The area of the frame at `index` that differs from the frame before it, or
+    /// [`None`] if the whole frame has to be drawn.
+    ///
+    /// The first frame is always drawn in full. A frame with a delta may only hold
+    /// the pixels within it, so it has to be drawn on top of the frame before it;
+    /// frames are always played in order, starting from the first one.
+    fn frame_delta(&self, _index: usize) -> Option<Rectangle> {
+        None
+    }
+
+    /// The number of times the animation should be played, or [`None`] to loop
+    /// forever.
+    fn play_count(&self) -> Option<u32> {
+        None
+    }
+
+    /// Draw the given area of the frame at `index` onto the target, in the
+    /// coordinates of the frame.
+    fn draw_frame<DT>(
+        &self,
+        index: usize,
+        area: &Rectangle,
+        target: &mut DT,
+    ) -> Result<(), DT::Error>
+    where
+        DT: DrawTarget<Color = Self::Colour>;
+}
@@ -5,6 +5,11 @@ use crate::foreign_types::*;
 
 /// A marker trait for functions capable of drawing a frame directly to a
 /// [`DrawTarget`].
+///
+/// A transition of `steps` steps draws the frames `0..=steps`: step `0` shows only
+/// the outgoing image, and step `steps` only the incoming image, so the progress at
+/// each step is `step / steps`. Steps can be skipped, but the last step is always
+/// drawn.
 pub trait DrawTransition<'a, COLOUR, T1, T2, DT>
 where
     DT: DrawTarget<Color = COLOUR>,
@@ -12,8 +17,7 @@ where
     T1: ImageDrawable<Color = COLOUR> + 'a,
     T2: ImageDrawable<Color = COLOUR> + 'a,
 {
-    fn draw_frame(&self, target: &mut DT, from: &'a T1, to: &'a T2, step: u32)
-        -> RPiResult<'a, ()>;
+    fn draw_frame(&self, target: &mut DT, from: &'a T1, to: &'a T2, step: u32) -> RPiResult<()>;
 }
 
 impl<'a, COLOUR, T1, T2, DT, F> DrawTransition<'a, COLOUR, T1, T2, DT> for F
@@ -22,16 +26,10 @@ where
     COLOUR: PixelColor + From<<COLOUR as PixelColor>::Raw>,
     T1: ImageDrawable<Color = COLOUR> + 'a,
     T2: ImageDrawable<Color = COLOUR> + 'a,
-    F: Fn(&mut DT, &'a T1, &'a T2, u32) -> RPiResult<'a, ()>,
+    F: Fn(&mut DT, &'a T1, &'a T2, u32) -> RPiResult<()>,
 {
     /// Calculate the frame for the given step.
-    fn draw_frame(
-        &self,
-        target: &mut DT,
-        from: &'a T1,
-        to: &'a T2,
-        step: u32,
-    ) -> RPiResult<'a, ()> {
+    fn draw_frame(&self, target: &mut DT, from: &'a T1, to: &'a T2, step: u32) -> RPiResult<()> {
         self(target, from, to, step)
     }
 }
@@ -13,8 +13,8 @@ where
     DC: DisplayComponent + Sync,
 {
     /// Execute the interface on the target [`LcdDisplay`].
-    async fn execute<'e>(
+    async fn execute(
         &self,
         display_component: &DC,
-    ) -> RPiResult<'e, Option<Arc<dyn UserInterface<DC>>>>;
+    ) -> RPiResult<Option<Arc<dyn UserInterface<DC>>>>;
 }
@@ -20,13 +20,24 @@ pub trait DisplayComponent: HardwareComponent {
     const H: u16;
 
     /// Clear the display.
-    async fn fill_display<'e>(&self, colour: Self::COLOUR) -> RPiResult<'e, ()>;
+    async fn fill_display(&self, colour: Self::COLOUR) -> RPiResult<()>;
+
+    /// Try to recover the display after `error` ended an interface, returning
+    /// whether the interface should be executed again; `attempt` counts the
+    /// consecutive failures from `1`.
+    ///
+    /// By default nothing is recovered, and the error is returned; boards with a
+    /// [`LcdDisplay`] use [`LcdDisplay::recover`](crate::LcdDisplay::recover), which
+    /// only recovers errors from the display itself.
+    async fn recover_display(&self, _error: &RPiError, _attempt: u32) -> bool {
+        false
+    }
 
     /// Execute the interface on the target [`DisplayComponent`].
-    async fn execute_interface<'e, UI>(
+    async fn execute_interface<UI>(
         &self,
         interface: &UI,
-    ) -> RPiResult<'e, Option<Arc<dyn UserInterface<Self>>>>
+    ) -> RPiResult<Option<Arc<dyn UserInterface<Self>>>>
     where
         Self: Sized + Sync,
         UI: UserInterface<Self> + ?Sized,
@@ -37,17 +48,32 @@ pub trait DisplayComponent: HardwareComponent {
     /// Execute an interface, and if that interface returns another [`UserInterface`],
     /// execute that interface as well. If that interface returns a [`None`] instead,
     /// return to the interface of the upper level.
-    async fn execute_interface_layers<'e>(
+    ///
+    /// If an interface fails, [`recover_display`](Self::recover_display) decides
+    /// whether to execute it again instead of returning the error.
+    async fn execute_interface_layers(
         &self,
         interface: Arc<dyn UserInterface<Self>>,
-    ) -> RPiResult<'e, ()>
+    ) -> RPiResult<()>
     where
         Self: Sized + Sync,
     {
         let mut interfaces = vec![interface];
+        let mut attempt = 0;
 
         while let Some(current_interface) = interfaces.last() {
-            let next_interface = self.execute_interface(current_interface.deref()).await?;
+            let next_interface = match self.execute_interface(current_interface.deref()).await {
+                Ok(next_interface) => next_interface,
+                Err(err) => {
+                    attempt += 1;
+                    if self.recover_display(&err, attempt).await {
+                        continue;
+                    }
+                    return Err(err);
+                }
+            };
+            attempt = 0;
+
             if let Some(next_interface) = next_interface {
                 interfaces.push(next_interface);
             } else {
@@ -62,28 +88,28 @@ pub trait DisplayComponent: HardwareComponent {
 #[async_trait]
 pub trait BacklightComponent {
     /// Turn the backlight on over an interval of time.
-    async fn backlight_fade_in<'e>(&self, step: u32, duration: Duration) -> RPiResult<'e, ()>;
+    async fn backlight_fade_in(&self, step: u32, duration: Duration) -> RPiResult<()>;
 
     /// Turn the backlight off over an interval of time.
-    async fn backlight_fade_out<'e>(&self, step: u32, duration: Duration) -> RPiResult<'e, ()>;
+    async fn backlight_fade_out(&self, step: u32, duration: Duration) -> RPiResult<()>;
 
     /// Turn the backlight on.
-    async fn backlight_on<'e>(&self) -> RPiResult<'e, f64>;
+    async fn backlight_on(&self) -> RPiResult<f64>;
 
     /// Turn the backlight off.
-    async fn backlight_off<'e>(&self) -> RPiResult<'e, f64>;
+    async fn backlight_off(&self) -> RPiResult<f64>;
 }
 
 // #[async_trait]
 // pub trait BacklightDisplayComponent: BacklightComponent + DisplayComponent
 // {
 //     /// Fade in to an interface, and fade out after the interface is complete.
-//     async fn fade_into_interface<'e, UI, R>(
+//     async fn fade_into_interface<UI, R>(
 //         &mut self,
 //         interface: &mut UI,
 //         step: u32,
 //         duration: Duration,
-//     ) -> RPiResult<'e, UI::Return>
+//     ) -> RPiResult<UI::Return>
 //     where
 //         Self: Sized,
 //         UI: UserInterface<Self, Return=R>,
@@ -104,3 +130,177 @@ pub trait BacklightComponent {
 // where
 //     T: BacklightComponent + DisplayComponent,
 // {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use display_interface::DisplayError;
+    use std::{
+        convert::Infallible,
+        io,
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Mutex,
+        },
+    };
+
+    struct MockInterface;
+
+    impl WriteOnlyDataCommand for MockInterface {
+        fn send_commands(&mut self, _cmd: DataFormat<'_>) -> Result<(), DisplayError> {
+            Ok(())
+        }
+
+        fn send_data(&mut self, _buf: DataFormat<'_>) -> Result<(), DisplayError> {
+            Ok(())
+        }
+    }
+
+    struct MockPin;
+
+    impl OutputPinType for MockPin {
+        type Error = Infallible;
+
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    /// A board that recovers from transient display errors up to twice in a row, and
+    /// records the attempts it was asked to recover.
+    #[derive(Default)]
+    struct MockBoard {
+        attempts: Mutex<Vec<u32>>,
+    }
+
+    impl HardwareComponent for MockBoard {}
+
+    #[async_trait]
+    impl DisplayComponent for MockBoard {
+        type COLOUR = pixelcolor::Rgb565;
+        type DI = MockInterface;
+        type MODEL = mipidsi::models::ST7789;
+        type RST = MockPin;
+
+        const W: u16 = 320;
+        const H: u16 = 240;
+
+        async fn fill_display(&self, _colour: Self::COLOUR) -> RPiResult<()> {
+            Ok(())
+        }
+
+        async fn recover_display(&self, error: &RPiError, attempt: u32) -> bool {
+            self.attempts.lock().unwrap().push(attempt);
+            error.is_display_transient() && attempt <= 2
+        }
+    }
+
+    /// An interface that fails with `errors` in turn, then opens `next` once.
+    struct Flaky {
+        errors: Mutex<Vec<RPiError>>,
+        next: Mutex<Option<Arc<dyn UserInterface<MockBoard>>>>,
+        runs: AtomicU32,
+    }
+
+    impl Flaky {
+        fn new(errors: Vec<RPiError>, next: Option<Arc<dyn UserInterface<MockBoard>>>) -> Self {
+            Self {
+                errors: Mutex::new(errors),
+                next: Mutex::new(next),
+                runs: AtomicU32::new(0),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl UserInterface<MockBoard> for Flaky {
+        async fn execute(
+            &self,
+            _board: &MockBoard,
+        ) -> RPiResult<Option<Arc<dyn UserInterface<MockBoard>>>> {
+            self.runs.fetch_add(1, Ordering::Relaxed);
+
+            let mut errors = self.errors.lock().unwrap();
+            if errors.is_empty() {
+                Ok(self.next.lock().unwrap().take())
+            } else {
+                Err(errors.remove(0))
+            }
+        }
+    }
+
+    fn bus_error() -> RPiError {
+        RPiError::DisplayInterfaceError(DisplayError::BusWriteError)
+    }
+
+    #[tokio::test]
+    async fn reruns_interfaces_after_recovery() {
+        let board = MockBoard::default();
+        let inner = Arc::new(Flaky::new(vec![bus_error(), bus_error()], None));
+        let outer = Arc::new(Flaky::new(
+            vec![bus_error()],
+            Some(inner.clone() as Arc<dyn UserInterface<MockBoard>>),
+        ));
+
+        board
+            .execute_interface_layers(outer.clone())
+            .await
+            .expect("Transient errors should be recovered.");
+
+        // The outer interface runs again after its failure, opens the inner one, then
+        // runs once more when the inner one returns.
+        assert_eq!(outer.runs.load(Ordering::Relaxed), 3);
+        assert_eq!(inner.runs.load(Ordering::Relaxed), 3);
+
+        // The attempts count up within a failing interface, and restart after a success.
+        assert_eq!(*board.attempts.lock().unwrap(), vec![1, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn returns_errors_that_are_not_recovered() {
+        let board = MockBoard::default();
+
+        let permanent = Arc::new(Flaky::new(vec![RPiError::Cancelled], None));
+        let result = board.execute_interface_layers(permanent.clone()).await;
+        assert!(matches!(result, Err(RPiError::Cancelled)));
+        assert_eq!(permanent.runs.load(Ordering::Relaxed), 1);
+
+        let persistent = Arc::new(Flaky::new((0..5).map(|_| bus_error()).collect(), None));
+        let result = board.execute_interface_layers(persistent.clone()).await;
+        assert!(matches!(
+            result,
+            Err(RPiError::DisplayInterfaceError(DisplayError::BusWriteError))
+        ));
+        assert_eq!(persistent.runs.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn returns_transient_errors_from_other_devices() {
+        let board = MockBoard::default();
+
+        let waiting = Arc::new(Flaky::new(
+            vec![RPiError::Timeout(
+                "wait for Button A".into(),
+                Duration::from_secs(1),
+            )],
+            None,
+        ));
+        let result = board.execute_interface_layers(waiting.clone()).await;
+        assert!(
+            matches!(result, Err(RPiError::Timeout(_, timeout)) if timeout == Duration::from_secs(1))
+        );
+        assert_eq!(waiting.runs.load(Ordering::Relaxed), 1);
+
+        let reading = Arc::new(Flaky::new(
+            vec![io::Error::from(io::ErrorKind::Interrupted).into()],
+            None,
+        ));
+        let result = board.execute_interface_layers(reading.clone()).await;
+        assert!(matches!(result, Err(RPiError::IOError(_))));
+        assert_eq!(reading.runs.load(Ordering::Relaxed), 1);
+    }
+}
@@ -1,12 +1,23 @@
 //! Public traits used within the crate, and for re-exporting.
 
+mod animation;
+pub use animation::*;
+
 mod interface;
 pub use interface::*;
 
 mod marker;
 pub use marker::*;
 
+mod preset_model;
+pub use preset_model::*;
+
 #[cfg(feature = "transitions")]
 mod draw_transition;
 #[cfg(feature = "transitions")]
 pub use draw_transition::*;
+
+#[cfg(feature = "transitions")]
+mod playable;
+#[cfg(feature = "transitions")]
+pub use playable::*;
@@ -0,0 +1,47 @@
+//! Trait for anything that can be played on a timeline.
+//!
+
+use std::time::Duration;
+
+use crate::foreign_types::*;
+use crate::func::transitions::{PingPong, Repeat, Repeated, Reversed};
+
+/// Something drawn frame by frame onto a `DT` over time, such as a transition.
+pub trait Playable<'a, DT> {
+    /// The interval between frames.
+    fn step_duration(&self) -> Duration;
+
+    /// The total duration, or [`None`] if it plays forever.
+    fn duration(&self) -> Option<Duration>;
+
+    /// Draw the frame at `elapsed` since the start; anything beyond the duration
+    /// draws the last frame.
+    ///
+    /// Returns whether anything was drawn, as the frame may not have changed since
+    /// the last call.
+    fn draw_at(&mut self, target: &mut DT, elapsed: Duration) -> RPiResult<bool>;
+
+    /// Play backwards, from the last frame to the first.
+    fn reversed(self) -> Reversed<Self>
+    where
+        Self: Sized,
+    {
+        Reversed::new(self)
+    }
+
+    /// Play forwards, then backwards.
+    fn ping_pong(self) -> PingPong<Self>
+    where
+        Self: Sized,
+    {
+        PingPong::new(self)
+    }
+
+    /// Play repeatedly.
+    fn repeat(self, repeat: Repeat) -> Repeated<Self>
+    where
+        Self: Sized,
+    {
+        Repeated::new(self, repeat)
+    }
+}
@@ -0,0 +1,18 @@
+//! Panel models identified by name.
+//!
+
+use crate::foreign_types::*;
+
+/// A [`DisplayModel`] that can be constructed without any parameters, and identified
+/// by its name.
+///
+/// This allows a board described at runtime, such as from a configuration file, to
+/// be checked against the panel model it is built with; the model itself is still
+/// chosen at compile time.
+pub trait PresetModel: DisplayModel {
+    /// The name of the panel model, such as `ST7789`.
+    const NAME: &'static str;
+
+    /// Create an instance of the panel model.
+    fn preset() -> Self;
+}
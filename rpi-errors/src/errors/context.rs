@@ -0,0 +1,77 @@
+//! The context an error happened in.
+//!
+
+use std::{borrow::Cow, fmt};
+
+/// The context an [`RPiError`](super::RPiError) happened in: the operation being
+/// carried out, the component carrying it out, and the GPIO pin involved.
+///
+/// A context can be created from an operation alone:
+///
+/// ```ignore
+/// gpio.get(pin).context(ErrorContext::new("claim pin").with_component("Button A").with_pin(pin))?;
+/// gpio.get(pin).context("claim pin")?;
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// The operation being carried out, such as `claim pin`.
+    pub operation: Option<Cow<'static, str>>,
+
+    /// The component carrying out the operation, such as `Button A`.
+    pub component: Option<Cow<'static, str>>,
+
+    /// The BCM number of the GPIO pin involved.
+    pub pin: Option<u8>,
+}
+
+impl ErrorContext {
+    /// Create a new context for the given operation.
+    pub fn new(operation: impl Into<Cow<'static, str>>) -> Self {
+        Self {
+            operation: Some(operation.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Set the component carrying out the operation.
+    pub fn with_component(mut self, component: impl Into<Cow<'static, str>>) -> Self {
+        self.component = Some(component.into());
+        self
+    }
+
+    /// Set the GPIO pin involved.
+    pub fn with_pin(mut self, pin: u8) -> Self {
+        self.pin = Some(pin);
+        self
+    }
+}
+
+impl From<&'static str> for ErrorContext {
+    fn from(operation: &'static str) -> Self {
+        Self::new(operation)
+    }
+}
+
+impl From<String> for ErrorContext {
+    fn from(operation: String) -> Self {
+        Self::new(operation)
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    /// Describe the context, such as ``Failed to claim pin for `Button A` on GPIO 5``.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.operation {
+            Some(operation) => write!(f, "Failed to {operation}")?,
+            None => write!(f, "Failed")?,
+        }
+        if let Some(component) = &self.component {
+            write!(f, " for `{component}`")?;
+        }
+        if let Some(pin) = self.pin {
+            write!(f, " on GPIO {pin}")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,21 @@
+//! Add context to a [`Result`] containing some error type convertible into
+//! [`RPiError`].
+//!
+
+use crate::errors::{ErrorContext, RPiError, ResultContext};
+
+impl<T, E> ResultContext<T> for Result<T, E>
+where
+    E: Into<RPiError>,
+{
+    fn context(self, context: impl Into<ErrorContext>) -> Result<T, RPiError> {
+        self.map_err(|err| err.into().with_context(context))
+    }
+
+    fn with_context<C>(self, context: impl FnOnce() -> C) -> Result<T, RPiError>
+    where
+        C: Into<ErrorContext>,
+    {
+        self.map_err(|err| err.into().with_context(context()))
+    }
+}
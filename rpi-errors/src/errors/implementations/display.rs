@@ -1,31 +1,63 @@
 use super::super::RPiError;
 use display_interface::DisplayError;
 use mipidsi::error::InitError;
+use std::{borrow::Cow, fmt::Debug};
 
-impl<PE> From<InitError<PE>> for RPiError<'_> {
-    /// Convert a [`mipidsi::error::InitError`] into a [`RPiError`].
+impl<PE> From<InitError<PE>> for RPiError
+where
+    PE: Debug,
+{
+    /// Convert a [`mipidsi::error::InitError`] into a [`RPiError`], keeping the
+    /// error of the reset pin.
+    ///
+    /// A failure of the display interface is a [`RPiError::DisplayOutputError`],
+    /// like any other failure to write to the display, so that it can be retried.
     fn from(value: InitError<PE>) -> Self {
         match value {
             InitError::DisplayError => RPiError::DisplayOutputError,
-            InitError::Pin(_) => RPiError::DisplayInitError,
+            InitError::Pin(err) => {
+                RPiError::DisplayInitError(format!("Failed to drive the reset pin: {err:?}").into())
+            }
         }
     }
 }
 
-impl From<DisplayError> for RPiError<'_> {
+impl From<DisplayError> for RPiError {
     /// Convert a [`display_interface::DisplayError`] into a [`RPiError`].
     fn from(value: DisplayError) -> Self {
-        RPiError::DisplayInterfaceError(
-            match value {
-                DisplayError::InvalidFormatError => "Invalid data format selected for interface selected.".into(),
-                DisplayError::BusWriteError => "Failed to write to Bus.".into(),
-                DisplayError::DCError => "Unable to assert or de-assert data/command switching signal.".into(),
-                DisplayError::CSError => "Unable to assert chip select signal.".into(),
-                DisplayError::DataFormatNotImplemented => "The requested DataFormat is not implemented by this display interface implementation.".into(),
-                DisplayError::RSError => "Unable to assert reset signal.".into(),
-                DisplayError::OutOfBoundsError => "The requested pixel is outside the display area.".into(),
-                err => format!("Unknown error occurred: {err:?}").into(),
-            }
-        )
+        RPiError::DisplayInterfaceError(value)
+    }
+}
+
+/// Describe a [`DisplayError`], which does not implement [`std::fmt::Display`].
+pub(crate) fn describe(err: &DisplayError) -> Cow<'static, str> {
+    match err {
+        DisplayError::InvalidFormatError => {
+            "Invalid data format selected for interface selected.".into()
+        }
+        DisplayError::BusWriteError => "Failed to write to Bus.".into(),
+        DisplayError::DCError => {
+            "Unable to assert or de-assert data/command switching signal.".into()
+        }
+        DisplayError::CSError => "Unable to assert chip select signal.".into(),
+        DisplayError::DataFormatNotImplemented => {
+            "The requested DataFormat is not implemented by this display interface implementation."
+                .into()
+        }
+        DisplayError::RSError => "Unable to assert reset signal.".into(),
+        DisplayError::OutOfBoundsError => "The requested pixel is outside the display area.".into(),
+        err => format!("Unknown error occurred: {err:?}").into(),
     }
 }
+
+/// Whether a [`DisplayError`] is likely to go away if retried: failures to drive
+/// the bus or its signals are, while invalid data or formats are not.
+pub(crate) fn is_transient(err: &DisplayError) -> bool {
+    matches!(
+        err,
+        DisplayError::BusWriteError
+            | DisplayError::DCError
+            | DisplayError::CSError
+            | DisplayError::RSError
+    )
+}
@@ -0,0 +1,16 @@
+use super::super::RPiError;
+
+use gif::DecodingError;
+
+impl From<DecodingError> for RPiError {
+    /// Convert a [`gif::DecodingError`] into a [`RPiError`], keeping it as the
+    /// source.
+    fn from(value: DecodingError) -> Self {
+        let message = match &value {
+            DecodingError::Io(err) => format!("Failed to read GIF data: {err}").into(),
+            DecodingError::Format(err) => format!("Invalid GIF data: {err}").into(),
+        };
+
+        RPiError::GIFError(message, Some(Box::new(value)))
+    }
+}
@@ -0,0 +1,11 @@
+use super::super::RPiError;
+use std::convert::Infallible;
+
+impl From<Infallible> for RPiError {
+    /// Convert an [`Infallible`] into a [`RPiError`], which can never happen; this
+    /// allows infallible draw targets, such as framebuffers, to be used anywhere a
+    /// fallible one is.
+    fn from(value: Infallible) -> Self {
+        match value {}
+    }
+}
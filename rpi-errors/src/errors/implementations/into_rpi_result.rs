@@ -3,12 +3,12 @@
 
 use crate::errors::{IntoRPiResult, RPiError};
 
-impl<'e, T, E> IntoRPiResult<'e, T> for Result<T, E>
+impl<T, E> IntoRPiResult<T> for Result<T, E>
 where
-    E: Into<RPiError<'e>>,
+    E: Into<RPiError>,
 {
     /// Convert a [`Result`] containing some other error types into a [`Result<_, RPiError>`].
-    fn into_rpi_result(self) -> Result<T, RPiError<'e>> {
+    fn into_rpi_result(self) -> Result<T, RPiError> {
         self.map_err(|e| e.into())
     }
 }
@@ -4,10 +4,24 @@
 #[cfg(doc)]
 use crate::RPiError;
 
+mod context;
+mod infallible;
 mod into_rpi_result;
 
 #[cfg(feature = "display")]
-mod display;
+pub(super) mod display;
 
 #[cfg(feature = "bmp")]
-mod bmp;
+pub(super) mod bmp;
+
+#[cfg(feature = "png")]
+mod png;
+
+#[cfg(feature = "qoi")]
+mod qoi;
+
+#[cfg(feature = "gif")]
+mod gif;
+
+#[cfg(feature = "ttf")]
+mod ttf;
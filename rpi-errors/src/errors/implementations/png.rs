@@ -0,0 +1,18 @@
+use super::super::RPiError;
+
+use png::DecodingError;
+
+impl From<DecodingError> for RPiError {
+    /// Convert a [`png::DecodingError`] into a [`RPiError`], keeping it as the
+    /// source.
+    fn from(value: DecodingError) -> Self {
+        let message = match &value {
+            DecodingError::IoError(err) => format!("Failed to read PNG data: {err}").into(),
+            DecodingError::Format(err) => format!("Invalid PNG data: {err}").into(),
+            DecodingError::Parameter(err) => format!("Invalid decoding parameter: {err}").into(),
+            DecodingError::LimitsExceeded => "Decoding limits exceeded.".into(),
+        };
+
+        RPiError::PNGError(message, Some(Box::new(value)))
+    }
+}
@@ -0,0 +1,30 @@
+use super::super::RPiError;
+
+use qoi::Error;
+
+impl From<Error> for RPiError {
+    /// Convert a [`qoi::Error`] into a [`RPiError`], keeping it as the source.
+    fn from(value: Error) -> Self {
+        let message = match &value {
+            Error::InvalidMagic { magic } => format!(
+                "Invalid file signature: QOI files must start with `qoif`, but {:?} found.",
+                magic.to_be_bytes()
+            )
+            .into(),
+            Error::InvalidChannels { channels } => {
+                format!("Unsupported number of channels: {channels}.").into()
+            }
+            Error::InvalidColorSpace { colorspace } => {
+                format!("Unsupported colour space: {colorspace}.").into()
+            }
+            Error::InvalidImageDimensions { width, height } => {
+                format!("Invalid image dimensions {width}x{height}.").into()
+            }
+            Error::UnexpectedBufferEnd => "File terminated unexpectedly.".into(),
+            Error::InvalidPadding => "Invalid padding at the end of the image.".into(),
+            err => format!("Unknown error occurred: {err}").into(),
+        };
+
+        RPiError::QOIError(message, Some(Box::new(value)))
+    }
+}
@@ -0,0 +1,11 @@
+use super::super::RPiError;
+
+use ab_glyph::InvalidFont;
+
+impl From<InvalidFont> for RPiError {
+    /// Convert a [`ab_glyph::InvalidFont`] into a [`RPiError`], keeping it as the
+    /// source.
+    fn from(value: InvalidFont) -> Self {
+        RPiError::FontError(value.to_string().into(), Some(Box::new(value)))
+    }
+}
@@ -1,31 +1,39 @@
 //! Error types.
 //!
 
-use std::{borrow::Cow, sync::LockResult, time::Duration};
+use std::{borrow::Cow, io::ErrorKind, sync::LockResult, time::Duration};
 
 use rppal::spi::Error as SpiError;
 
 use thiserror::Error;
 
+mod context;
+pub use context::ErrorContext;
+
 mod implementations;
 mod traits;
-pub use traits::IntoRPiResult;
+pub use traits::{IntoRPiResult, ResultContext};
 
 /// Error type
+///
+/// The error owns all of its data, so it is `Send + Sync + 'static` and can be
+/// sent across tasks, stored, or returned from `main`. Each variant has a stable
+/// numeric [`code`](Self::code), and [`Context`](Self::Context) wraps an error
+/// with the pin, operation and component it happened in.
 #[derive(Error, Debug)]
-pub enum RPiError<'e> {
+pub enum RPiError {
     /// Invalid input; first argument is the variable name, second argument is the input.
     #[error("Invalid input for {0}: {1}")]
-    InvalidInput(Cow<'e, str>, Cow<'e, str>),
+    InvalidInput(Cow<'static, str>, Cow<'static, str>),
 
     #[error("Lock Poisoned when {0}")]
-    Poisoned(Cow<'e, str>),
+    Poisoned(Cow<'static, str>),
 
     #[error("Tokio reported an async Error: {0}")]
     AsyncError(#[from] tokio::task::JoinError),
 
     #[error("Raspberry Pi System level Error: {0}")]
-    System(Cow<'e, str>),
+    System(Cow<'static, str>),
 
     #[error("IO Error: {0}")]
     IOError(#[from] std::io::Error),
@@ -37,44 +45,402 @@ pub enum RPiError<'e> {
     SPI(#[from] SpiError),
 
     #[error("Operation to {0} timed out after {1:?}.")]
-    Timeout(Cow<'e, str>, Duration),
+    Timeout(Cow<'static, str>, Duration),
 
     #[error("Operation Cancelled.")]
     Cancelled,
 
     #[error("`{0}` object is not yet initialised, cannot be used yet.")]
-    NotInitialised(Cow<'e, str>),
+    NotInitialised(Cow<'static, str>),
 
     #[error("`{0}` object is already initialised, cannot be initialised again.")]
-    AlreadyInitialised(Cow<'e, str>),
+    AlreadyInitialised(Cow<'static, str>),
+
+    /// A GPIO pin is already claimed; first argument is the BCM pin number, second
+    /// argument is the current owner, third argument is the function it is used for.
+    #[error("GPIO {0} is already claimed by `{1}` as {2}.")]
+    PinConflict(u8, Cow<'static, str>, Cow<'static, str>),
 
     /// Catch all errors
     #[error("Unexpected error: {0}")]
-    Unknown(Cow<'e, str>),
+    Unknown(Cow<'static, str>),
+
+    /// An error with the context it happened in; the error itself is the
+    /// [`source`](std::error::Error::source).
+    #[error("{context}")]
+    Context {
+        context: ErrorContext,
+        #[source]
+        source: Box<RPiError>,
+    },
 
     #[cfg(feature = "display")]
-    #[error("Display failed to initialise.")]
-    DisplayInitError,
+    #[error("Display failed to initialise: {0}")]
+    DisplayInitError(Cow<'static, str>),
 
+    /// The display interface failed while initialising or drawing to the display.
     #[cfg(feature = "display")]
     #[error("Failed to display content.")]
     DisplayOutputError,
 
+    /// The display interface reported an error; it is kept as is, as it does not
+    /// implement [`std::error::Error`] to be a [`source`](std::error::Error::source).
     #[cfg(feature = "display")]
-    #[error("Display interface reported an error: {0}")]
-    DisplayInterfaceError(Cow<'e, str>),
+    #[error("Display interface reported an error: {}", implementations::display::describe(.0))]
+    DisplayInterfaceError(display_interface::DisplayError),
 
+    /// The Bitmap could not be parsed; it is kept as is, as it does not implement
+    /// [`std::error::Error`] to be a [`source`](std::error::Error::source).
     #[cfg(feature = "bmp")]
-    #[error("Failed to parse BMP image: {0}")]
-    BMPError(Cow<'e, str>),
+    #[error("Failed to parse BMP image: {}", implementations::bmp::describe(.0))]
+    BMPError(tinybmp::ParseError),
+
+    /// The PNG image could not be decoded; first argument is the description,
+    /// second argument is the error of the decoder, if any.
+    #[cfg(feature = "png")]
+    #[error("Failed to decode PNG image: {0}")]
+    PNGError(Cow<'static, str>, #[source] Option<BoxedError>),
+
+    /// The QOI image could not be decoded; first argument is the description,
+    /// second argument is the error of the decoder, if any.
+    #[cfg(feature = "qoi")]
+    #[error("Failed to decode QOI image: {0}")]
+    QOIError(Cow<'static, str>, #[source] Option<BoxedError>),
+
+    /// The GIF image could not be decoded; first argument is the description,
+    /// second argument is the error of the decoder, if any.
+    #[cfg(feature = "gif")]
+    #[error("Failed to decode GIF image: {0}")]
+    GIFError(Cow<'static, str>, #[source] Option<BoxedError>),
+
+    /// The font could not be loaded; first argument is the description, second
+    /// argument is the error of the parser, if any.
+    #[cfg(feature = "font")]
+    #[error("Failed to load font: {0}")]
+    FontError(Cow<'static, str>, #[source] Option<BoxedError>),
 }
 
 /// Result type with the error being [`RPiError`].
-pub type RPiResult<'e, T> = Result<T, RPiError<'e>>;
+pub type RPiResult<T> = Result<T, RPiError>;
+
+/// An error from another crate, kept as the [`source`](std::error::Error::source)
+/// of a [`RPiError`].
+pub type BoxedError = Box<dyn std::error::Error + Send + Sync + 'static>;
 
-impl<'e> RPiError<'e> {
+// The error must be usable across tasks and threads.
+const _: fn() = || {
+    fn assert_owned<T: Send + Sync + 'static>() {}
+    assert_owned::<RPiError>();
+};
+
+impl RPiError {
     /// Create a new [`RPiError::Poisoned`] error from a [`LockResult`].
-    pub fn from_poison_result<T>(result: LockResult<T>, operation: &'e str) -> RPiResult<'e, T> {
+    pub fn from_poison_result<T>(
+        result: LockResult<T>,
+        operation: impl Into<Cow<'static, str>>,
+    ) -> RPiResult<T> {
         result.map_err(|_| RPiError::Poisoned(operation.into()))
     }
+
+    /// Wrap the error with the context it happened in.
+    pub fn with_context(self, context: impl Into<ErrorContext>) -> Self {
+        RPiError::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
+
+    /// Get the outermost context of the error, if any.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            RPiError::Context { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Get the error without any of its [`Context`](Self::Context).
+    pub fn root(&self) -> &RPiError {
+        match self {
+            RPiError::Context { source, .. } => source.root(),
+            err => err,
+        }
+    }
+
+    /// Get the stable numeric code of the error, which is the code of its
+    /// [`root`](Self::root) for [`Context`](Self::Context).
+    ///
+    /// | Code | Variant |
+    /// |------|---------|
+    /// | 1 | [`InvalidInput`](Self::InvalidInput) |
+    /// | 2 | [`Poisoned`](Self::Poisoned) |
+    /// | 3 | [`AsyncError`](Self::AsyncError) |
+    /// | 4 | [`System`](Self::System) |
+    /// | 5 | [`IOError`](Self::IOError) |
+    /// | 6 | [`GPIO`](Self::GPIO) |
+    /// | 7 | [`SPI`](Self::SPI) |
+    /// | 8 | [`Timeout`](Self::Timeout) |
+    /// | 9 | [`Cancelled`](Self::Cancelled) |
+    /// | 10 | [`NotInitialised`](Self::NotInitialised) |
+    /// | 11 | [`AlreadyInitialised`](Self::AlreadyInitialised) |
+    /// | 12 | [`PinConflict`](Self::PinConflict) |
+    /// | 99 | [`Unknown`](Self::Unknown) |
+    /// | 101 | `DisplayInitError` |
+    /// | 102 | `DisplayOutputError` |
+    /// | 103 | `DisplayInterfaceError` |
+    /// | 201 | `BMPError` |
+    /// | 202 | `PNGError` |
+    /// | 203 | `QOIError` |
+    /// | 204 | `GIFError` |
+    /// | 301 | `FontError` |
+    ///
+    /// Codes are never reused or renumbered.
+    pub fn code(&self) -> u16 {
+        match self {
+            RPiError::InvalidInput(..) => 1,
+            RPiError::Poisoned(_) => 2,
+            RPiError::AsyncError(_) => 3,
+            RPiError::System(_) => 4,
+            RPiError::IOError(_) => 5,
+            RPiError::GPIO(_) => 6,
+            RPiError::SPI(_) => 7,
+            RPiError::Timeout(..) => 8,
+            RPiError::Cancelled => 9,
+            RPiError::NotInitialised(_) => 10,
+            RPiError::AlreadyInitialised(_) => 11,
+            RPiError::PinConflict(..) => 12,
+            RPiError::Unknown(_) => 99,
+            RPiError::Context { source, .. } => source.code(),
+            #[cfg(feature = "display")]
+            RPiError::DisplayInitError(_) => 101,
+            #[cfg(feature = "display")]
+            RPiError::DisplayOutputError => 102,
+            #[cfg(feature = "display")]
+            RPiError::DisplayInterfaceError(_) => 103,
+            #[cfg(feature = "bmp")]
+            RPiError::BMPError(..) => 201,
+            #[cfg(feature = "png")]
+            RPiError::PNGError(..) => 202,
+            #[cfg(feature = "qoi")]
+            RPiError::QOIError(..) => 203,
+            #[cfg(feature = "gif")]
+            RPiError::GIFError(..) => 204,
+            #[cfg(feature = "font")]
+            RPiError::FontError(..) => 301,
+        }
+    }
+
+    /// Whether the error is likely to go away if the operation is retried, such as
+    /// a failed write to the SPI bus; looks through any [`Context`](Self::Context).
+    ///
+    /// Errors from invalid input, missing resources or failed initialisation are
+    /// never transient; IO errors are only transient if they were interrupted or
+    /// timed out, SPI errors only if a transfer on the bus failed, and display
+    /// interface errors only if driving the bus or its signals failed.
+    pub fn is_transient(&self) -> bool {
+        match self.root() {
+            RPiError::IOError(err) => is_transient_io(err),
+            // A failed transfer is transient; a missing or misconfigured bus is not.
+            RPiError::SPI(SpiError::Io(err)) => !matches!(
+                err.kind(),
+                ErrorKind::NotFound | ErrorKind::PermissionDenied | ErrorKind::InvalidInput
+            ),
+            RPiError::Timeout(..) => true,
+            #[cfg(feature = "display")]
+            RPiError::DisplayOutputError => true,
+            #[cfg(feature = "display")]
+            RPiError::DisplayInterfaceError(err) => implementations::display::is_transient(err),
+            _ => false,
+        }
+    }
+
+    /// Whether the error is [transient](Self::is_transient) and came from driving a
+    /// display, such as a failed write to the SPI bus; looks through any
+    /// [`Context`](Self::Context).
+    ///
+    /// Transient errors from anything else, such as a button timing out or an
+    /// interrupted file read, are not worth retrying or resetting the display for.
+    pub fn is_display_transient(&self) -> bool {
+        match self.root() {
+            RPiError::SPI(..) => self.is_transient(),
+            #[cfg(feature = "display")]
+            RPiError::DisplayOutputError | RPiError::DisplayInterfaceError(..) => {
+                self.is_transient()
+            }
+            _ => false,
+        }
+    }
+
+    /// Describe the error followed by all of its sources, such as
+    /// ``Failed to claim pin for `Button A` on GPIO 5: GPIO Error: Pin 5 is not available``.
+    pub fn report(&self) -> String {
+        let mut report = self.to_string();
+        let mut source = std::error::Error::source(self);
+
+        while let Some(err) = source {
+            // Variants wrapping another error already include its message.
+            let message = err.to_string();
+            if !report.ends_with(&message) {
+                report.push_str(&format!(": {message}"));
+            }
+            source = err.source();
+        }
+
+        report
+    }
+}
+
+/// Whether an IO error is likely to go away if the operation is retried.
+fn is_transient_io(err: &std::io::Error) -> bool {
+    matches!(
+        err.kind(),
+        ErrorKind::Interrupted | ErrorKind::TimedOut | ErrorKind::WouldBlock
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn io_error(kind: ErrorKind) -> RPiError {
+        std::io::Error::new(kind, "device busy").into()
+    }
+
+    #[test]
+    fn codes_are_stable() {
+        assert_eq!(RPiError::InvalidInput("pin".into(), "99".into()).code(), 1);
+        assert_eq!(RPiError::Poisoned("reading".into()).code(), 2);
+        assert_eq!(RPiError::System("no model".into()).code(), 4);
+        assert_eq!(io_error(ErrorKind::NotFound).code(), 5);
+        assert_eq!(
+            RPiError::Timeout("read".into(), Duration::from_secs(1)).code(),
+            8
+        );
+        assert_eq!(RPiError::Cancelled.code(), 9);
+        assert_eq!(RPiError::NotInitialised("Gpio".into()).code(), 10);
+        assert_eq!(RPiError::AlreadyInitialised("Gpio".into()).code(), 11);
+        assert_eq!(
+            RPiError::PinConflict(5, "Button A".into(), "input".into()).code(),
+            12
+        );
+        assert_eq!(RPiError::Unknown("?".into()).code(), 99);
+    }
+
+    #[test]
+    fn context_keeps_the_code_of_its_root() {
+        let err = RPiError::PinConflict(5, "Button A".into(), "input".into())
+            .with_context("claim pin")
+            .with_context(ErrorContext::new("create board").with_component("Display HAT"));
+
+        assert_eq!(err.code(), 12);
+        assert!(matches!(err.root(), RPiError::PinConflict(5, ..)));
+        assert_eq!(
+            err.context(),
+            Some(&ErrorContext::new("create board").with_component("Display HAT"))
+        );
+    }
+
+    #[test]
+    fn context_display() {
+        assert_eq!(ErrorContext::default().to_string(), "Failed");
+        assert_eq!(
+            ErrorContext::new("claim pin").to_string(),
+            "Failed to claim pin"
+        );
+        assert_eq!(
+            ErrorContext::new("claim pin")
+                .with_component("Button A")
+                .with_pin(5)
+                .to_string(),
+            "Failed to claim pin for `Button A` on GPIO 5"
+        );
+        assert_eq!(
+            ErrorContext::default().with_pin(5).to_string(),
+            "Failed on GPIO 5"
+        );
+    }
+
+    #[test]
+    fn report_follows_the_chain() {
+        let err = io_error(ErrorKind::NotFound)
+            .with_context(ErrorContext::new("read config").with_component("Board"));
+
+        assert_eq!(err.to_string(), "Failed to read config for `Board`");
+        assert_eq!(
+            err.report(),
+            "Failed to read config for `Board`: IO Error: device busy"
+        );
+    }
+
+    #[test]
+    fn report_does_not_repeat_wrapped_messages() {
+        let err = RPiError::Unknown("lost".into())
+            .with_context("draw")
+            .with_context("show menu");
+
+        assert_eq!(
+            err.report(),
+            "Failed to show menu: Failed to draw: Unexpected error: lost"
+        );
+    }
+
+    #[test]
+    fn transient_errors() {
+        assert!(io_error(ErrorKind::Interrupted).is_transient());
+        assert!(io_error(ErrorKind::TimedOut)
+            .with_context("write")
+            .is_transient());
+        assert!(!io_error(ErrorKind::NotFound).is_transient());
+        assert!(RPiError::Timeout("read".into(), Duration::from_secs(1)).is_transient());
+        assert!(!RPiError::InvalidInput("pin".into(), "99".into()).is_transient());
+        assert!(!RPiError::SPI(SpiError::Io(ErrorKind::NotFound.into())).is_transient());
+        assert!(RPiError::SPI(SpiError::Io(ErrorKind::BrokenPipe.into())).is_transient());
+    }
+
+    #[test]
+    fn display_transient_errors() {
+        assert!(RPiError::SPI(SpiError::Io(ErrorKind::BrokenPipe.into()))
+            .with_context("draw")
+            .is_display_transient());
+        assert!(!RPiError::SPI(SpiError::Io(ErrorKind::NotFound.into())).is_display_transient());
+        assert!(!io_error(ErrorKind::Interrupted).is_display_transient());
+        assert!(!RPiError::Timeout("read".into(), Duration::from_secs(1)).is_display_transient());
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn png_errors_keep_their_source() {
+        let err: RPiError = png::Decoder::new(&b"not a png"[..])
+            .read_info()
+            .map(|_| ())
+            .unwrap_err()
+            .into();
+
+        assert_eq!(err.code(), 202);
+        assert!(std::error::Error::source(&err).is_some());
+        assert!(err
+            .report()
+            .starts_with("Failed to decode PNG image: Invalid PNG data: "));
+    }
+
+    #[cfg(feature = "qoi")]
+    #[test]
+    fn qoi_errors_keep_their_source() {
+        let err: RPiError = qoi::decode_to_vec(b"qoif").unwrap_err().into();
+
+        assert_eq!(err.code(), 203);
+        assert!(std::error::Error::source(&err)
+            .is_some_and(|source| source.downcast_ref::<qoi::Error>().is_some()));
+    }
+
+    #[cfg(feature = "bmp")]
+    #[test]
+    fn bmp_errors_are_described() {
+        let err = RPiError::from(tinybmp::ParseError::UnexpectedEndOfFile);
+
+        assert_eq!(err.code(), 201);
+        assert_eq!(
+            err.to_string(),
+            "Failed to parse BMP image: File terminated unexpectedly."
+        );
+    }
 }
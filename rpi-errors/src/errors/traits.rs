@@ -1,8 +1,21 @@
 //! Traits for error handling.
 //!
-use crate::errors::RPiError;
+use crate::errors::{ErrorContext, RPiError};
 
 // Convert a [`Result`] containing some other error types into a [`Result<_, RPiError>`].
-pub trait IntoRPiResult<'e, T> {
-    fn into_rpi_result(self) -> Result<T, RPiError<'e>>;
+pub trait IntoRPiResult<T> {
+    fn into_rpi_result(self) -> Result<T, RPiError>;
+}
+
+/// Add the context an error happened in to a [`Result`], converting its error into
+/// a [`RPiError`].
+pub trait ResultContext<T> {
+    /// Wrap the error with the given context.
+    fn context(self, context: impl Into<ErrorContext>) -> Result<T, RPiError>;
+
+    /// Wrap the error with the context returned by `context`, which is only called
+    /// on error.
+    fn with_context<C>(self, context: impl FnOnce() -> C) -> Result<T, RPiError>
+    where
+        C: Into<ErrorContext>;
 }
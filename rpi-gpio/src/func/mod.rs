@@ -1,10 +1,22 @@
 //! Helper functions.
 //!
 
-use rppal::gpio::Gpio;
+use rppal::gpio::{Gpio, Pin};
 pub mod termination;
 
+use rpi_errors::{ErrorContext, RPiResult, ResultContext};
+
 /// Initialize GPIO interface.
 pub fn init_gpio() -> Result<Gpio, rppal::gpio::Error> {
     Gpio::new()
 }
+
+/// Get a pin from the GPIO interface on behalf of `owner`, naming both in the
+/// error if the pin is unavailable.
+pub fn get_pin(gpio: &Gpio, pin: u8, owner: &'static str) -> RPiResult<Pin> {
+    gpio.get(pin).with_context(|| {
+        ErrorContext::new("get pin")
+            .with_component(owner)
+            .with_pin(pin)
+    })
+}
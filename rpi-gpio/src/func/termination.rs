@@ -7,12 +7,12 @@ use std::time::Duration;
 use rpi_errors::{IntoRPiResult, RPiResult};
 
 /// Wait for a a `SIGTERM` to be issued.
-pub async fn ctrl_c<'e>() -> RPiResult<'e, ()> {
+pub async fn ctrl_c() -> RPiResult<()> {
     tokio::signal::ctrl_c().await.into_rpi_result()
 }
 
 /// Wait for a certain amount of time to pass; then returns [`Ok(())`].
-pub async fn timeout<'e>(duration: Duration) -> RPiResult<'e, ()> {
+pub async fn timeout(duration: Duration) -> RPiResult<()> {
     tokio::time::sleep(duration).await;
 
     Ok(())
@@ -20,7 +20,7 @@ pub async fn timeout<'e>(duration: Duration) -> RPiResult<'e, ()> {
 
 /// Wait for a certain amount of time to pass; then returns [`Ok(())`]. If the
 /// duration is [`None`], then wait forever.
-pub async fn timeout_opt<'e>(duration_opt: Option<Duration>) -> RPiResult<'e, ()> {
+pub async fn timeout_opt(duration_opt: Option<Duration>) -> RPiResult<()> {
     if let Some(duration) = duration_opt {
         tokio::time::sleep(duration).await;
     } else {
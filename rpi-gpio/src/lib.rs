@@ -1,7 +1,7 @@
 //! A library for interacting with GPIO pins on a Raspberry Pi, with all polling done
 //! asynchronously.
 //!
-//! Supports [`Button`]s and [RGB LED]s.
+//! Supports [`Button`]s, [`RotaryEncoder`]s and [RGB LED]s.
 //!
 //! This is currently written for the express purpose of using a
 //! [Pimoroni Display HAT Mini] on a Pi Zero 2W; features are added as required.
@@ -15,6 +15,8 @@ pub mod config;
 
 pub mod func;
 
+pub mod registry;
+
 mod models;
 pub use models::*;
 
@@ -4,25 +4,31 @@
 use rppal::gpio::{Gpio, InputPin};
 use std::time::Duration;
 
-use crate::func::termination;
+use crate::{
+    func::{self, termination},
+    registry::{self, PinFunction, PinGuard},
+};
 use rpi_errors::{RPiError, RPiResult};
 
 /// A physical button connected via GPIO.
 pub struct Button {
     pin: InputPin,
+    _claim: PinGuard,
 }
 
 impl Button {
     /// Create a new button on the given pin.
     pub fn try_new(gpio: &Gpio, pin: u8) -> RPiResult<Self> {
-        let pin = gpio.get(pin)?.into_input_pullup();
-        Ok(Self { pin })
+        let claim = registry::claim_pin(pin, PinFunction::Input, "Button")?;
+        let pin = func::get_pin(gpio, pin, "Button")?.into_input_pullup();
+        Ok(Self { pin, _claim: claim })
     }
 
     /// Create a new button on the given pin with the High/Low states inverted.
     pub fn try_new_inverted(gpio: &Gpio, pin: u8) -> RPiResult<Self> {
-        let pin = gpio.get(pin)?.into_input_pulldown();
-        Ok(Self { pin })
+        let claim = registry::claim_pin(pin, PinFunction::Input, "Button")?;
+        let pin = func::get_pin(gpio, pin, "Button")?.into_input_pulldown();
+        Ok(Self { pin, _claim: claim })
     }
 
     /// Create a new button on the given pin; if it fails, panic.
@@ -48,11 +54,11 @@ impl Button {
     }
 
     /// Blocks until the button is pressed, then eturns `true`.
-    pub async fn wait_until_pressed_state<'e>(
+    pub async fn wait_until_pressed_state(
         &self,
         state: bool,
         timeout: Option<Duration>,
-    ) -> RPiResult<'e, bool> {
+    ) -> RPiResult<bool> {
         tokio::select! {
             state = async {
                 loop {
@@ -72,27 +78,27 @@ impl Button {
     }
 
     /// Blocks until the button is pressed, then eturns `true`.
-    pub async fn pressed<'e>(&self, timeout: Option<Duration>) -> RPiResult<'e, bool> {
+    pub async fn pressed(&self, timeout: Option<Duration>) -> RPiResult<bool> {
         self.wait_until_pressed_state(true, timeout).await
     }
 
     /// Executes `func` and returns the return value when the button is pressed.
-    pub async fn pressed_then<'e, T>(
+    pub async fn pressed_then<T>(
         &self,
-        func: impl Fn(RPiResult<'e, bool>) -> RPiResult<'e, T>,
+        func: impl Fn(RPiResult<bool>) -> RPiResult<T>,
         timeout: Option<Duration>,
-    ) -> RPiResult<'e, T> {
+    ) -> RPiResult<T> {
         func(self.pressed(timeout).await)
     }
 
     /// Returns `true` when the button is released.
-    pub async fn released<'e>(&self, timeout: Option<Duration>) -> RPiResult<'e, bool> {
+    pub async fn released(&self, timeout: Option<Duration>) -> RPiResult<bool> {
         self.wait_until_pressed_state(false, timeout).await
     }
 
     /// Wait until the button is pressed for the first time, and then released;
     /// then returns `true`.
-    pub async fn pressed_and_released<'e>(&self, timeout: Option<Duration>) -> RPiResult<'e, bool> {
+    pub async fn pressed_and_released(&self, timeout: Option<Duration>) -> RPiResult<bool> {
         self.pressed(timeout).await?;
         self.released(timeout).await
     }
@@ -100,11 +106,11 @@ impl Button {
     /// Wait until the button is pressed for the first time, perform a callback;
     /// then another when released. Finally return the final return value of the
     /// callback.
-    pub async fn pressed_and_released_then<'e, T>(
+    pub async fn pressed_and_released_then<T>(
         &self,
-        func: impl Fn(RPiResult<'e, bool>) -> RPiResult<'e, T>,
+        func: impl Fn(RPiResult<bool>) -> RPiResult<T>,
         timeout: Option<Duration>,
-    ) -> RPiResult<'e, T> {
+    ) -> RPiResult<T> {
         let start_time = tokio::time::Instant::now();
 
         func(self.pressed(timeout).await)?;
@@ -117,10 +123,10 @@ impl Button {
 
     /// Infinitely loop the button press callback - execute the callback when
     /// pressed and released, then repeat.
-    pub async fn release_event_loop<'e, T>(
+    pub async fn release_event_loop<T>(
         &self,
-        func: impl Fn(RPiResult<'e, bool>) -> RPiResult<'e, T>,
-    ) -> RPiResult<'e, T> {
+        func: impl Fn(RPiResult<bool>) -> RPiResult<T>,
+    ) -> RPiResult<T> {
         loop {
             func(self.pressed(None).await)?;
             func(self.released(None).await)?;
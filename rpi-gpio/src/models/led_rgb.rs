@@ -7,7 +7,8 @@ use embedded_graphics::pixelcolor::{Rgb888, RgbColor};
 use rppal::gpio::{Gpio, OutputPin};
 
 use crate::{
-    func::termination,
+    func::{self, termination},
+    registry::{self, PinFunction, PinGuard},
     traits::{CanRgbTransition, FromTupleRGB},
 };
 use rpi_errors::{RPiError, RPiResult};
@@ -17,6 +18,7 @@ pub struct RgbLed {
     red: OutputPin,
     green: OutputPin,
     blue: OutputPin,
+    _claims: Vec<PinGuard>,
     frequency: f64,
 
     last_value: (u8, u8, u8),
@@ -26,14 +28,16 @@ pub struct RgbLed {
 impl RgbLed {
     /// Create a new RGB LED light on the given pins.
     pub fn try_new(gpio: &Gpio, red: u8, green: u8, blue: u8, frequency: f64) -> RPiResult<Self> {
-        let red = gpio.get(red)?.into_output();
-        let green = gpio.get(green)?.into_output();
-        let blue = gpio.get(blue)?.into_output();
+        let claims = registry::claim_pins(&[red, green, blue], PinFunction::Pwm, "RgbLed")?;
+        let red = func::get_pin(gpio, red, "RgbLed")?.into_output();
+        let green = func::get_pin(gpio, green, "RgbLed")?.into_output();
+        let blue = func::get_pin(gpio, blue, "RgbLed")?.into_output();
 
         let mut led = Self {
             red,
             green,
             blue,
+            _claims: claims,
             frequency,
             last_value: (0, 0, 0),
             enabled: false,
@@ -62,7 +66,7 @@ impl RgbLed {
 
     /// Get the last value set for the LED, and set it to the LED.
     /// Used to restore the LED to its last value after it has been disabled.
-    pub fn load_last_value<'e>(&mut self) -> RPiResult<'e, (u8, u8, u8)> {
+    pub fn load_last_value(&mut self) -> RPiResult<(u8, u8, u8)> {
         let red = self.last_value.0;
         let green = self.last_value.1;
         let blue = self.last_value.2;
@@ -86,7 +90,7 @@ impl RgbLed {
 
     /// Internal function to set the raw values for the LED without recording
     /// the last value.
-    fn set_raw_values<'e>(&mut self, red: u8, green: u8, blue: u8) -> RPiResult<'e, ()> {
+    fn set_raw_values(&mut self, red: u8, green: u8, blue: u8) -> RPiResult<()> {
         macro_rules! expand_colours {
             (
                 $($color:ident),*
@@ -102,7 +106,7 @@ impl RgbLed {
     }
 
     /// Set the values for the LED, and return the previous values.
-    pub fn set_values<'e>(&mut self, red: u8, green: u8, blue: u8) -> RPiResult<'e, (u8, u8, u8)> {
+    pub fn set_values(&mut self, red: u8, green: u8, blue: u8) -> RPiResult<(u8, u8, u8)> {
         self.set_raw_values(red, green, blue)?;
 
         self.enabled = (red != 0) || (green != 0) || (blue != 0);
@@ -112,7 +116,7 @@ impl RgbLed {
 
     /// Set the values for the LED using the given [`RgbColor`], and return the
     /// previous values.
-    pub fn set_rgb<'e, RGB>(&mut self, rgb: &RGB) -> RPiResult<'e, RGB>
+    pub fn set_rgb<RGB>(&mut self, rgb: &RGB) -> RPiResult<RGB>
     where
         RGB: RgbColor + FromTupleRGB,
     {
@@ -126,7 +130,7 @@ impl RgbLed {
     }
 
     /// Disable the LED.
-    pub fn disable<'e>(&mut self) -> RPiResult<'e, ()> {
+    pub fn disable(&mut self) -> RPiResult<()> {
         self.set_raw_values(0, 0, 0)?;
 
         self.enabled = false;
@@ -135,7 +139,7 @@ impl RgbLed {
     }
 
     /// Enable the LED.
-    pub fn enable<'e>(&mut self) -> RPiResult<'e, (u8, u8, u8)> {
+    pub fn enable(&mut self) -> RPiResult<(u8, u8, u8)> {
         let prev_colours = self.load_last_value()?;
         self.enabled = true;
 
@@ -144,12 +148,12 @@ impl RgbLed {
 
     /// Transition the LED to the given [`RgbColor`], using the given number of
     /// steps and duration.
-    pub async fn transition_to_rgb<'e, RGB>(
+    pub async fn transition_to_rgb<RGB>(
         &mut self,
         dest: &RGB,
         steps: u32,
         duration: Duration,
-    ) -> RPiResult<'e, ()>
+    ) -> RPiResult<()>
     where
         RGB: RgbColor + FromTupleRGB,
     {
@@ -179,14 +183,14 @@ impl RgbLed {
 
     /// Transition the LED to the given RGB values, using the given number of
     /// steps and duration.
-    pub async fn transition_to<'e>(
+    pub async fn transition_to(
         &mut self,
         red: u8,
         green: u8,
         blue: u8,
         steps: u32,
         duration: Duration,
-    ) -> RPiResult<'e, ()> {
+    ) -> RPiResult<()> {
         self.transition_to_rgb(&Rgb888::new(red, green, blue), steps, duration)
             .await
     }
@@ -7,4 +7,7 @@ pub use led_rgb::*;
 mod pwm_device;
 pub use pwm_device::*;
 
+mod rotary_encoder;
+pub use rotary_encoder::*;
+
 pub mod traits;
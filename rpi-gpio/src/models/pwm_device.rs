@@ -2,11 +2,15 @@
 //!
 use rppal::gpio::{Gpio, OutputPin};
 
-use crate::func::termination;
+use crate::{
+    func::{self, termination},
+    registry::{self, PinFunction, PinGuard},
+};
 use rpi_errors::{RPiError, RPiResult};
 
 pub struct PwmDevice {
     pin: OutputPin,
+    _claim: PinGuard,
     frequency: f64,
 
     last_value: f64,
@@ -16,10 +20,12 @@ pub struct PwmDevice {
 impl PwmDevice {
     /// Create a new PWM device on the given pin.
     pub fn try_new(gpio: &Gpio, pin: u8, frequency: f64) -> RPiResult<Self> {
-        let pin = gpio.get(pin)?.into_output();
+        let claim = registry::claim_pin(pin, PinFunction::Pwm, "PwmDevice")?;
+        let pin = func::get_pin(gpio, pin, "PwmDevice")?.into_output();
 
         let mut device = Self {
             pin,
+            _claim: claim,
             frequency,
             last_value: 0.,
             enabled: false,
@@ -44,7 +50,7 @@ impl PwmDevice {
 
     /// Get the last value set for the device, and set it as the current value.
     /// Used to restore the device to its last value after it has been disabled.
-    pub fn load_last_value<'e>(&mut self) -> RPiResult<'e, f64> {
+    pub fn load_last_value(&mut self) -> RPiResult<f64> {
         let value = self.last_value;
 
         self.set_raw_value(value)
@@ -58,7 +64,7 @@ impl PwmDevice {
 
     /// Internal function to set the raw values for the LED without recording the
     /// last value.
-    fn set_raw_value<'e>(&mut self, value: f64) -> RPiResult<'e, ()> {
+    fn set_raw_value(&mut self, value: f64) -> RPiResult<()> {
         if !(0. ..=1.).contains(&value) {
             return Err(RPiError::InvalidInput(
                 "Pwm value".into(),
@@ -72,33 +78,33 @@ impl PwmDevice {
     }
 
     /// Set the value of the device, and returns the previous value.
-    pub fn set_value<'e>(&mut self, value: f64) -> RPiResult<'e, f64> {
+    pub fn set_value(&mut self, value: f64) -> RPiResult<f64> {
         self.set_raw_value(value)?;
         self.enabled = value > 0.;
         Ok(self.set_last_value(value))
     }
 
     /// Disable the Device.
-    pub fn disable<'e>(&mut self) -> RPiResult<'e, ()> {
+    pub fn disable(&mut self) -> RPiResult<()> {
         self.set_raw_value(0.)?;
         self.enabled = false;
         Ok(())
     }
 
     /// Enable the Device.
-    pub fn enable<'e>(&mut self) -> RPiResult<'e, f64> {
+    pub fn enable(&mut self) -> RPiResult<f64> {
         let prev_value = self.load_last_value()?;
         Ok(prev_value)
     }
 
     /// Transition the device to the given value, using the given number of steps and
     /// duration.
-    pub async fn transition_to<'e>(
+    pub async fn transition_to(
         &mut self,
         value: f64,
         steps: u32,
         duration: std::time::Duration,
-    ) -> RPiResult<'e, ()> {
+    ) -> RPiResult<()> {
         if steps == 0 {
             return Err(RPiError::InvalidInput(
                 "steps".into(),
@@ -0,0 +1,495 @@
+//! Async structure for a physical quadrature rotary encoder connected via GPIO.
+//!
+
+use async_trait::async_trait;
+use futures::Stream;
+use rppal::gpio::{Gpio, InputPin, Level, Trigger};
+use std::{
+    sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    time::Duration,
+};
+use tokio::sync::{mpsc, Mutex as AsyncMutex};
+
+use crate::{
+    func::{self, termination},
+    registry::{self, PinFunction, PinGuard},
+    traits::{Navigation, NavigationInput},
+    Button,
+};
+use rpi_errors::{ErrorContext, RPiError, RPiResult, ResultContext};
+
+/// The direction of a single detent step of a [`RotaryEncoder`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RotaryDirection {
+    Clockwise,
+    CounterClockwise,
+}
+
+impl RotaryDirection {
+    /// Return the change in position that this step represents.
+    pub fn delta(&self) -> i64 {
+        match self {
+            Self::Clockwise => 1,
+            Self::CounterClockwise => -1,
+        }
+    }
+}
+
+/// A state machine decoding the Gray code emitted by the two pins of a quadrature
+/// encoder.
+///
+/// Every valid transition moves an internal counter by one; a step is only reported
+/// once the counter reaches the number of transitions per detent, so that a single
+/// click of the knob is reported exactly once.
+#[derive(Clone, Copy, Debug)]
+pub struct QuadratureDecoder {
+    state: u8,
+    accumulator: i8,
+    transitions_per_detent: i8,
+}
+
+impl QuadratureDecoder {
+    /// Lookup table indexed by `(previous_state << 2) | current_state`.
+    ///
+    /// Transitions where both pins changed at once are invalid (bounced or missed
+    /// readings), and are counted as `0`.
+    const TRANSITIONS: [i8; 16] = [0, -1, 1, 0, 1, 0, 0, -1, -1, 0, 0, 1, 0, 1, -1, 0];
+
+    /// Create a new decoder, starting from the given pin levels.
+    ///
+    /// `transitions_per_detent` is typically `4` for encoders with one full
+    /// quadrature cycle per click, `2` or `1` for cheaper ones.
+    pub fn new(a: bool, b: bool, transitions_per_detent: u8) -> Self {
+        Self {
+            state: Self::encode(a, b),
+            accumulator: 0,
+            transitions_per_detent: transitions_per_detent.clamp(1, 4) as i8,
+        }
+    }
+
+    /// Encode the levels of the two pins into a 2-bit state.
+    fn encode(a: bool, b: bool) -> u8 {
+        ((a as u8) << 1) | b as u8
+    }
+
+    /// Feed the current pin levels into the decoder; returns the direction if a full
+    /// detent has been completed.
+    ///
+    /// Pin A leading pin B is treated as [`RotaryDirection::Clockwise`]; swap the pins
+    /// if the encoder is wired the other way round.
+    pub fn update(&mut self, a: bool, b: bool) -> Option<RotaryDirection> {
+        let current = Self::encode(a, b);
+        if current == self.state {
+            return None;
+        }
+
+        self.accumulator += Self::TRANSITIONS[((self.state << 2) | current) as usize];
+        self.state = current;
+
+        if self.accumulator >= self.transitions_per_detent {
+            self.accumulator = 0;
+            Some(RotaryDirection::Clockwise)
+        } else if self.accumulator <= -self.transitions_per_detent {
+            self.accumulator = 0;
+            Some(RotaryDirection::CounterClockwise)
+        } else {
+            None
+        }
+    }
+}
+
+/// The levels last seen on the two pins of a [`RotaryEncoder`], fed into a
+/// [`QuadratureDecoder`] as either of them changes.
+#[derive(Clone, Copy, Debug)]
+struct EdgeDecoder {
+    a: bool,
+    b: bool,
+    decoder: QuadratureDecoder,
+}
+
+impl EdgeDecoder {
+    fn new(a: bool, b: bool, transitions_per_detent: u8) -> Self {
+        Self {
+            a,
+            b,
+            decoder: QuadratureDecoder::new(a, b, transitions_per_detent),
+        }
+    }
+
+    /// Feed a new level of pin A into the decoder.
+    fn update_a(&mut self, level: bool) -> Option<RotaryDirection> {
+        self.a = level;
+        self.decoder.update(self.a, self.b)
+    }
+
+    /// Feed a new level of pin B into the decoder.
+    fn update_b(&mut self, level: bool) -> Option<RotaryDirection> {
+        self.b = level;
+        self.decoder.update(self.a, self.b)
+    }
+}
+
+/// The state of a [`RotaryEncoder`] shared with the interrupt handlers of its pins.
+struct EncoderState {
+    decoder: Mutex<EdgeDecoder>,
+    position: AtomicI64,
+    steps: mpsc::Sender<RotaryDirection>,
+}
+
+impl EncoderState {
+    /// Apply an edge to the decoder, updating the position and queueing the step if
+    /// a detent has been completed.
+    fn edge(&self, update: impl FnOnce(&mut EdgeDecoder) -> Option<RotaryDirection>) {
+        // The decoder is never left half updated, so a poisoned lock is still usable.
+        let mut decoder = self.decoder.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(direction) = update(&mut decoder) {
+            self.position
+                .fetch_add(direction.delta(), Ordering::Relaxed);
+
+            // If nothing is waiting for steps, the position is still kept up to date.
+            let _ = self.steps.try_send(direction);
+        }
+    }
+}
+
+/// A physical quadrature rotary encoder connected via GPIO, with an optional push
+/// switch.
+///
+/// The pins are decoded on their edge interrupts, in the background, so the
+/// [`position`](Self::position) is kept up to date whether or not anything is
+/// waiting for the next step. Steps not yet waited for are queued, up to
+/// [`STEP_QUEUE`](Self::STEP_QUEUE) of them.
+pub struct RotaryEncoder {
+    // Dropping the pins clears their interrupts.
+    _pin_a: InputPin,
+    _pin_b: InputPin,
+    _claims: Vec<PinGuard>,
+    switch: Option<Button>,
+
+    state: Arc<EncoderState>,
+    steps: AsyncMutex<mpsc::Receiver<RotaryDirection>>,
+}
+
+impl RotaryEncoder {
+    /// The number of transitions per detent for a typical encoder.
+    pub const DEFAULT_TRANSITIONS_PER_DETENT: u8 = 4;
+
+    /// The number of steps queued while nothing is waiting for them; further steps
+    /// only move the position.
+    pub const STEP_QUEUE: usize = 16;
+
+    /// Create a new rotary encoder on the given pins, with a push switch if `switch`
+    /// is provided.
+    pub fn try_new(gpio: &Gpio, pin_a: u8, pin_b: u8, switch: Option<u8>) -> RPiResult<Self> {
+        Self::try_new_with_detent(
+            gpio,
+            pin_a,
+            pin_b,
+            switch,
+            Self::DEFAULT_TRANSITIONS_PER_DETENT,
+        )
+    }
+
+    /// Create a new rotary encoder on the given pins, reporting a step every
+    /// `transitions_per_detent` valid transitions.
+    pub fn try_new_with_detent(
+        gpio: &Gpio,
+        pin_a: u8,
+        pin_b: u8,
+        switch: Option<u8>,
+        transitions_per_detent: u8,
+    ) -> RPiResult<Self> {
+        let claims = registry::claim_pins(&[pin_a, pin_b], PinFunction::Input, "RotaryEncoder")?;
+        let mut pin_a = func::get_pin(gpio, pin_a, "RotaryEncoder")?.into_input_pullup();
+        let mut pin_b = func::get_pin(gpio, pin_b, "RotaryEncoder")?.into_input_pullup();
+        let switch = switch.map(|pin| Button::try_new(gpio, pin)).transpose()?;
+
+        let (sender, receiver) = mpsc::channel(Self::STEP_QUEUE);
+        let state = Arc::new(EncoderState {
+            decoder: Mutex::new(EdgeDecoder::new(
+                pin_a.is_high(),
+                pin_b.is_high(),
+                transitions_per_detent,
+            )),
+            position: AtomicI64::new(0),
+            steps: sender,
+        });
+
+        let state_a = Arc::clone(&state);
+        watch_edges(&mut pin_a, move |level| {
+            state_a.edge(|decoder| decoder.update_a(level == Level::High))
+        })?;
+        let state_b = Arc::clone(&state);
+        watch_edges(&mut pin_b, move |level| {
+            state_b.edge(|decoder| decoder.update_b(level == Level::High))
+        })?;
+
+        Ok(Self {
+            _pin_a: pin_a,
+            _pin_b: pin_b,
+            _claims: claims,
+            switch,
+            state,
+            steps: AsyncMutex::new(receiver),
+        })
+    }
+
+    /// Create a new rotary encoder on the given pins; if it fails, panic.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the pins cannot be initialized.
+    pub fn new(gpio: &Gpio, pin_a: u8, pin_b: u8, switch: Option<u8>) -> Self {
+        Self::try_new(gpio, pin_a, pin_b, switch).expect("Failed to initialize rotary encoder.")
+    }
+
+    /// Get the push switch of the encoder, if any.
+    pub fn switch(&self) -> Option<&Button> {
+        self.switch.as_ref()
+    }
+
+    /// Get the absolute position of the encoder, in detents since creation or the
+    /// last reset.
+    pub fn position(&self) -> i64 {
+        self.state.position.load(Ordering::Relaxed)
+    }
+
+    /// Set the absolute position of the encoder, and return the previous position.
+    pub fn set_position(&self, position: i64) -> i64 {
+        self.state.position.swap(position, Ordering::Relaxed)
+    }
+
+    /// Reset the absolute position of the encoder to `0`, and return the previous
+    /// position.
+    pub fn reset_position(&self) -> i64 {
+        self.set_position(0)
+    }
+
+    /// Blocks until the encoder has been turned by one detent, then returns the
+    /// direction of the step.
+    ///
+    /// Steps turned since the last call are returned first, oldest first.
+    pub async fn next_step(&self, timeout: Option<Duration>) -> RPiResult<RotaryDirection> {
+        tokio::select! {
+            // The sender lives as long as the encoder, so the queue is never closed.
+            direction = async { self.steps.lock().await.recv().await } => direction.ok_or(RPiError::Cancelled),
+            _ = termination::timeout_opt(timeout) => {
+                // You can never timeout if timeout is `None`. So this is safe.
+                Err(RPiError::Timeout("wait for rotary encoder step".into(), timeout.unwrap()))
+            },
+            _ = termination::ctrl_c() => Err(RPiError::Cancelled),
+        }
+    }
+
+    /// A [`Stream`] of the steps of this encoder.
+    ///
+    /// The stream ends after yielding the first error, such as a
+    /// [`RPiError::Cancelled`].
+    pub fn steps(&self) -> impl Stream<Item = RPiResult<RotaryDirection>> + '_ {
+        futures::stream::unfold(Some(self), |state| async move {
+            let encoder = state?;
+            let step = encoder.next_step(None).await;
+            let next_state = step.is_ok().then_some(encoder);
+
+            Some((step, next_state))
+        })
+    }
+
+    /// Infinitely loop the step callback - execute the callback every time the
+    /// encoder is turned by one detent.
+    pub async fn step_event_loop<T>(
+        &self,
+        func: impl Fn(RPiResult<RotaryDirection>) -> RPiResult<T>,
+    ) -> RPiResult<T> {
+        loop {
+            func(self.next_step(None).await)?;
+        }
+    }
+}
+
+/// Call `callback` with the new level of `pin` on both of its edges, from the
+/// interrupt thread of [`rppal`].
+fn watch_edges(pin: &mut InputPin, callback: impl FnMut(Level) + Send + 'static) -> RPiResult<()> {
+    let number = pin.pin();
+
+    pin.set_async_interrupt(Trigger::Both, callback)
+        .with_context(|| {
+            ErrorContext::new("watch pin")
+                .with_component("RotaryEncoder")
+                .with_pin(number)
+        })
+}
+
+#[async_trait]
+impl NavigationInput for RotaryEncoder {
+    /// Turning the encoder moves to the [`Navigation::Next`] or
+    /// [`Navigation::Previous`] item; pressing the switch, if any, selects it.
+    async fn next_navigation(&self, timeout: Option<Duration>) -> RPiResult<Navigation> {
+        tokio::select! {
+            step = self.next_step(timeout) => step.map(|direction| match direction {
+                RotaryDirection::Clockwise => Navigation::Next,
+                RotaryDirection::CounterClockwise => Navigation::Previous,
+            }),
+            pressed = async {
+                match &self.switch {
+                    Some(switch) => switch.pressed_and_released(timeout).await,
+                    None => std::future::pending().await,
+                }
+            } => pressed.map(|_| Navigation::Select),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use RotaryDirection::{Clockwise, CounterClockwise};
+
+    /// One full quadrature cycle with pin A leading, as `(a, b)` levels.
+    const CLOCKWISE: [(bool, bool); 4] =
+        [(true, false), (true, true), (false, true), (false, false)];
+
+    /// Feed the levels into the decoder, and collect the steps reported.
+    fn decode(decoder: &mut QuadratureDecoder, levels: &[(bool, bool)]) -> Vec<RotaryDirection> {
+        levels
+            .iter()
+            .filter_map(|&(a, b)| decoder.update(a, b))
+            .collect()
+    }
+
+    #[test]
+    fn one_step_per_detent() {
+        let mut decoder = QuadratureDecoder::new(false, false, 4);
+
+        assert_eq!(decode(&mut decoder, &CLOCKWISE[..3]), []);
+        assert_eq!(decode(&mut decoder, &CLOCKWISE[3..]), [Clockwise]);
+        assert_eq!(decode(&mut decoder, &CLOCKWISE), [Clockwise]);
+    }
+
+    #[test]
+    fn pin_b_leading_is_counter_clockwise() {
+        let mut decoder = QuadratureDecoder::new(false, false, 4);
+        let mut levels = CLOCKWISE;
+        levels[..3].reverse();
+
+        assert_eq!(decode(&mut decoder, &levels), [CounterClockwise]);
+    }
+
+    #[test]
+    fn fewer_transitions_per_detent() {
+        let mut decoder = QuadratureDecoder::new(false, false, 2);
+        assert_eq!(decode(&mut decoder, &CLOCKWISE), [Clockwise, Clockwise]);
+
+        let mut decoder = QuadratureDecoder::new(false, false, 1);
+        assert_eq!(decode(&mut decoder, &CLOCKWISE), [Clockwise; 4]);
+    }
+
+    #[test]
+    fn transitions_per_detent_are_clamped() {
+        let mut decoder = QuadratureDecoder::new(false, false, 0);
+        assert_eq!(decode(&mut decoder, &CLOCKWISE[..1]), [Clockwise]);
+
+        let mut decoder = QuadratureDecoder::new(false, false, 8);
+        assert_eq!(decode(&mut decoder, &CLOCKWISE), [Clockwise]);
+    }
+
+    #[test]
+    fn repeated_readings_are_ignored() {
+        let mut decoder = QuadratureDecoder::new(false, false, 4);
+        let levels = CLOCKWISE
+            .iter()
+            .flat_map(|&level| [level; 3])
+            .collect::<Vec<_>>();
+
+        assert_eq!(decode(&mut decoder, &levels), [Clockwise]);
+    }
+
+    #[test]
+    fn bounces_cancel_out() {
+        let mut decoder = QuadratureDecoder::new(false, false, 4);
+
+        // Pin A bounces twice before settling, then the cycle completes.
+        let levels = [
+            (true, false),
+            (false, false),
+            (true, false),
+            (false, false),
+            (true, false),
+            (true, true),
+            (false, true),
+            (false, false),
+        ];
+
+        assert_eq!(decode(&mut decoder, &levels), [Clockwise]);
+    }
+
+    #[test]
+    fn bounces_at_a_detent_do_not_step() {
+        let mut decoder = QuadratureDecoder::new(false, false, 4);
+        let levels = [(true, false), (false, false)].repeat(8);
+
+        assert_eq!(decode(&mut decoder, &levels), []);
+    }
+
+    #[test]
+    fn invalid_transitions_are_not_counted() {
+        let mut decoder = QuadratureDecoder::new(false, false, 4);
+
+        // Both pins change at once, skipping two transitions of the cycle.
+        let levels = [(true, true), (false, true), (false, false)];
+        assert_eq!(decode(&mut decoder, &levels), []);
+
+        // The decoder follows the new state, so the next full cycle still steps.
+        assert_eq!(decode(&mut decoder, &CLOCKWISE), [Clockwise]);
+    }
+
+    #[test]
+    fn edges_of_either_pin_are_decoded() {
+        let mut decoder = EdgeDecoder::new(false, false, 4);
+
+        let steps = [
+            decoder.update_a(true),
+            decoder.update_b(true),
+            decoder.update_a(false),
+            decoder.update_b(false),
+        ];
+        assert_eq!(steps, [None, None, None, Some(Clockwise)]);
+
+        let steps = [
+            decoder.update_b(true),
+            decoder.update_a(true),
+            decoder.update_b(false),
+            decoder.update_a(false),
+        ];
+        assert_eq!(steps, [None, None, None, Some(CounterClockwise)]);
+    }
+
+    #[test]
+    fn edges_move_the_position_without_waiting() {
+        let (steps, mut receiver) = mpsc::channel(1);
+        let state = EncoderState {
+            decoder: Mutex::new(EdgeDecoder::new(false, false, 1)),
+            position: AtomicI64::new(0),
+            steps,
+        };
+
+        // Pin A rises, pin B rises, pin A falls, pin B falls; twice.
+        for (pin_a, level) in [(true, true), (false, true), (true, false), (false, false)].repeat(2)
+        {
+            state.edge(|decoder| match pin_a {
+                true => decoder.update_a(level),
+                false => decoder.update_b(level),
+            });
+        }
+
+        // The queue is full after the first step, but the position is not.
+        assert_eq!(state.position.load(Ordering::Relaxed), 8);
+        assert_eq!(receiver.try_recv(), Ok(Clockwise));
+        assert!(receiver.try_recv().is_err());
+    }
+}
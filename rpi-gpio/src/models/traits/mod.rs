@@ -11,3 +11,6 @@ pub use rgb_transition::*;
 
 mod marker;
 pub use marker::*;
+
+mod navigation;
+pub use navigation::*;
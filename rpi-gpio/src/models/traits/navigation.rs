@@ -0,0 +1,24 @@
+//! Traits for input devices that can be used to navigate lists and menus.
+//!
+
+use async_trait::async_trait;
+use std::time::Duration;
+
+use rpi_errors::RPiResult;
+
+/// A navigation action issued by an input device.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Navigation {
+    Previous,
+    Next,
+    Select,
+    Back,
+}
+
+/// An input device, or a composite of input devices, that can drive list and menu
+/// navigation.
+#[async_trait]
+pub trait NavigationInput: Send + Sync {
+    /// Wait for the next navigation action.
+    async fn next_navigation(&self, timeout: Option<Duration>) -> RPiResult<Navigation>;
+}
@@ -28,7 +28,10 @@ where
                 .round() as u8
         };
 
-        // TODO Possible SIMD optimisation here?
+        // This stays scalar: a single colour, such as that of a LED, is interpolated
+        // per call, leaving nothing to vectorise. Whole images are crossfaded by
+        // the kernels of `rpi-display-mipidsi` instead, which blend linearly and
+        // cannot be used here, as that crate depends on this one.
         RGB::from_rgb(
             factorise(self.r(), other.r()),
             factorise(self.g(), other.g()),
@@ -0,0 +1,242 @@
+//! Registry of the GPIO pins claimed by the components of this process.
+//!
+//! Every component claims its BCM pins before using them, and releases them when it
+//! is dropped; claiming a pin that is already claimed returns a
+//! [`RPiError::PinConflict`] naming its current owner.
+//!
+//! Boards additionally claim the pins reserved by their peripherals, such as the SPI
+//! bus, so that no other component can be created on them.
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+};
+
+use rpi_errors::{RPiError, RPiResult};
+
+/// The function that a pin is claimed for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PinFunction {
+    Input,
+    Output,
+    Pwm,
+    Spi,
+    I2c,
+    Uart,
+    I2s,
+}
+
+impl fmt::Display for PinFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Input => "input",
+            Self::Output => "output",
+            Self::Pwm => "PWM",
+            Self::Spi => "SPI",
+            Self::I2c => "I2C",
+            Self::Uart => "UART",
+            Self::I2s => "I2S",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// The owner and function of a claimed pin.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PinClaim {
+    pub owner: String,
+    pub function: PinFunction,
+}
+
+/// The pins claimed by this process, indexed by BCM pin number.
+static PIN_REGISTRY: Mutex<BTreeMap<u8, PinClaim>> = Mutex::new(BTreeMap::new());
+
+/// Lock the registry.
+///
+/// The registry is never left in an inconsistent state, so a poisoned lock is
+/// recovered instead of being reported.
+fn registry() -> MutexGuard<'static, BTreeMap<u8, PinClaim>> {
+    PIN_REGISTRY.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// A claim on a GPIO pin; the pin is released when this is dropped.
+#[derive(Debug)]
+pub struct PinGuard {
+    pin: u8,
+    function: PinFunction,
+}
+
+impl PinGuard {
+    /// The BCM pin number of this claim.
+    pub fn pin(&self) -> u8 {
+        self.pin
+    }
+
+    /// The function that the pin is claimed for.
+    pub fn function(&self) -> PinFunction {
+        self.function
+    }
+}
+
+impl Drop for PinGuard {
+    fn drop(&mut self) {
+        registry().remove(&self.pin);
+    }
+}
+
+/// Claim a pin for the given function on behalf of `owner`.
+pub fn claim_pin(pin: u8, function: PinFunction, owner: &str) -> RPiResult<PinGuard> {
+    let mut registry = registry();
+
+    if let Some(claim) = registry.get(&pin) {
+        return Err(RPiError::PinConflict(
+            pin,
+            claim.owner.clone().into(),
+            claim.function.to_string().into(),
+        ));
+    }
+
+    registry.insert(
+        pin,
+        PinClaim {
+            owner: owner.to_owned(),
+            function,
+        },
+    );
+
+    Ok(PinGuard { pin, function })
+}
+
+/// Claim all of the given pins for the same function on behalf of `owner`.
+///
+/// If any of the pins cannot be claimed, the pins claimed so far are released.
+pub fn claim_pins(pins: &[u8], function: PinFunction, owner: &str) -> RPiResult<Vec<PinGuard>> {
+    pins.iter()
+        .map(|pin| claim_pin(*pin, function, owner))
+        .collect()
+}
+
+/// Get the current claim on a pin, if any.
+pub fn pin_claim(pin: u8) -> Option<PinClaim> {
+    registry().get(&pin).cloned()
+}
+
+/// Get all the current claims, ordered by BCM pin number.
+pub fn pin_claims() -> Vec<(u8, PinClaim)> {
+    registry()
+        .iter()
+        .map(|(pin, claim)| (*pin, claim.clone()))
+        .collect()
+}
+
+/// A guard ensuring that only one instance of a board exists at a time; the board
+/// can be initialised again once this is dropped.
+#[derive(Debug)]
+pub struct SingletonGuard {
+    flag: &'static AtomicBool,
+}
+
+impl SingletonGuard {
+    /// Acquire the singleton `flag` on behalf of `name`.
+    pub fn try_acquire(flag: &'static AtomicBool, name: &'static str) -> RPiResult<Self> {
+        if flag.swap(true, Ordering::AcqRel) {
+            Err(RPiError::AlreadyInitialised(name.into()))
+        } else {
+            Ok(Self { flag })
+        }
+    }
+}
+
+impl Drop for SingletonGuard {
+    fn drop(&mut self) {
+        self.flag.store(false, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The registry is shared by every test in this process, so each test claims its
+    // own pins, outside of the range of real BCM pins.
+
+    #[test]
+    fn pins_cannot_be_claimed_twice() {
+        let guard = claim_pin(200, PinFunction::Input, "Button A").unwrap();
+        assert_eq!(guard.pin(), 200);
+        assert_eq!(guard.function(), PinFunction::Input);
+
+        let err = claim_pin(200, PinFunction::Output, "Backlight").unwrap_err();
+        assert!(
+            matches!(&err, RPiError::PinConflict(200, owner, function) if owner == "Button A" && function == "input")
+        );
+
+        assert_eq!(
+            pin_claim(200),
+            Some(PinClaim {
+                owner: "Button A".to_owned(),
+                function: PinFunction::Input,
+            })
+        );
+    }
+
+    #[test]
+    fn pins_are_released_on_drop() {
+        let guard = claim_pin(201, PinFunction::Pwm, "Backlight").unwrap();
+        drop(guard);
+
+        assert_eq!(pin_claim(201), None);
+        let guard = claim_pin(201, PinFunction::Output, "LED").unwrap();
+        assert_eq!(
+            pin_claim(201).map(|claim| claim.owner),
+            Some("LED".to_owned())
+        );
+        drop(guard);
+    }
+
+    #[test]
+    fn failed_claims_release_the_pins_claimed_so_far() {
+        let _taken = claim_pin(204, PinFunction::Spi, "Display").unwrap();
+
+        let err = claim_pins(&[202, 203, 204], PinFunction::I2c, "Sensor").unwrap_err();
+        assert!(matches!(err, RPiError::PinConflict(204, ..)));
+        assert_eq!(pin_claim(202), None);
+        assert_eq!(pin_claim(203), None);
+
+        let guards = claim_pins(&[202, 203], PinFunction::I2c, "Sensor").unwrap();
+        assert_eq!(guards.len(), 2);
+    }
+
+    #[test]
+    fn claims_are_listed_in_pin_order() {
+        let _guards = [
+            claim_pin(207, PinFunction::Uart, "Serial").unwrap(),
+            claim_pin(205, PinFunction::I2s, "Audio").unwrap(),
+        ];
+
+        let pins: Vec<_> = pin_claims()
+            .into_iter()
+            .map(|(pin, _)| pin)
+            .filter(|pin| [205, 207].contains(pin))
+            .collect();
+        assert_eq!(pins, [205, 207]);
+    }
+
+    #[test]
+    fn singletons_can_be_acquired_again_once_dropped() {
+        static FLAG: AtomicBool = AtomicBool::new(false);
+
+        let guard = SingletonGuard::try_acquire(&FLAG, "Board").unwrap();
+        let err = SingletonGuard::try_acquire(&FLAG, "Board").unwrap_err();
+        assert!(matches!(err, RPiError::AlreadyInitialised(name) if name == "Board"));
+
+        drop(guard);
+        assert!(!FLAG.load(Ordering::Acquire));
+        assert!(SingletonGuard::try_acquire(&FLAG, "Board").is_ok());
+    }
+}
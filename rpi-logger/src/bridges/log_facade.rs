@@ -0,0 +1,243 @@
+//! Bridge to and from the [`log`] facade.
+//!
+
+use std::borrow::Cow;
+
+use log::{
+    kv::{self, VisitSource},
+    Level, LevelFilter, Log, Metadata,
+};
+
+use super::{forwarding, is_forwarding};
+use crate::{enabled, log_record, LogLevel, LogSink, Record};
+
+impl From<Level> for LogLevel {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Trace => Self::Trace,
+            Level::Debug => Self::Debug,
+            Level::Info => Self::Info,
+            Level::Warn => Self::Warning,
+            Level::Error => Self::Error,
+        }
+    }
+}
+
+impl From<LogLevel> for Level {
+    /// Convert a level, with [`LogLevel::Critical`] becoming [`Level::Error`].
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::Trace => Self::Trace,
+            LogLevel::Debug => Self::Debug,
+            LogLevel::Info => Self::Info,
+            LogLevel::Warning => Self::Warn,
+            LogLevel::Error | LogLevel::Critical => Self::Error,
+        }
+    }
+}
+
+/// Collect the key-value pairs of a [`log::Record`].
+struct FieldVisitor<'r>(&'r mut Vec<(String, String)>);
+
+impl<'kvs> VisitSource<'kvs> for FieldVisitor<'_> {
+    fn visit_pair(&mut self, key: kv::Key<'kvs>, value: kv::Value<'kvs>) -> Result<(), kv::Error> {
+        self.0.push((key.to_string(), value.to_string()));
+        Ok(())
+    }
+}
+
+/// A [`log::Log`] implementation, sending the lines logged through the [`log`]
+/// macros to the registered [`LogSink`]s.
+///
+/// Install it with [`init_log`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LogBridge;
+
+impl Log for LogBridge {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        !is_forwarding() && enabled(metadata.level().into(), Some(metadata.target()))
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let mut received = Record::new(record.level().into(), record.args().to_string())
+            .with_target(record.target().to_owned());
+        received.file = record
+            .file_static()
+            .map(Cow::Borrowed)
+            .or_else(|| record.file().map(|file| Cow::Owned(file.to_owned())));
+        received.line = record.line();
+        // Invalid pairs are skipped rather than losing the line.
+        let _ = record
+            .key_values()
+            .visit(&mut FieldVisitor(&mut received.fields));
+
+        log_record(received);
+    }
+
+    fn flush(&self) {}
+}
+
+/// Install [`LogBridge`] as the [`log`] backend.
+///
+/// Every level is let through to the current [`Filter`](crate::Filter). Fails if
+/// another backend is already installed.
+pub fn init_log() -> Result<(), log::SetLoggerError> {
+    static BRIDGE: LogBridge = LogBridge;
+
+    log::set_logger(&BRIDGE)?;
+    log::set_max_level(LevelFilter::Trace);
+
+    Ok(())
+}
+
+/// Forward every log line to the [`log`] backend installed by the application,
+/// with its fields as key-value pairs.
+///
+/// Lines without a target are logged with `rpi_logger` as the target.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LogCrateSink;
+
+impl LogSink for LogCrateSink {
+    fn log(&self, record: &Record) {
+        let level = Level::from(record.level);
+        let target = record.target.as_deref().unwrap_or(module_path!());
+        let fields = record
+            .fields
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect::<Vec<_>>();
+
+        forwarding(|| {
+            let logger = log::logger();
+
+            if logger.enabled(&Metadata::builder().level(level).target(target).build()) {
+                logger.log(
+                    &log::Record::builder()
+                        .level(level)
+                        .target(target)
+                        .args(format_args!("{}", record.message))
+                        .module_path(record.target.as_deref())
+                        .file(record.file.as_deref())
+                        .line(record.line)
+                        .key_values(&fields.as_slice())
+                        .build(),
+                );
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Mutex, Once};
+
+    use super::*;
+    use crate::{bridges::forwarding, capture};
+
+    /// The lines received by the [`log`] backend of the tests, with whether they
+    /// were received while forwarding.
+    static RECEIVED: Mutex<Vec<(Level, String, bool)>> = Mutex::new(Vec::new());
+
+    /// A [`log`] backend recording the lines it receives.
+    struct Recorder;
+
+    impl Log for Recorder {
+        fn enabled(&self, _: &Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, record: &log::Record) {
+            RECEIVED.lock().unwrap().push((
+                record.level(),
+                record.args().to_string(),
+                is_forwarding(),
+            ));
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn levels_are_mapped_both_ways() {
+        for level in [
+            Level::Trace,
+            Level::Debug,
+            Level::Info,
+            Level::Warn,
+            Level::Error,
+        ] {
+            assert_eq!(Level::from(LogLevel::from(level)), level);
+        }
+
+        assert_eq!(LogLevel::from(Level::Warn), LogLevel::Warning);
+        assert_eq!(Level::from(LogLevel::Critical), Level::Error);
+    }
+
+    #[test]
+    fn lines_are_received_with_their_fields() {
+        let fields = [("frame", 3), ("skipped", 1)];
+        let records = capture(module_path!(), || {
+            LogBridge.log(
+                &log::Record::builder()
+                    .level(Level::Warn)
+                    .target(module_path!())
+                    .args(format_args!("Frame {} late.", 3))
+                    .file_static(Some(file!()))
+                    .line(Some(7))
+                    .key_values(&fields.as_slice())
+                    .build(),
+            );
+        });
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].level, LogLevel::Warning);
+        assert_eq!(records[0].message, "Frame 3 late.");
+        assert_eq!(records[0].target.as_deref(), Some(module_path!()));
+        assert_eq!(records[0].file.as_deref(), Some(file!()));
+        assert_eq!(records[0].line, Some(7));
+        assert_eq!(
+            records[0].fields,
+            [
+                ("frame".to_owned(), "3".to_owned()),
+                ("skipped".to_owned(), "1".to_owned())
+            ]
+        );
+    }
+
+    #[test]
+    fn forwarded_lines_are_not_received_back() {
+        let records = capture(module_path!(), || {
+            forwarding(|| {
+                LogBridge.log(
+                    &log::Record::builder()
+                        .level(Level::Info)
+                        .target(module_path!())
+                        .args(format_args!("Forwarded."))
+                        .build(),
+                );
+            });
+        });
+
+        assert!(records.is_empty());
+        assert!(!is_forwarding());
+    }
+
+    #[test]
+    fn lines_are_forwarded_to_the_backend() {
+        static INSTALL: Once = Once::new();
+        INSTALL.call_once(|| {
+            log::set_logger(&Recorder).unwrap();
+            log::set_max_level(LevelFilter::Trace);
+        });
+
+        LogCrateSink.log(&Record::new(LogLevel::Critical, "Display lost.").with_field("pin", 25));
+
+        let received = RECEIVED.lock().unwrap();
+        assert!(received.contains(&(Level::Error, "Display lost.".to_owned(), true)));
+        assert!(!is_forwarding());
+    }
+}
@@ -0,0 +1,40 @@
+//! Bridges to and from the [`log`](https://docs.rs/log) and
+//! [`tracing`](https://docs.rs/tracing) ecosystems.
+//!
+//! Each bridge goes both ways: lines logged through the other crate can be
+//! received as [`Record`](crate::Record)s, and [`Record`](crate::Record)s can be
+//! forwarded to the backend of the other crate by a [`LogSink`](crate::LogSink).
+//! Lines forwarded by a sink are not received back, so both directions can be used
+//! at once.
+
+use std::cell::Cell;
+
+#[cfg(feature = "log")]
+mod log_facade;
+#[cfg(feature = "log")]
+pub use log_facade::*;
+
+#[cfg(feature = "tracing")]
+mod tracing_layer;
+#[cfg(feature = "tracing")]
+pub use tracing_layer::*;
+
+thread_local! {
+    /// Whether a sink on this thread is forwarding a line to another crate.
+    static FORWARDING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Run `forward` with lines received from other crates ignored on this thread.
+fn forwarding<T>(forward: impl FnOnce() -> T) -> T {
+    let previous = FORWARDING.with(|flag| flag.replace(true));
+    let result = forward();
+    FORWARDING.with(|flag| flag.set(previous));
+
+    result
+}
+
+/// Check if a line received from another crate was forwarded by a sink, and
+/// should be ignored.
+fn is_forwarding() -> bool {
+    FORWARDING.with(Cell::get)
+}
@@ -0,0 +1,271 @@
+//! Bridge to and from [`tracing`].
+//!
+
+use std::fmt;
+
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Level, Subscriber,
+};
+use tracing_subscriber::{layer::Context, registry::LookupSpan, Layer};
+
+use super::{forwarding, is_forwarding};
+use crate::{enabled, log_record, LogLevel, LogSink, Record, SpanContext};
+
+impl From<Level> for LogLevel {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::TRACE => Self::Trace,
+            Level::DEBUG => Self::Debug,
+            Level::INFO => Self::Info,
+            Level::WARN => Self::Warning,
+            Level::ERROR => Self::Error,
+        }
+    }
+}
+
+/// Collect the fields of an event or a span, keeping the `message` field apart.
+struct FieldVisitor<'r> {
+    message: Option<String>,
+    fields: &'r mut Vec<(String, String)>,
+}
+
+impl<'r> FieldVisitor<'r> {
+    fn new(fields: &'r mut Vec<(String, String)>) -> Self {
+        Self {
+            message: None,
+            fields,
+        }
+    }
+}
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = Some(value.to_owned());
+        } else {
+            self.fields
+                .push((field.name().to_owned(), value.to_owned()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record_str(field, &format!("{value:?}"));
+    }
+}
+
+/// The fields recorded on a span so far, kept in its extensions.
+struct SpanFields(Vec<(String, String)>);
+
+/// A [`tracing_subscriber`] layer, sending [`tracing`] events to the registered
+/// [`LogSink`]s with their fields and the spans they are in.
+///
+/// ```ignore
+/// use tracing_subscriber::prelude::*;
+///
+/// tracing_subscriber::registry().with(TracingLayer).init();
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TracingLayer;
+
+impl<S> Layer<S> for TracingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut fields = Vec::new();
+        attrs.record(&mut FieldVisitor::new(&mut fields));
+        span.extensions_mut().insert(SpanFields(fields));
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+
+        let mut extensions = span.extensions_mut();
+        if let Some(SpanFields(fields)) = extensions.get_mut::<SpanFields>() {
+            values.record(&mut FieldVisitor::new(fields));
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let level = LogLevel::from(*metadata.level());
+
+        if is_forwarding() || !enabled(level, Some(metadata.target())) {
+            return;
+        }
+
+        let mut record = Record::new(level, String::new()).with_target(metadata.target());
+        record.file = metadata.file().map(Into::into);
+        record.line = metadata.line();
+        let mut visitor = FieldVisitor::new(&mut record.fields);
+        event.record(&mut visitor);
+        record.message = visitor.message.unwrap_or_default();
+
+        for span in ctx
+            .event_scope(event)
+            .into_iter()
+            .flat_map(|scope| scope.from_root())
+        {
+            let fields = span
+                .extensions()
+                .get::<SpanFields>()
+                .map(|SpanFields(fields)| fields.clone())
+                .unwrap_or_default();
+
+            record.spans.push(SpanContext {
+                name: span.name().to_owned(),
+                fields,
+            });
+        }
+
+        log_record(record);
+    }
+}
+
+/// Forward every log line to the [`tracing`] subscriber installed by the
+/// application, as an event with the `rpi_logger` target.
+///
+/// The module the line came from is recorded as the `module` field, and its own
+/// fields joined into the `fields` field, as [`tracing`] needs field names known
+/// at compile time.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TracingSink;
+
+impl LogSink for TracingSink {
+    fn log(&self, record: &Record) {
+        let module = record.target.as_deref().unwrap_or_default();
+        let fields = record
+            .fields
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let message = &record.message;
+
+        macro_rules! forward {
+            ($(($level:ident, $macro:ident)),*$(,)?) => {
+                match record.level {
+                    $(
+                        LogLevel::$level => tracing::$macro!(
+                            target: "rpi_logger", module, fields, "{message}"
+                        ),
+                    )*
+                    LogLevel::Error | LogLevel::Critical => tracing::error!(
+                        target: "rpi_logger", module, fields, "{message}"
+                    ),
+                }
+            };
+        }
+
+        forwarding(|| {
+            forward!(
+                (Trace, trace),
+                (Debug, debug),
+                (Info, info),
+                (Warning, warn),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+
+    use tracing_subscriber::prelude::*;
+
+    use super::*;
+    use crate::capture;
+
+    /// A layer counting the events it sees.
+    struct Counter(Arc<AtomicUsize>);
+
+    impl<S: Subscriber> Layer<S> for Counter {
+        fn on_event(&self, _: &Event<'_>, _: Context<'_, S>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn levels_are_mapped() {
+        assert_eq!(LogLevel::from(Level::TRACE), LogLevel::Trace);
+        assert_eq!(LogLevel::from(Level::DEBUG), LogLevel::Debug);
+        assert_eq!(LogLevel::from(Level::INFO), LogLevel::Info);
+        assert_eq!(LogLevel::from(Level::WARN), LogLevel::Warning);
+        assert_eq!(LogLevel::from(Level::ERROR), LogLevel::Error);
+    }
+
+    #[test]
+    fn events_are_received_with_their_fields_and_spans() {
+        let subscriber = tracing_subscriber::registry().with(TracingLayer);
+
+        let records = capture(module_path!(), || {
+            tracing::subscriber::with_default(subscriber, || {
+                let outer =
+                    tracing::info_span!("render", frame = 3, elapsed = tracing::field::Empty);
+                let _outer = outer.enter();
+                outer.record("elapsed", "12ms");
+
+                let inner = tracing::debug_span!("draw");
+                let _inner = inner.enter();
+
+                tracing::warn!(skipped = 2, reason = ?"late", "Frame {} dropped.", 4);
+            });
+        });
+
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.level, LogLevel::Warning);
+        assert_eq!(record.message, "Frame 4 dropped.");
+        assert_eq!(record.target.as_deref(), Some(module_path!()));
+        assert_eq!(record.file.as_deref(), Some(file!()));
+        assert_eq!(
+            record.fields,
+            [
+                ("skipped".to_owned(), "2".to_owned()),
+                ("reason".to_owned(), "\"late\"".to_owned())
+            ]
+        );
+        assert_eq!(
+            record.spans,
+            [
+                SpanContext {
+                    name: "render".to_owned(),
+                    fields: vec![
+                        ("frame".to_owned(), "3".to_owned()),
+                        ("elapsed".to_owned(), "12ms".to_owned())
+                    ],
+                },
+                SpanContext::new("draw"),
+            ]
+        );
+    }
+
+    #[test]
+    fn forwarded_lines_are_not_received_back() {
+        let events = Arc::new(AtomicUsize::new(0));
+        let subscriber = tracing_subscriber::registry()
+            .with(TracingLayer)
+            .with(Counter(events.clone()));
+
+        let records = capture("rpi_logger", || {
+            tracing::subscriber::with_default(subscriber, || {
+                TracingSink.log(&Record::new(LogLevel::Info, "Forwarded.").with_field("pin", 25));
+            });
+        });
+
+        assert_eq!(events.load(Ordering::Relaxed), 1);
+        assert!(records.is_empty());
+        assert!(!crate::bridges::is_forwarding());
+    }
+}
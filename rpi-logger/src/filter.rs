@@ -0,0 +1,202 @@
+//! Filtering of log lines by level and module.
+//!
+
+use std::{env, fmt, str::FromStr};
+
+use super::LogLevel;
+
+/// The environment variable read by [`Filter::from_env`].
+pub const ENV_VAR: &str = "RPI_LOG";
+
+/// An error parsing a [`LogLevel`] or a [`Filter`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseFilterError(String);
+
+impl fmt::Display for ParseFilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid log filter: {}", self.0)
+    }
+}
+
+impl std::error::Error for ParseFilterError {}
+
+impl FromStr for LogLevel {
+    type Err = ParseFilterError;
+
+    /// Parse a level by its name, ignoring case; `warn` is accepted for `warning`.
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.trim().to_lowercase().as_str() {
+            "trace" => Ok(Self::Trace),
+            "debug" => Ok(Self::Debug),
+            "info" => Ok(Self::Info),
+            "warn" | "warning" => Ok(Self::Warning),
+            "error" => Ok(Self::Error),
+            "critical" => Ok(Self::Critical),
+            _ => Err(ParseFilterError(format!("unknown level {name:?}"))),
+        }
+    }
+}
+
+/// The minimum level for log lines to be sent to the sinks, overridden for
+/// individual modules.
+///
+/// A module's level also applies to its submodules, unless they have their own.
+/// Lines without a target only use the default level.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Filter {
+    level: LogLevel,
+
+    /// Module paths and their levels, the longest paths first.
+    modules: Vec<(String, LogLevel)>,
+}
+
+impl Default for Filter {
+    /// Let every line through.
+    fn default() -> Self {
+        Self::new(LogLevel::Trace)
+    }
+}
+
+impl Filter {
+    /// Create a new filter with the given default level.
+    pub fn new(level: LogLevel) -> Self {
+        Self {
+            level,
+            modules: Vec::new(),
+        }
+    }
+
+    /// Read the filter from the `RPI_LOG` environment variable, in the format
+    /// accepted by [`from_str`](Self::from_str).
+    ///
+    /// If the variable is unset or invalid, every line is let through; an invalid
+    /// value is reported on `stderr`.
+    pub fn from_env() -> Self {
+        match env::var(ENV_VAR) {
+            Ok(spec) => spec.parse().unwrap_or_else(|err| {
+                eprintln!("Ignoring {ENV_VAR}: {err}");
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Set the default level.
+    pub fn with_level(mut self, level: LogLevel) -> Self {
+        self.set_level(level);
+        self
+    }
+
+    /// Set the level of a module and its submodules.
+    pub fn with_module(mut self, module: impl Into<String>, level: LogLevel) -> Self {
+        self.set_module(module, level);
+        self
+    }
+
+    /// Set the default level.
+    pub fn set_level(&mut self, level: LogLevel) {
+        self.level = level;
+    }
+
+    /// Set the level of a module and its submodules.
+    pub fn set_module(&mut self, module: impl Into<String>, level: LogLevel) {
+        let module = module.into();
+
+        match self.modules.iter_mut().find(|(name, _)| *name == module) {
+            Some((_, existing)) => *existing = level,
+            None => {
+                self.modules.push((module, level));
+                self.modules
+                    .sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+            }
+        }
+    }
+
+    /// Get the default level.
+    pub fn level(&self) -> LogLevel {
+        self.level
+    }
+
+    /// Get the minimum level for lines from the given target.
+    pub fn level_for(&self, target: Option<&str>) -> LogLevel {
+        target
+            .and_then(|target| {
+                self.modules.iter().find(|(module, _)| {
+                    target
+                        .strip_prefix(module.as_str())
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+                })
+            })
+            .map_or(self.level, |(_, level)| *level)
+    }
+
+    /// Check if a line at `level` from the given target is let through.
+    pub fn enabled(&self, level: LogLevel, target: Option<&str>) -> bool {
+        level >= self.level_for(target)
+    }
+}
+
+impl FromStr for Filter {
+    type Err = ParseFilterError;
+
+    /// Parse a comma separated list of a default level and `module=level` pairs,
+    /// such as `info,rpi_display_mipidsi::func::transitions=warning`.
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        spec.split(',')
+            .map(str::trim)
+            .filter(|directive| !directive.is_empty())
+            .try_fold(Self::default(), |filter, directive| {
+                match directive.split_once('=') {
+                    Some((module, level)) if !module.trim().is_empty() => {
+                        Ok(filter.with_module(module.trim(), level.parse()?))
+                    }
+                    Some(_) => Err(ParseFilterError(format!("missing module in {directive:?}"))),
+                    None => Ok(filter.with_level(directive.parse()?)),
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_spec() {
+        let filter: Filter =
+            "info, rpi_display_mipidsi::func=warn,rpi_display_mipidsi::func::transitions=error"
+                .parse()
+                .expect("Failed to parse filter");
+
+        assert_eq!(filter.level(), LogLevel::Info);
+        assert_eq!(filter.level_for(None), LogLevel::Info);
+        assert_eq!(filter.level_for(Some("rpi_gpio")), LogLevel::Info);
+        assert_eq!(
+            filter.level_for(Some("rpi_display_mipidsi::func::fs")),
+            LogLevel::Warning
+        );
+        assert_eq!(
+            filter.level_for(Some("rpi_display_mipidsi::func::transitions::wipe")),
+            LogLevel::Error
+        );
+        assert_eq!(
+            filter.level_for(Some("rpi_display_mipidsi::functions")),
+            LogLevel::Info
+        );
+
+        assert!(filter.enabled(LogLevel::Info, None));
+        assert!(!filter.enabled(LogLevel::Debug, None));
+        assert!(!filter.enabled(
+            LogLevel::Warning,
+            Some("rpi_display_mipidsi::func::transitions")
+        ));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!("verbose".parse::<Filter>().is_err());
+        assert!("=info".parse::<Filter>().is_err());
+        assert!("rpi_gpio=loud".parse::<Filter>().is_err());
+        assert_eq!("".parse::<Filter>(), Ok(Filter::default()));
+    }
+}
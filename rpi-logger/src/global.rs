@@ -0,0 +1,128 @@
+//! The global logger configuration.
+//!
+
+use std::sync::{Arc, RwLock};
+
+use lazy_static::lazy_static;
+
+use super::{sinks, Filter, LogLevel, LogSink, Record, StderrSink};
+
+lazy_static! {
+    /// The filter applied to every log line, read from `RPI_LOG` on first use.
+    static ref FILTER: RwLock<Filter> = RwLock::new(Filter::from_env());
+}
+
+/// Get a copy of the current [`Filter`].
+pub fn filter() -> Filter {
+    FILTER
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+/// Replace the current [`Filter`].
+pub fn set_filter(filter: Filter) {
+    *FILTER
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = filter;
+}
+
+/// Set the default minimum level of the current [`Filter`].
+pub fn set_level(level: LogLevel) {
+    FILTER
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .set_level(level);
+}
+
+/// Set the minimum level of a module and its submodules in the current
+/// [`Filter`].
+pub fn set_module_level(module: impl Into<String>, level: LogLevel) {
+    FILTER
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .set_module(module, level);
+}
+
+/// Check if a line at `level` from the given target would be logged.
+pub fn enabled(level: LogLevel, target: Option<&str>) -> bool {
+    FILTER
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .enabled(level, target)
+}
+
+/// Send a record to every registered sink, if it passes the current [`Filter`].
+pub fn log_record(record: Record) {
+    if enabled(record.level, record.target.as_deref()) {
+        sinks::dispatch(&record);
+    }
+}
+
+/// A complete logger configuration, replacing the current one when
+/// [installed](Self::install).
+///
+/// ```ignore
+/// LoggerConfig::from_env()
+///     .with_module_level("rpi_display_mipidsi::func::transitions", LogLevel::Info)
+///     .with_sink(Arc::new(FileSink::new("rpi.log")?))
+///     .install();
+/// ```
+#[derive(Clone)]
+pub struct LoggerConfig {
+    filter: Filter,
+    sinks: Vec<Arc<dyn LogSink>>,
+}
+
+impl Default for LoggerConfig {
+    /// Let every line through to `stderr`.
+    fn default() -> Self {
+        Self::new(Filter::default())
+    }
+}
+
+impl LoggerConfig {
+    /// Create a new configuration with the given filter, logging to `stderr`.
+    pub fn new(filter: Filter) -> Self {
+        Self {
+            filter,
+            sinks: vec![Arc::new(StderrSink::default())],
+        }
+    }
+
+    /// Create a new configuration with the filter from the `RPI_LOG` environment
+    /// variable, logging to `stderr`.
+    pub fn from_env() -> Self {
+        Self::new(Filter::from_env())
+    }
+
+    /// Set the default minimum level.
+    pub fn with_level(mut self, level: LogLevel) -> Self {
+        self.filter.set_level(level);
+        self
+    }
+
+    /// Set the minimum level of a module and its submodules.
+    pub fn with_module_level(mut self, module: impl Into<String>, level: LogLevel) -> Self {
+        self.filter.set_module(module, level);
+        self
+    }
+
+    /// Add a sink to receive the log lines.
+    pub fn with_sink(mut self, sink: Arc<dyn LogSink>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Remove all the sinks added so far, including `stderr`.
+    pub fn without_sinks(mut self) -> Self {
+        self.sinks.clear();
+        self
+    }
+
+    /// Replace the current filter and sinks with this configuration.
+    pub fn install(self) {
+        set_filter(self.filter);
+        sinks::set_sinks(self.sinks);
+    }
+}
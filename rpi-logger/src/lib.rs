@@ -1,11 +1,33 @@
 //! Logging module, with public functions to print different levels of log messages to `stderr`. Supports JSONL logging.
+//!
+//! Lines are filtered by level and module, and sent to any number of sinks. The
+//! filter is read from the `RPI_LOG` environment variable on first use, such as
+//! `RPI_LOG=info,rpi_display_mipidsi::func::transitions=warning`; only the macros
+//! such as [`info!`] record the module a line came from.
 
-#[cfg(feature = "jsonl_logging")]
-use serde_json;
+#[cfg(any(feature = "log", feature = "tracing"))]
+mod bridges;
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub use bridges::*;
 
 mod config;
 
+mod filter;
+pub use filter::{Filter, ParseFilterError, ENV_VAR};
+
+mod global;
+pub use global::*;
+
+mod macros;
+
+mod record;
+pub use record::{Record, SpanContext};
+
+mod sinks;
+pub use sinks::*;
+
 /// An enum for different log levels that appears differently.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum LogLevel {
     Trace,
     Debug,
@@ -17,7 +39,6 @@ pub enum LogLevel {
 
 impl LogLevel {
     /// Return the ANSI 8-bit colour code for this level.
-    #[cfg(not(feature = "jsonl_logging"))]
     pub fn ansi_code(&self) -> u8 {
         match self {
             Self::Trace => 8,
@@ -66,37 +87,23 @@ impl LogLevel {
 
     /// Format a string for the requested level.
     pub fn format(&self, message: &str) -> String {
-        let now = time::OffsetDateTime::now_utc()
-            .format(&config::DATETIME_FORMAT)
-            .unwrap_or("(Time unavailable)        ".to_owned());
-
-        #[cfg(feature = "jsonl_logging")]
-        {
-            serde_json::to_string(&serde_json::json!(
-                {
-                    "timestamp": now,
-                    "level": self.name(),
-                    "message": message,
-                }
-            ))
-            .unwrap_or("Invalid JSON".to_owned())
-        }
-
-        #[cfg(not(feature = "jsonl_logging"))]
-        {
-            let level = self.wraps(true, &self.name().to_uppercase());
-            let message = self.wraps(false, message);
-            let suffix = self.suffix();
-
-            let level_len = 9 + 22 + self.ansi_code().to_string().len();
-
-            format!("{now} \u{2502} {level:<level_len$} \u{2502} {message}{suffix}")
-        }
+        Record::new(*self, message).format(true)
     }
 
-    /// Log a string to `stderr` for the requested level.
+    /// Log a string at the requested level to every registered [`LogSink`], if the
+    /// level passes the current [`Filter`].
+    ///
+    /// The file and line of the caller are recorded, but not its module; use the
+    /// macros such as [`info!`] for that.
+    #[track_caller]
     pub fn log(&self, message: &str) {
-        eprintln!("{}", self.format(message))
+        if enabled(*self, None) {
+            let caller = std::panic::Location::caller();
+
+            sinks::dispatch(
+                &Record::new(*self, message).with_location(caller.file(), caller.line()),
+            );
+        }
     }
 }
 
@@ -106,9 +113,10 @@ macro_rules! expand_variants {
         $func:ident
     )),+$(,)?) => {
         $(
-            #[doc = "Write a log line to `stderr` at the "]
+            #[doc = "Write a log line to the registered sinks at the "]
             #[doc = stringify!($level)]
             #[doc = " level."]
+            #[track_caller]
             pub fn $func(message: &str) {
                 LogLevel::$level.log(message)
             }
@@ -0,0 +1,141 @@
+//! Macros logging formatted lines with structured fields, recording where they
+//! were logged from.
+//!
+
+/// Log a formatted line at the given level, with the calling module as the
+/// target so that it can be filtered by module, and the file and line it came
+/// from.
+///
+/// Key-value fields can follow the format arguments after a `;`; their values
+/// are formatted with [`Display`](std::fmt::Display). The message and fields are
+/// only formatted if the line passes the current filter.
+///
+/// ```ignore
+/// rpi_logger::log!(LogLevel::Info, "Rendered {} frames.", frames; fps = fps, skipped = 2);
+/// ```
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $fmt:literal $(, $arg:expr)* ; $($key:ident = $value:expr),+ $(,)?) => {{
+        let level: $crate::LogLevel = $level;
+        if $crate::enabled(level, Some(module_path!())) {
+            $crate::log_record(
+                $crate::Record::new(level, format!($fmt $(, $arg)*))
+                    .with_target(module_path!())
+                    .with_location(file!(), line!())
+                    $(.with_field(stringify!($key), $value))+,
+            );
+        }
+    }};
+    ($level:expr, $($arg:tt)+) => {{
+        let level: $crate::LogLevel = $level;
+        if $crate::enabled(level, Some(module_path!())) {
+            $crate::log_record(
+                $crate::Record::new(level, format!($($arg)+))
+                    .with_target(module_path!())
+                    .with_location(file!(), line!()),
+            );
+        }
+    }};
+}
+
+/// Log a formatted line at the trace level, with optional fields; see [`log!`].
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::LogLevel::Trace, $($arg)+)
+    };
+}
+
+/// Log a formatted line at the debug level, with optional fields; see [`log!`].
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::LogLevel::Debug, $($arg)+)
+    };
+}
+
+/// Log a formatted line at the info level, with optional fields; see [`log!`].
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::LogLevel::Info, $($arg)+)
+    };
+}
+
+/// Log a formatted line at the warning level, with optional fields; see [`log!`].
+#[macro_export]
+macro_rules! warning {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::LogLevel::Warning, $($arg)+)
+    };
+}
+
+/// Log a formatted line at the error level, with optional fields; see [`log!`].
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::LogLevel::Error, $($arg)+)
+    };
+}
+
+/// Log a formatted line at the critical level, with optional fields; see [`log!`].
+#[macro_export]
+macro_rules! critical {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::LogLevel::Critical, $($arg)+)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{capture, set_module_level, LogLevel};
+
+    #[test]
+    fn fields_follow_the_format_arguments() {
+        let frames = 12;
+        let records = capture(module_path!(), || {
+            crate::info!("Rendered {} frames.", frames; fps = 29.5, skipped = 2,);
+            crate::warning!("No fields {}.", "here");
+        });
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].level, LogLevel::Info);
+        assert_eq!(records[0].message, "Rendered 12 frames.");
+        assert_eq!(
+            records[0].fields,
+            [
+                ("fps".to_owned(), "29.5".to_owned()),
+                ("skipped".to_owned(), "2".to_owned())
+            ]
+        );
+        assert_eq!(records[0].target.as_deref(), Some(module_path!()));
+        assert_eq!(records[0].file.as_deref(), Some(file!()));
+
+        assert_eq!(records[1].message, "No fields here.");
+        assert!(records[1].fields.is_empty());
+    }
+
+    #[test]
+    fn filtered_lines_are_not_formatted() {
+        set_module_level(concat!(module_path!(), "::quiet"), LogLevel::Error);
+
+        mod quiet {
+            use std::fmt;
+
+            /// A value that must never be formatted.
+            struct Unformattable;
+
+            impl fmt::Display for Unformattable {
+                fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+                    panic!("formatted a filtered line")
+                }
+            }
+
+            pub fn log() {
+                crate::debug!("{}", Unformattable; field = Unformattable);
+            }
+        }
+
+        assert!(capture(module_path!(), quiet::log).is_empty());
+    }
+}
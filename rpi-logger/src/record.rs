@@ -0,0 +1,352 @@
+//! A single log line, as received by the sinks.
+//!
+
+use std::{
+    borrow::Cow,
+    fmt::Display,
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+};
+
+use time::OffsetDateTime;
+
+use super::{config, LogLevel};
+
+/// The width of the thread column in the human format.
+#[cfg(not(feature = "jsonl_logging"))]
+const THREAD_WIDTH: usize = 12;
+
+/// The width of the module column in the human format.
+#[cfg(not(feature = "jsonl_logging"))]
+const MODULE_WIDTH: usize = 24;
+
+/// The width of the `file:line` column in the human format.
+#[cfg(not(feature = "jsonl_logging"))]
+const LOCATION_WIDTH: usize = 24;
+
+/// The id to give to the next thread that logs a line.
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    /// The id of this thread, given the first time it logs a line.
+    static THREAD_ID: u64 = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+}
+
+/// A span the log line was logged in, such as from [`tracing`](https://docs.rs/tracing).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpanContext {
+    /// The name of the span.
+    pub name: String,
+
+    /// The fields recorded on the span, in order.
+    pub fields: Vec<(String, String)>,
+}
+
+impl SpanContext {
+    /// Create a new span context without fields.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            fields: Vec::new(),
+        }
+    }
+}
+
+/// A log line with its level, where it came from and the time it was logged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Record {
+    /// The time the line was logged, in UTC.
+    pub timestamp: OffsetDateTime,
+
+    /// The level of the line.
+    pub level: LogLevel,
+
+    /// The module path the line came from, if known.
+    ///
+    /// Lines logged through the macros have one; lines logged through the
+    /// functions such as [`info`](crate::info) do not.
+    pub target: Option<Cow<'static, str>>,
+
+    /// The source file the line was logged from, if known.
+    pub file: Option<Cow<'static, str>>,
+
+    /// The line in [`file`](Self::file) the line was logged from, if known.
+    pub line: Option<u32>,
+
+    /// The name of the thread the line was logged from, if it has one.
+    pub thread: Option<String>,
+
+    /// A number identifying the thread the line was logged from within this
+    /// process, telling apart threads of the same name, such as the workers of an
+    /// async runtime.
+    pub thread_id: u64,
+
+    /// The message, without formatting.
+    pub message: String,
+
+    /// Key-value pairs attached to the line, in order.
+    pub fields: Vec<(String, String)>,
+
+    /// The spans the line was logged in, outermost first.
+    pub spans: Vec<SpanContext>,
+}
+
+impl Record {
+    /// Create a new record logged now from the current thread, without a target
+    /// or location.
+    pub fn new(level: LogLevel, message: impl Into<String>) -> Self {
+        Self {
+            timestamp: OffsetDateTime::now_utc(),
+            level,
+            target: None,
+            file: None,
+            line: None,
+            thread: thread::current().name().map(str::to_owned),
+            thread_id: THREAD_ID.with(|id| *id),
+            message: message.into(),
+            fields: Vec::new(),
+            spans: Vec::new(),
+        }
+    }
+
+    /// Set the module path the line came from.
+    pub fn with_target(mut self, target: impl Into<Cow<'static, str>>) -> Self {
+        self.target = Some(target.into());
+        self
+    }
+
+    /// Set the source file and line the line was logged from.
+    pub fn with_location(mut self, file: impl Into<Cow<'static, str>>, line: u32) -> Self {
+        self.file = Some(file.into());
+        self.line = Some(line);
+        self
+    }
+
+    /// Attach a key-value pair to the line.
+    pub fn with_field(mut self, key: impl Into<String>, value: impl Display) -> Self {
+        self.fields.push((key.into(), value.to_string()));
+        self
+    }
+
+    /// Add a span the line was logged in, inside those added before.
+    pub fn with_span(mut self, span: SpanContext) -> Self {
+        self.spans.push(span);
+        self
+    }
+
+    /// Format the record as a single line, with ANSI colours if `colour` is set.
+    ///
+    /// With the `jsonl_logging` feature, the record is formatted as a JSON object
+    /// instead, and `colour` is ignored; its fields are top-level keys, prefixed
+    /// with `field_` if they clash with a key of the record itself, such as
+    /// `message`.
+    #[allow(unused_variables)]
+    pub fn format(&self, colour: bool) -> String {
+        let timestamp = self
+            .timestamp
+            .format(&config::DATETIME_FORMAT)
+            .unwrap_or("(Time unavailable)        ".to_owned());
+
+        #[cfg(feature = "jsonl_logging")]
+        {
+            let fields = |fields: &[(String, String)]| {
+                fields
+                    .iter()
+                    .map(|(key, value)| (key.clone(), serde_json::Value::from(value.as_str())))
+                    .collect::<serde_json::Map<_, _>>()
+            };
+
+            let mut line = serde_json::json!(
+                {
+                    "timestamp": timestamp,
+                    "level": self.level.name(),
+                    "target": self.target,
+                    "file": self.file,
+                    "line": self.line,
+                    "thread": self.thread,
+                    "thread_id": self.thread_id,
+                    "message": self.message,
+                }
+            );
+
+            // Lines without spans are formatted as they always were.
+            if !self.spans.is_empty() {
+                line["spans"] = self
+                    .spans
+                    .iter()
+                    .map(|span| {
+                        serde_json::json!({
+                            "name": span.name,
+                            "fields": fields(&span.fields),
+                        })
+                    })
+                    .collect();
+            }
+
+            if let Some(object) = line.as_object_mut() {
+                for (key, value) in fields(&self.fields) {
+                    let key = if object.contains_key(&key) {
+                        format!("field_{key}")
+                    } else {
+                        key
+                    };
+                    object.insert(key, value);
+                }
+            }
+
+            serde_json::to_string(&line).unwrap_or("Invalid JSON".to_owned())
+        }
+
+        #[cfg(not(feature = "jsonl_logging"))]
+        {
+            let message = self.plain_message();
+            let location = match (&self.file, self.line) {
+                (Some(file), Some(line)) => format!("{file}:{line}"),
+                (Some(file), None) => file.to_string(),
+                _ => String::new(),
+            };
+            let thread = format!(
+                "{}#{}",
+                self.thread.as_deref().unwrap_or_default(),
+                self.thread_id
+            );
+            let origin = format!(
+                "{} \u{2502} {} \u{2502} {}",
+                column(&thread, THREAD_WIDTH),
+                column(self.target.as_deref().unwrap_or_default(), MODULE_WIDTH),
+                column(&location, LOCATION_WIDTH),
+            );
+
+            if colour {
+                let level = self.level.wraps(true, &self.level.name().to_uppercase());
+                let message = self.level.wraps(false, &message);
+                let suffix = self.level.suffix();
+
+                let level_len = 9 + 22 + self.level.ansi_code().to_string().len();
+
+                format!(
+                    "{timestamp} \u{2502} {level:<level_len$} \u{2502} {origin} \u{2502} {message}{suffix}"
+                )
+            } else {
+                let level = self.level.name().to_uppercase();
+
+                format!("{timestamp} \u{2502} {level:<8} \u{2502} {origin} \u{2502} {message}")
+            }
+        }
+    }
+
+    /// Format the message with its spans and fields, such as
+    /// `render{frame=3}: Drawn. elapsed=12ms`.
+    #[cfg(not(feature = "jsonl_logging"))]
+    fn plain_message(&self) -> Cow<'_, str> {
+        if self.fields.is_empty() && self.spans.is_empty() {
+            return Cow::Borrowed(self.message.as_str());
+        }
+
+        let pairs = |fields: &[(String, String)], separator: &str| {
+            fields
+                .iter()
+                .map(|(key, value)| format!("{key}={value}"))
+                .collect::<Vec<_>>()
+                .join(separator)
+        };
+
+        let mut message = String::new();
+        for span in &self.spans {
+            message.push_str(&span.name);
+            if !span.fields.is_empty() {
+                message.push_str(&format!("{{{}}}", pairs(&span.fields, " ")));
+            }
+            message.push(':');
+        }
+        if !self.spans.is_empty() {
+            message.push(' ');
+        }
+        message.push_str(&self.message);
+        if !self.fields.is_empty() {
+            message.push(' ');
+            message.push_str(&pairs(&self.fields, " "));
+        }
+
+        Cow::Owned(message)
+    }
+}
+
+/// Pad `text` to `width` characters, keeping only its end if it is longer.
+#[cfg(not(feature = "jsonl_logging"))]
+fn column(text: &str, width: usize) -> String {
+    let length = text.chars().count();
+
+    if length > width {
+        let end = text.chars().skip(length - width + 1).collect::<String>();
+        format!("\u{2026}{end}")
+    } else {
+        format!("{text:<width$}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn threads_are_told_apart() {
+        let here = Record::new(LogLevel::Info, "Here.");
+        let there = thread::Builder::new()
+            .name(here.thread.clone().unwrap_or_default())
+            .spawn(|| Record::new(LogLevel::Info, "There."))
+            .unwrap()
+            .join()
+            .unwrap();
+
+        assert_eq!(here.thread, there.thread);
+        assert_ne!(here.thread_id, there.thread_id);
+        assert_eq!(here.thread_id, Record::new(LogLevel::Info, "").thread_id);
+    }
+
+    #[cfg(not(feature = "jsonl_logging"))]
+    #[test]
+    fn columns_keep_the_end_of_long_text() {
+        assert_eq!(column("main", 6), "main  ");
+        assert_eq!(column("worker", 6), "worker");
+        assert_eq!(column("tokio-runtime-worker#3", 10), "\u{2026}-worker#3");
+        assert_eq!(column("\u{e9}\u{e9}\u{e9}", 2), "\u{2026}\u{e9}");
+        assert_eq!(column("", 2), "  ");
+    }
+
+    #[cfg(not(feature = "jsonl_logging"))]
+    #[test]
+    fn human_lines_show_spans_and_fields() {
+        let record = Record::new(LogLevel::Warning, "Drawn.")
+            .with_target("rpi_display_mipidsi::func::transitions::wipe")
+            .with_location("src/func/transitions/wipe.rs", 42)
+            .with_span(SpanContext {
+                name: "render".to_owned(),
+                fields: vec![("frame".to_owned(), "3".to_owned())],
+            })
+            .with_field("elapsed", "12ms");
+        let line = record.format(false);
+
+        assert!(line.contains(" \u{2502} WARNING  \u{2502} "));
+        assert!(line.contains(&format!("#{}", record.thread_id)));
+        assert!(line.contains("\u{2026}func::transitions::wipe \u{2502} "));
+        assert!(line.contains("\u{2026}/transitions/wipe.rs:42 \u{2502} "));
+        assert!(line.ends_with(" \u{2502} render{frame=3}: Drawn. elapsed=12ms"));
+    }
+
+    #[cfg(feature = "jsonl_logging")]
+    #[test]
+    fn fields_are_top_level_keys() {
+        let record = Record::new(LogLevel::Info, "Rendered.")
+            .with_field("fps", 30)
+            .with_field("message", "clashing");
+        let line: serde_json::Value = serde_json::from_str(&record.format(false)).unwrap();
+
+        assert_eq!(line["message"], "Rendered.");
+        assert_eq!(line["fps"], "30");
+        assert_eq!(line["field_message"], "clashing");
+        assert_eq!(line["thread_id"], record.thread_id);
+        assert!(line.get("fields").is_none());
+        assert!(line.get("spans").is_none());
+    }
+}
@@ -0,0 +1,39 @@
+//! Pass log lines to a closure.
+//!
+
+use std::fmt;
+
+use super::{LogSink, Record};
+
+/// Call a closure with every log line.
+pub struct CallbackSink<F>(F)
+where
+    F: Fn(&Record) + Send + Sync;
+
+impl<F> CallbackSink<F>
+where
+    F: Fn(&Record) + Send + Sync,
+{
+    /// Create a new sink calling `callback` with every log line.
+    pub fn new(callback: F) -> Self {
+        Self(callback)
+    }
+}
+
+impl<F> fmt::Debug for CallbackSink<F>
+where
+    F: Fn(&Record) + Send + Sync,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackSink").finish_non_exhaustive()
+    }
+}
+
+impl<F> LogSink for CallbackSink<F>
+where
+    F: Fn(&Record) + Send + Sync,
+{
+    fn log(&self, record: &Record) {
+        (self.0)(record)
+    }
+}
@@ -0,0 +1,86 @@
+//! Append log lines to a file.
+//!
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    sync::Mutex,
+};
+
+use super::{LogSink, Record};
+
+/// Append every log line to a file, without colours.
+#[derive(Debug)]
+pub struct FileSink {
+    file: Mutex<File>,
+}
+
+impl FileSink {
+    /// Open the file at `path` for appending, creating it if needed.
+    pub fn new(path: impl AsRef<Path>) -> io::Result<Self> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map(Self::from)
+    }
+}
+
+impl From<File> for FileSink {
+    fn from(file: File) -> Self {
+        Self {
+            file: Mutex::new(file),
+        }
+    }
+}
+
+impl LogSink for FileSink {
+    fn log(&self, record: &Record) {
+        let mut file = self
+            .file
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        // Logging must not fail the caller, and the error cannot be logged either.
+        if let Err(err) = writeln!(file, "{}", record.format(false)) {
+            eprintln!("Failed to write log line to file: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogLevel;
+    use std::fs;
+
+    #[test]
+    fn appends_plain_lines() {
+        let path = std::env::temp_dir().join(format!("rpi-logger-file-{}.log", std::process::id()));
+        fs::write(&path, "existing\n").expect("Failed to create log file");
+
+        let records = [
+            Record::new(LogLevel::Warning, "first"),
+            Record::new(LogLevel::Error, "second"),
+        ];
+        let sink = FileSink::new(&path).expect("Failed to open log file");
+        for record in &records {
+            sink.log(record);
+        }
+        drop(sink);
+
+        let contents = fs::read_to_string(&path).expect("Missing log file");
+        assert_eq!(
+            contents.lines().collect::<Vec<_>>(),
+            [
+                "existing".to_owned(),
+                records[0].format(false),
+                records[1].format(false)
+            ]
+        );
+        assert!(!contents.contains('\x1b'));
+
+        fs::remove_file(path).expect("Failed to clean up");
+    }
+}
@@ -0,0 +1,109 @@
+//! Destinations for log lines.
+//!
+
+use std::sync::{Arc, RwLock};
+
+use lazy_static::lazy_static;
+
+use super::Record;
+
+mod callback;
+pub use callback::*;
+
+mod file;
+pub use file::*;
+
+#[cfg(feature = "rotation")]
+mod rotating_file;
+#[cfg(feature = "rotation")]
+pub use rotating_file::*;
+
+mod ring_buffer;
+pub use ring_buffer::*;
+
+mod stderr;
+pub use stderr::*;
+
+/// A destination for log lines, such as a file or an on-screen console.
+///
+/// Sinks are called synchronously from the logging functions, so they should
+/// return quickly; they must not log themselves, as the line would be fed back to
+/// them.
+pub trait LogSink: Send + Sync {
+    /// Receive a log line that passed the [`Filter`](crate::Filter).
+    fn log(&self, record: &Record);
+}
+
+lazy_static! {
+    /// The sinks currently registered, [`StderrSink`] only by default.
+    static ref SINKS: RwLock<Vec<Arc<dyn LogSink>>> =
+        RwLock::new(vec![Arc::new(StderrSink::default())]);
+}
+
+/// Register a sink to receive every log line from now on.
+pub fn add_sink(sink: Arc<dyn LogSink>) {
+    SINKS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .push(sink);
+}
+
+/// Stop sending log lines to a sink registered by [`add_sink`].
+///
+/// Returns whether the sink was registered.
+pub fn remove_sink(sink: &Arc<dyn LogSink>) -> bool {
+    let mut sinks = SINKS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let count = sinks.len();
+
+    sinks.retain(|registered| !Arc::ptr_eq(registered, sink));
+
+    sinks.len() != count
+}
+
+/// Replace all the registered sinks, including the default [`StderrSink`].
+pub fn set_sinks(sinks: Vec<Arc<dyn LogSink>>) {
+    *SINKS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = sinks;
+}
+
+/// Send a log line to every registered sink.
+pub(crate) fn dispatch(record: &Record) {
+    // The list is copied, so that sinks can be added or removed from within a sink.
+    let sinks = SINKS
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone();
+
+    for sink in sinks {
+        sink.log(record);
+    }
+}
+
+/// Collect the lines logged from this thread by `log`, with every level let through
+/// for `module`.
+#[cfg(test)]
+pub(crate) fn capture(module: &str, log: impl FnOnce()) -> Vec<Record> {
+    use std::sync::Mutex;
+
+    let thread_id = Record::new(crate::LogLevel::Trace, "").thread_id;
+    let records = Arc::new(Mutex::new(Vec::new()));
+    let sink: Arc<dyn LogSink> = Arc::new(CallbackSink::new({
+        let records = records.clone();
+        move |record: &Record| {
+            if record.thread_id == thread_id {
+                records.lock().unwrap().push(record.clone());
+            }
+        }
+    }));
+
+    crate::set_module_level(module, crate::LogLevel::Trace);
+    add_sink(sink.clone());
+    log();
+    remove_sink(&sink);
+
+    let records = records.lock().unwrap().clone();
+    records
+}
@@ -0,0 +1,128 @@
+//! Keep the last log lines in memory.
+//!
+
+use std::{collections::VecDeque, sync::Mutex};
+
+use super::{LogSink, Record};
+
+/// Keep the last log lines in memory, dropping the oldest when full.
+#[derive(Debug)]
+pub struct RingBufferSink {
+    records: Mutex<VecDeque<Record>>,
+    capacity: usize,
+}
+
+impl RingBufferSink {
+    /// Create a new sink keeping the last `capacity` lines.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    /// Get a copy of the lines kept, the oldest first.
+    pub fn records(&self) -> Vec<Record> {
+        self.records
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Remove all the lines kept.
+    pub fn clear(&self) {
+        self.records
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clear();
+    }
+
+    /// Get the number of lines kept.
+    pub fn len(&self) -> usize {
+        self.records
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .len()
+    }
+
+    /// Check if no lines are kept.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl LogSink for RingBufferSink {
+    fn log(&self, record: &Record) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut records = self
+            .records
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if records.len() >= self.capacity {
+            records.pop_front();
+        }
+        records.push_back(record.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogLevel;
+
+    fn log_lines(sink: &RingBufferSink, messages: &[&str]) {
+        for message in messages {
+            sink.log(&Record::new(LogLevel::Info, *message));
+        }
+    }
+
+    fn messages(sink: &RingBufferSink) -> Vec<String> {
+        sink.records()
+            .into_iter()
+            .map(|record| record.message)
+            .collect()
+    }
+
+    #[test]
+    fn evicts_the_oldest_when_full() {
+        let sink = RingBufferSink::new(3);
+
+        log_lines(&sink, &["one", "two"]);
+        assert_eq!(messages(&sink), ["one", "two"]);
+
+        log_lines(&sink, &["three", "four", "five"]);
+        assert_eq!(sink.len(), 3);
+        assert_eq!(messages(&sink), ["three", "four", "five"]);
+    }
+
+    #[test]
+    fn records_are_a_snapshot() {
+        let sink = RingBufferSink::new(2);
+        log_lines(&sink, &["one"]);
+
+        let snapshot = sink.records();
+        log_lines(&sink, &["two", "three"]);
+
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].message, "one");
+        assert_eq!(messages(&sink), ["two", "three"]);
+
+        sink.clear();
+        assert!(sink.is_empty());
+    }
+
+    #[test]
+    fn zero_capacity_keeps_nothing() {
+        let sink = RingBufferSink::new(0);
+        log_lines(&sink, &["one", "two"]);
+
+        assert!(sink.is_empty());
+        assert!(sink.records().is_empty());
+    }
+}
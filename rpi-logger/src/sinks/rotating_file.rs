@@ -0,0 +1,556 @@
+//! Write log lines to a file, rotating it by size or by day.
+//!
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, Sender, SyncSender, TrySendError},
+        Mutex,
+    },
+    thread::{self, JoinHandle},
+};
+
+use flate2::{write::GzEncoder, Compression};
+use time::{Date, OffsetDateTime};
+
+use super::{LogSink, Record};
+
+/// When to rotate the file of a [`RotatingFileSink`], and what to keep.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RotationPolicy {
+    /// Rotate before a line would make the file larger than this many bytes.
+    pub max_size: Option<u64>,
+
+    /// Rotate at midnight UTC, rather than local time, as the local offset cannot
+    /// be read reliably once other threads are running.
+    pub daily: bool,
+
+    /// The number of rotated files to keep, the oldest being deleted first.
+    pub max_files: usize,
+
+    /// Compress rotated files with gzip.
+    pub compress: bool,
+}
+
+impl Default for RotationPolicy {
+    /// Rotate at 10 MiB, keeping 5 compressed files.
+    fn default() -> Self {
+        Self {
+            max_size: Some(10 * 1024 * 1024),
+            daily: false,
+            max_files: 5,
+            compress: true,
+        }
+    }
+}
+
+impl RotationPolicy {
+    /// Rotate before the file grows larger than `bytes`, or never if `None`.
+    pub fn with_max_size(mut self, bytes: Option<u64>) -> Self {
+        self.max_size = bytes;
+        self
+    }
+
+    /// Rotate at midnight UTC; see [`daily`](Self::daily).
+    pub fn with_daily(mut self, daily: bool) -> Self {
+        self.daily = daily;
+        self
+    }
+
+    /// Keep at most `count` rotated files.
+    pub fn with_max_files(mut self, count: usize) -> Self {
+        self.max_files = count;
+        self
+    }
+
+    /// Compress rotated files with gzip.
+    pub fn with_compress(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+}
+
+/// The number of lines a [`RotatingFileSink`] queues for its writer thread by
+/// default.
+pub const DEFAULT_QUEUE_LENGTH: usize = 1024;
+
+/// What a [`RotatingFileSink`] does with a line logged while its queue is full,
+/// such as when the SD card stalls.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Overflow {
+    /// Drop the line, counting it in [`RotatingFileSink::dropped`].
+    #[default]
+    Discard,
+
+    /// Wait for room in the queue, blocking the thread logging the line.
+    Block,
+}
+
+/// A message to the writer thread.
+enum Message {
+    Line(String),
+    Flush(Sender<()>),
+}
+
+/// Write every log line to a file in the same format as
+/// [`FileSink`](super::FileSink), rotating it according to a [`RotationPolicy`].
+///
+/// Rotated files are named after the file with a number appended, `1` being the
+/// most recent, such as `rpi.log.1.gz`.
+///
+/// Lines are written by a background thread through a bounded queue, so logging
+/// does not wait for the SD card unless the queue fills up, in which case the
+/// [`Overflow`] policy applies; call [`flush`](Self::flush) before exiting to make
+/// sure every line is written.
+#[derive(Debug)]
+pub struct RotatingFileSink {
+    sender: Mutex<SyncSender<Message>>,
+    overflow: Overflow,
+    dropped: AtomicU64,
+    writer: Option<JoinHandle<()>>,
+}
+
+impl RotatingFileSink {
+    /// Open the file at `path` for appending, creating it if needed, and start the
+    /// writer thread, queueing up to [`DEFAULT_QUEUE_LENGTH`] lines and dropping
+    /// any more.
+    pub fn new(path: impl Into<PathBuf>, policy: RotationPolicy) -> io::Result<Self> {
+        Self::with_queue(path, policy, DEFAULT_QUEUE_LENGTH, Overflow::default())
+    }
+
+    /// Open the file at `path` for appending, creating it if needed, and start the
+    /// writer thread, queueing up to `length` lines before applying `overflow`.
+    ///
+    /// At least one line is always queued, as without a queue nearly every line
+    /// would find the writer thread busy.
+    pub fn with_queue(
+        path: impl Into<PathBuf>,
+        policy: RotationPolicy,
+        length: usize,
+        overflow: Overflow,
+    ) -> io::Result<Self> {
+        let writer = Writer::open(path.into(), policy)?;
+        let (sender, receiver) = mpsc::sync_channel(length.max(1));
+
+        let writer = thread::Builder::new()
+            .name("rpi-logger-file".to_owned())
+            .spawn(move || writer.run(receiver))?;
+
+        Ok(Self {
+            sender: Mutex::new(sender),
+            overflow,
+            dropped: AtomicU64::new(0),
+            writer: Some(writer),
+        })
+    }
+
+    /// Get the number of lines dropped so far because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Wait until every line logged so far is written to the file.
+    pub fn flush(&self) {
+        let (sender, receiver) = mpsc::channel();
+
+        if self.send(Message::Flush(sender)) {
+            // The writer thread only stops when the sink is dropped.
+            let _ = receiver.recv();
+        }
+    }
+
+    /// Send a message to the writer thread, waiting for room in the queue,
+    /// returning whether it is still running.
+    fn send(&self, message: Message) -> bool {
+        self.sender
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .send(message)
+            .is_ok()
+    }
+
+    /// Queue a line for the writer thread, according to the [`Overflow`] policy.
+    fn send_line(&self, line: String) {
+        if self.overflow == Overflow::Block {
+            self.send(Message::Line(line));
+            return;
+        }
+
+        let result = self
+            .sender
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .try_send(Message::Line(line));
+
+        if let Err(TrySendError::Full(_)) = result {
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for RotatingFileSink {
+    /// Write the remaining lines, and stop the writer thread.
+    fn drop(&mut self) {
+        // Replacing the sender disconnects the channel, ending the thread.
+        *self
+            .sender
+            .get_mut()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = mpsc::sync_channel(0).0;
+
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+    }
+}
+
+impl LogSink for RotatingFileSink {
+    fn log(&self, record: &Record) {
+        self.send_line(record.format(false));
+    }
+}
+
+/// The file being written by the writer thread.
+struct Writer {
+    path: PathBuf,
+    policy: RotationPolicy,
+    file: BufWriter<File>,
+    size: u64,
+    date: Date,
+}
+
+impl Writer {
+    /// Open the file for appending, resuming from its size and modification date.
+    fn open(path: PathBuf, policy: RotationPolicy) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let metadata = file.metadata()?;
+        let date = metadata
+            .modified()
+            .map(OffsetDateTime::from)
+            .unwrap_or_else(|_| OffsetDateTime::now_utc())
+            .date();
+
+        Ok(Self {
+            path,
+            policy,
+            file: BufWriter::new(file),
+            size: metadata.len(),
+            date,
+        })
+    }
+
+    /// Write messages until the sink is dropped, flushing whenever the channel is
+    /// empty.
+    fn run(mut self, receiver: Receiver<Message>) {
+        while let Ok(message) = receiver.recv() {
+            self.handle(message);
+
+            while let Ok(message) = receiver.try_recv() {
+                self.handle(message);
+            }
+
+            report(&self.path, self.file.flush());
+        }
+
+        report(&self.path, self.file.flush());
+    }
+
+    fn handle(&mut self, message: Message) {
+        match message {
+            Message::Line(line) => {
+                let result = self.write_line(&line, OffsetDateTime::now_utc().date());
+                report(&self.path, result);
+            }
+            Message::Flush(done) => {
+                report(&self.path, self.file.flush());
+                let _ = done.send(());
+            }
+        }
+    }
+
+    /// Write a line on the given day, rotating the file first if needed; days
+    /// change at midnight UTC.
+    fn write_line(&mut self, line: &str, today: Date) -> io::Result<()> {
+        let length = line.len() as u64 + 1;
+
+        let oversized = self
+            .policy
+            .max_size
+            .is_some_and(|max_size| self.size > 0 && self.size + length > max_size);
+        let overdue = self.policy.daily && today != self.date;
+
+        if oversized || overdue {
+            self.rotate()?;
+        }
+
+        writeln!(self.file, "{line}")?;
+        self.size += length;
+        self.date = today;
+
+        Ok(())
+    }
+
+    /// Get the path of the `index`th rotated file.
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{index}"));
+        if self.policy.compress {
+            path.push(".gz");
+        }
+
+        path.into()
+    }
+
+    /// Move the file to the first rotated file, shifting the others along and
+    /// deleting the oldest, then start a new file.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+
+        if self.policy.max_files == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            remove_if_exists(&self.rotated_path(self.policy.max_files))?;
+            for index in (1..self.policy.max_files).rev() {
+                let from = self.rotated_path(index);
+                if from.exists() {
+                    fs::rename(from, self.rotated_path(index + 1))?;
+                }
+            }
+
+            if self.policy.compress {
+                compress(&self.path, &self.rotated_path(1))?;
+                fs::remove_file(&self.path)?;
+            } else {
+                fs::rename(&self.path, self.rotated_path(1))?;
+            }
+        }
+
+        self.file = BufWriter::new(
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?,
+        );
+        self.size = 0;
+
+        Ok(())
+    }
+}
+
+/// Report a failure to write the file at `path` on `stderr`, as it cannot be
+/// logged.
+fn report(path: &Path, result: io::Result<()>) {
+    if let Err(err) = result {
+        eprintln!("Failed to write log file {path:?}: {err}");
+    }
+}
+
+/// Delete a file, if it exists.
+fn remove_if_exists(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
+
+/// Compress the file at `from` into a new gzip file at `to`.
+fn compress(from: &Path, to: &Path) -> io::Result<()> {
+    let mut encoder = GzEncoder::new(File::create(to)?, Compression::default());
+    io::copy(&mut File::open(from)?, &mut encoder)?;
+    encoder.finish()?.sync_all()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::LogLevel;
+    use std::io::Read;
+
+    use flate2::read::GzDecoder;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rpi-logger-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("Failed to create temporary directory");
+        dir
+    }
+
+    fn read_gz(path: &Path) -> String {
+        let mut contents = String::new();
+        GzDecoder::new(File::open(path).expect("Missing rotated file"))
+            .read_to_string(&mut contents)
+            .expect("Invalid gzip file");
+        contents
+    }
+
+    #[test]
+    fn rotate_by_size() {
+        let dir = temp_dir("size");
+        let path = dir.join("test.log");
+        let sink = RotatingFileSink::new(
+            &path,
+            RotationPolicy::default()
+                .with_max_size(Some(60))
+                .with_max_files(2),
+        )
+        .expect("Failed to create sink");
+
+        for index in 0..6 {
+            sink.log(&Record::new(LogLevel::Info, format!("line {index}")));
+        }
+        drop(sink);
+
+        // Each line is too long for two to share a file.
+        let current = fs::read_to_string(&path).expect("Missing log file");
+        assert_eq!(current.lines().count(), 1);
+        assert!(current.contains("line 5"));
+        assert!(read_gz(&dir.join("test.log.1.gz")).contains("line 4"));
+        assert!(read_gz(&dir.join("test.log.2.gz")).contains("line 3"));
+        assert!(!dir.join("test.log.3.gz").exists());
+
+        fs::remove_dir_all(dir).expect("Failed to clean up");
+    }
+
+    #[test]
+    fn flush_without_rotation() {
+        let dir = temp_dir("flush");
+        let path = dir.join("test.log");
+        let sink = RotatingFileSink::new(
+            &path,
+            RotationPolicy::default()
+                .with_max_size(None)
+                .with_compress(false),
+        )
+        .expect("Failed to create sink");
+
+        sink.log(&Record::new(LogLevel::Warning, "first"));
+        sink.log(&Record::new(LogLevel::Error, "second"));
+        sink.flush();
+
+        let contents = fs::read_to_string(&path).expect("Missing log file");
+        assert_eq!(contents.lines().count(), 2);
+        assert!(contents
+            .lines()
+            .last()
+            .is_some_and(|line| line.contains("second")));
+
+        drop(sink);
+        fs::remove_dir_all(dir).expect("Failed to clean up");
+    }
+
+    #[test]
+    fn rotate_daily() {
+        let dir = temp_dir("daily");
+        let path = dir.join("test.log");
+        let mut writer = Writer::open(
+            path.clone(),
+            RotationPolicy::default()
+                .with_max_size(None)
+                .with_daily(true)
+                .with_compress(false),
+        )
+        .expect("Failed to open log file");
+
+        let day = Date::from_calendar_date(2024, time::Month::February, 28).unwrap();
+        writer.date = day;
+
+        for line in ["before midnight", "still the same day"] {
+            writer.write_line(line, day).unwrap();
+        }
+        writer
+            .write_line("after midnight", day.next_day().unwrap())
+            .unwrap();
+        writer.file.flush().unwrap();
+
+        assert_eq!(
+            fs::read_to_string(dir.join("test.log.1")).expect("Missing rotated file"),
+            "before midnight\nstill the same day\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&path).expect("Missing log file"),
+            "after midnight\n"
+        );
+
+        drop(writer);
+        fs::remove_dir_all(dir).expect("Failed to clean up");
+    }
+
+    #[test]
+    fn empty_queues_hold_a_line() {
+        let dir = temp_dir("empty-queue");
+        let path = dir.join("test.log");
+        let sink = RotatingFileSink::with_queue(
+            &path,
+            RotationPolicy::default().with_max_size(None),
+            0,
+            Overflow::Discard,
+        )
+        .expect("Failed to create sink");
+
+        sink.log(&Record::new(LogLevel::Info, "kept"));
+        sink.flush();
+
+        assert_eq!(sink.dropped(), 0);
+        assert!(fs::read_to_string(&path)
+            .expect("Missing log file")
+            .contains("kept"));
+
+        drop(sink);
+        fs::remove_dir_all(dir).expect("Failed to clean up");
+    }
+
+    /// Create a sink with a queue of `length` lines and no writer thread, so that
+    /// the queue can be filled.
+    fn stalled(length: usize, overflow: Overflow) -> (RotatingFileSink, Receiver<Message>) {
+        let (sender, receiver) = mpsc::sync_channel(length);
+        let sink = RotatingFileSink {
+            sender: Mutex::new(sender),
+            overflow,
+            dropped: AtomicU64::new(0),
+            writer: None,
+        };
+
+        (sink, receiver)
+    }
+
+    #[test]
+    fn full_queues_drop_lines() {
+        let (sink, receiver) = stalled(2, Overflow::Discard);
+
+        for index in 0..5 {
+            sink.log(&Record::new(LogLevel::Info, format!("line {index}")));
+        }
+
+        assert_eq!(sink.dropped(), 3);
+        let queued = receiver
+            .try_iter()
+            .filter(|message| matches!(message, Message::Line(_)))
+            .count();
+        assert_eq!(queued, 2);
+    }
+
+    #[test]
+    fn full_queues_block_until_there_is_room() {
+        let (sink, receiver) = stalled(1, Overflow::Block);
+
+        let lines = thread::scope(|scope| {
+            scope.spawn(|| {
+                for index in 0..3 {
+                    sink.log(&Record::new(LogLevel::Info, format!("line {index}")));
+                }
+            });
+
+            (0..3)
+                .map(|_| match receiver.recv() {
+                    Ok(Message::Line(line)) => line,
+                    _ => panic!("Expected a line"),
+                })
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(sink.dropped(), 0);
+        assert!(lines[2].contains("line 2"));
+    }
+}
@@ -0,0 +1,42 @@
+//! Write log lines to `stderr`.
+//!
+
+use super::{LogSink, Record};
+
+/// Write every log line to `stderr`, coloured by level unless disabled.
+///
+/// This is the only sink registered by default.
+#[derive(Clone, Copy, Debug)]
+pub struct StderrSink {
+    colour: bool,
+}
+
+impl Default for StderrSink {
+    fn default() -> Self {
+        Self { colour: true }
+    }
+}
+
+impl StderrSink {
+    /// Create a new sink writing plain lines, without ANSI colours.
+    pub fn plain() -> Self {
+        Self { colour: false }
+    }
+}
+
+impl LogSink for StderrSink {
+    fn log(&self, record: &Record) {
+        eprintln!("{}", record.format(self.colour));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coloured_unless_plain() {
+        assert!(StderrSink::default().colour);
+        assert!(!StderrSink::plain().colour);
+    }
+}
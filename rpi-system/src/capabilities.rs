@@ -0,0 +1,327 @@
+//! Hardware peripherals available on each Raspberry Pi model.
+//!
+
+use rpi_errors::*;
+use rppal::spi::Bus;
+use std::sync::RwLock;
+
+use crate::{model, Model};
+
+/// A [`Model`] to use in place of the one reported by the running device.
+static MODEL_OVERRIDE: RwLock<Option<Model>> = RwLock::new(None);
+
+/// Override the [`Model`] used by [`capabilities`], so that board validation can be
+/// tested without the hardware; pass [`None`] to restore the detected model.
+pub fn set_model_override(model: Option<Model>) {
+    *MODEL_OVERRIDE
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner) = model;
+}
+
+/// Get the [`Capabilities`] of the current device, or of the overriding model if
+/// one is set.
+pub fn capabilities() -> RPiResult<Capabilities> {
+    let overridden = *MODEL_OVERRIDE
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+    match overridden {
+        Some(model) => Ok(Capabilities::from_model(model)),
+        None => model().map(|model| Capabilities::from_model(*model)),
+    }
+}
+
+/// BCM pins available on the 26-pin header of the first revision Model B.
+const HEADER_26_PINS_REV1: &[u8] = &[0, 1, 4, 7, 8, 9, 10, 11, 14, 15, 17, 18, 21, 22, 23, 24, 25];
+
+/// BCM pins available on the 26-pin header of the second revision Model A and B.
+const HEADER_26_PINS_REV2: &[u8] = &[2, 3, 4, 7, 8, 9, 10, 11, 14, 15, 17, 18, 22, 23, 24, 25, 27];
+
+/// BCM pins available on the 40-pin header.
+const HEADER_40_PINS: &[u8] = &[
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+    26, 27,
+];
+
+/// BCM pins available on the SO-DIMM or board-to-board connectors of Compute Modules.
+const COMPUTE_MODULE_PINS: &[u8] = &[
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+    26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45,
+];
+
+/// The hardware peripherals available on a Raspberry Pi [`Model`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Capabilities {
+    pub model: Model,
+
+    /// The BCM pins that are broken out.
+    pub gpio_pins: &'static [u8],
+
+    /// The SPI buses that can be enabled.
+    pub spi_buses: &'static [Bus],
+
+    /// The highest SPI clock speed in Hz, being half of the default core clock.
+    pub max_spi_clock_speed: u32,
+}
+
+impl Capabilities {
+    /// Get the capabilities of a [`Model`].
+    ///
+    /// Models unknown to this crate are assumed to have a 40-pin header with only
+    /// `SPI0` and `SPI1`.
+    pub fn from_model(model: Model) -> Self {
+        let (gpio_pins, spi_buses, max_spi_clock_speed): (&[u8], &[Bus], u32) = match model {
+            Model::RaspberryPiBRev1 => (HEADER_26_PINS_REV1, &[Bus::Spi0], 125_000_000),
+            Model::RaspberryPiA | Model::RaspberryPiBRev2 => {
+                (HEADER_26_PINS_REV2, &[Bus::Spi0], 125_000_000)
+            }
+            Model::RaspberryPiAPlus
+            | Model::RaspberryPiBPlus
+            | Model::RaspberryPi2B
+            | Model::RaspberryPiZero
+            | Model::RaspberryPiZeroW => (HEADER_40_PINS, &[Bus::Spi0, Bus::Spi1], 125_000_000),
+            Model::RaspberryPi3APlus
+            | Model::RaspberryPi3B
+            | Model::RaspberryPi3BPlus
+            | Model::RaspberryPiZero2W => (HEADER_40_PINS, &[Bus::Spi0, Bus::Spi1], 200_000_000),
+            Model::RaspberryPiComputeModule => (
+                COMPUTE_MODULE_PINS,
+                &[Bus::Spi0, Bus::Spi1, Bus::Spi2],
+                125_000_000,
+            ),
+            Model::RaspberryPiComputeModule3 | Model::RaspberryPiComputeModule3Plus => (
+                COMPUTE_MODULE_PINS,
+                &[Bus::Spi0, Bus::Spi1, Bus::Spi2],
+                200_000_000,
+            ),
+            Model::RaspberryPi4B | Model::RaspberryPi400 => (
+                HEADER_40_PINS,
+                &[
+                    Bus::Spi0,
+                    Bus::Spi1,
+                    Bus::Spi3,
+                    Bus::Spi4,
+                    Bus::Spi5,
+                    Bus::Spi6,
+                ],
+                250_000_000,
+            ),
+            Model::RaspberryPiComputeModule4 => (
+                COMPUTE_MODULE_PINS,
+                &[
+                    Bus::Spi0,
+                    Bus::Spi1,
+                    Bus::Spi2,
+                    Bus::Spi3,
+                    Bus::Spi4,
+                    Bus::Spi5,
+                    Bus::Spi6,
+                ],
+                250_000_000,
+            ),
+            _ => (HEADER_40_PINS, &[Bus::Spi0, Bus::Spi1], 125_000_000),
+        };
+
+        Self {
+            model,
+            gpio_pins,
+            spi_buses,
+            max_spi_clock_speed,
+        }
+    }
+
+    /// Get the hardware PWM channel that a BCM pin can be muxed to on this model, if
+    /// the pin is broken out and has one.
+    pub fn pwm_channel(&self, pin: u8) -> Option<u8> {
+        if !self.gpio_pins.contains(&pin) {
+            return None;
+        }
+
+        match pin {
+            12 | 18 | 40 => Some(0),
+            13 | 19 | 41 | 45 => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Check that a BCM pin is broken out on this model.
+    pub fn validate_gpio_pin(&self, pin: u8) -> RPiResult<()> {
+        if self.gpio_pins.contains(&pin) {
+            Ok(())
+        } else {
+            Err(RPiError::System(
+                format!("GPIO {pin} is not broken out on {}.", self.model).into(),
+            ))
+        }
+    }
+
+    /// Check that all the BCM pins are broken out on this model.
+    pub fn validate_gpio_pins(&self, pins: &[u8]) -> RPiResult<()> {
+        pins.iter().try_for_each(|pin| self.validate_gpio_pin(*pin))
+    }
+
+    /// Check that the SPI bus exists on this model, and that the clock speed is
+    /// achievable.
+    pub fn validate_spi(&self, bus: Bus, clock_speed: u32) -> RPiResult<()> {
+        if !self.spi_buses.contains(&bus) {
+            return Err(RPiError::System(
+                format!(
+                    "{bus} is not available on {}; available buses are {}.",
+                    self.model,
+                    self.spi_buses
+                        .iter()
+                        .map(|bus| bus.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+                .into(),
+            ));
+        }
+
+        if clock_speed > self.max_spi_clock_speed {
+            return Err(RPiError::System(
+                format!(
+                    "SPI clock speed of {clock_speed} Hz is not achievable on {}; the maximum is {} Hz.",
+                    self.model, self.max_spi_clock_speed
+                )
+                .into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Check that a pin is broken out on this model, and can be driven by a hardware
+    /// PWM channel, returning the channel.
+    ///
+    /// This is only needed for components driven by hardware PWM, such as through
+    /// [`rppal::pwm`]; backlights and LEDs dimmed by software PWM can be on any pin,
+    /// so [`validate_gpio_pins`](Self::validate_gpio_pins) is enough for them.
+    pub fn validate_pwm_pin(&self, pin: u8) -> RPiResult<u8> {
+        self.validate_gpio_pin(pin)?;
+
+        self.pwm_channel(pin).ok_or_else(|| {
+            RPiError::System(
+                format!(
+                    "GPIO {pin} cannot be driven by a hardware PWM channel on {}.",
+                    self.model
+                )
+                .into(),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spi_buses_by_model() {
+        let zero = Capabilities::from_model(Model::RaspberryPiZero2W);
+        assert!(zero.validate_spi(Bus::Spi0, 70_000_000).is_ok());
+        assert!(zero.validate_spi(Bus::Spi1, 10_000_000).is_ok());
+        assert!(zero.validate_spi(Bus::Spi3, 10_000_000).is_err());
+
+        let pi4 = Capabilities::from_model(Model::RaspberryPi4B);
+        assert!(pi4.validate_spi(Bus::Spi3, 10_000_000).is_ok());
+        assert!(pi4.validate_spi(Bus::Spi2, 10_000_000).is_err());
+
+        let rev2 = Capabilities::from_model(Model::RaspberryPiBRev2);
+        assert!(rev2.validate_spi(Bus::Spi1, 10_000_000).is_err());
+    }
+
+    #[test]
+    fn spi_clock_speed() {
+        let zero = Capabilities::from_model(Model::RaspberryPiZeroW);
+        assert!(zero.validate_spi(Bus::Spi0, 125_000_000).is_ok());
+        assert!(matches!(
+            zero.validate_spi(Bus::Spi0, 200_000_000),
+            Err(RPiError::System(_))
+        ));
+    }
+
+    #[test]
+    fn pwm_pins() {
+        let zero = Capabilities::from_model(Model::RaspberryPiZero2W);
+        assert_eq!(zero.validate_pwm_pin(13).unwrap(), 1);
+        assert_eq!(zero.validate_pwm_pin(12).unwrap(), 0);
+        assert!(zero.validate_pwm_pin(17).is_err());
+
+        // `GPIO 13` is not on the 26-pin header.
+        let rev2 = Capabilities::from_model(Model::RaspberryPiBRev2);
+        assert!(rev2.validate_pwm_pin(13).is_err());
+        assert_eq!(rev2.validate_pwm_pin(18).unwrap(), 0);
+    }
+
+    /// Get the PWM channel of each of the pins that have one on some model.
+    fn pwm_channels(model: Model) -> Vec<Option<u8>> {
+        let capabilities = Capabilities::from_model(model);
+
+        [12, 13, 18, 19, 40, 41, 45]
+            .into_iter()
+            .map(|pin| capabilities.pwm_channel(pin))
+            .collect()
+    }
+
+    #[test]
+    fn pwm_channels_by_model() {
+        // Only `GPIO 18` is on the 26-pin headers.
+        for model in [Model::RaspberryPiBRev1, Model::RaspberryPiBRev2] {
+            assert_eq!(
+                pwm_channels(model),
+                [None, None, Some(0), None, None, None, None]
+            );
+        }
+
+        for model in [
+            Model::RaspberryPiZeroW,
+            Model::RaspberryPi3B,
+            Model::RaspberryPi4B,
+        ] {
+            assert_eq!(
+                pwm_channels(model),
+                [Some(0), Some(1), Some(0), Some(1), None, None, None]
+            );
+        }
+
+        // Compute Modules also break out the pins of the second bank.
+        for model in [
+            Model::RaspberryPiComputeModule3,
+            Model::RaspberryPiComputeModule4,
+        ] {
+            assert_eq!(
+                pwm_channels(model),
+                [
+                    Some(0),
+                    Some(1),
+                    Some(0),
+                    Some(1),
+                    Some(0),
+                    Some(1),
+                    Some(1)
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn gpio_pins() {
+        let rev1 = Capabilities::from_model(Model::RaspberryPiBRev1);
+        assert!(rev1.validate_gpio_pins(&[0, 1, 21]).is_ok());
+        assert!(rev1.validate_gpio_pins(&[2, 3]).is_err());
+
+        let cm4 = Capabilities::from_model(Model::RaspberryPiComputeModule4);
+        assert!(cm4.validate_gpio_pins(&[40, 45]).is_ok());
+    }
+
+    #[test]
+    fn model_override() {
+        set_model_override(Some(Model::RaspberryPiBRev2));
+        let capabilities = capabilities().unwrap();
+        set_model_override(None);
+
+        assert_eq!(capabilities.model, Model::RaspberryPiBRev2);
+        assert_eq!(capabilities.spi_buses, &[Bus::Spi0]);
+    }
+}
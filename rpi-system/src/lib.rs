@@ -2,12 +2,23 @@
 //!
 //! This is a thin wrapper around [`rppal::system`], re-exporting the most common
 //! structs and types while providing additional helper functions.
+//!
+//! The [`Capabilities`] of the running model can be used to validate the buses and
+//! pins requested by a board before they are initialised.
+//!
+//! The [`telemetry`] module reads the health of the running system, such as the SoC
+//! temperature and throttling flags.
 
 use rpi_errors::*;
 use std::sync::{Arc, OnceLock};
 
 pub use rppal::system::{DeviceInfo, Error as SystemError, Model, SoC};
 
+mod capabilities;
+pub use capabilities::*;
+
+pub mod telemetry;
+
 /// A static reference to the [`DeviceInfo`] struct for the current device.
 static DEVICE_INFO: OnceLock<Result<Arc<DeviceInfo>, SystemError>> = OnceLock::new();
 
@@ -18,10 +29,7 @@ static MODEL: OnceLock<Result<Arc<Model>, SystemError>> = OnceLock::new();
 static SOC: OnceLock<Result<Arc<SoC>, SystemError>> = OnceLock::new();
 
 /// Helper function to get a static reference to a [`OnceLock`].
-fn get_static<'e, T, F>(
-    data: &OnceLock<Result<Arc<T>, SystemError>>,
-    getter: F,
-) -> RPiResult<'e, Arc<T>>
+fn get_static<T, F>(data: &OnceLock<Result<Arc<T>, SystemError>>, getter: F) -> RPiResult<Arc<T>>
 where
     F: Fn() -> Result<T, SystemError>,
 {
@@ -32,18 +40,18 @@ where
 }
 
 /// Get the [`DeviceInfo`] struct for the current device.
-pub fn device_info<'e>() -> RPiResult<'e, Arc<DeviceInfo>> {
+pub fn device_info() -> RPiResult<Arc<DeviceInfo>> {
     get_static(&DEVICE_INFO, rppal::system::DeviceInfo::new)
 }
 
 /// Get the [``] struct for the current device.
-pub fn model<'e>() -> RPiResult<'e, Arc<Model>> {
+pub fn model() -> RPiResult<Arc<Model>> {
     let device_info = device_info()?;
     get_static(&MODEL, || Ok(device_info.model()))
 }
 
 /// Get the [`SoC`] struct for the current device.
-pub fn soc<'e>() -> RPiResult<'e, Arc<SoC>> {
+pub fn soc() -> RPiResult<Arc<SoC>> {
     let device_info = device_info()?;
     get_static(&SOC, || Ok(device_info.soc()))
 }
@@ -0,0 +1,384 @@
+//! System health telemetry, read asynchronously from sysfs and procfs.
+//!
+//! All the readers resolve their paths against the configurable roots of a
+//! [`Telemetry`], so that they can be pointed at fixture directories in tests.
+
+use rpi_errors::*;
+use std::{
+    ffi::CString,
+    net::IpAddr,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::fs;
+
+mod models;
+pub use models::*;
+
+mod network;
+
+/// Reader for the health of the running system.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Telemetry {
+    sysfs_root: PathBuf,
+    procfs_root: PathBuf,
+}
+
+impl Default for Telemetry {
+    /// Read from the real `/sys` and `/proc`.
+    fn default() -> Self {
+        Self::with_roots("/sys", "/proc")
+    }
+}
+
+impl Telemetry {
+    /// Create a new reader for the real `/sys` and `/proc`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new reader with the given sysfs and procfs roots.
+    pub fn with_roots(sysfs_root: impl Into<PathBuf>, procfs_root: impl Into<PathBuf>) -> Self {
+        Self {
+            sysfs_root: sysfs_root.into(),
+            procfs_root: procfs_root.into(),
+        }
+    }
+
+    /// The sysfs root of this reader.
+    pub fn sysfs_root(&self) -> &Path {
+        &self.sysfs_root
+    }
+
+    /// The procfs root of this reader.
+    pub fn procfs_root(&self) -> &Path {
+        &self.procfs_root
+    }
+
+    /// Read a file relative to the sysfs root.
+    async fn read_sysfs(&self, path: &str) -> RPiResult<String> {
+        let path = self.sysfs_root.join(path);
+
+        fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("read {}", path.display()))
+    }
+
+    /// Read a file relative to the procfs root.
+    async fn read_procfs(&self, path: &str) -> RPiResult<String> {
+        let path = self.procfs_root.join(path);
+
+        fs::read_to_string(&path)
+            .await
+            .with_context(|| format!("read {}", path.display()))
+    }
+
+    /// Get the SoC temperature, in degrees Celsius.
+    pub async fn temperature(&self) -> RPiResult<f64> {
+        let content = self.read_sysfs("class/thermal/thermal_zone0/temp").await?;
+
+        parse_value::<i64>(&content, "SoC temperature")
+            .map(|millidegrees| millidegrees as f64 / 1000.)
+    }
+
+    /// Get the under-voltage and throttling flags reported by the firmware.
+    pub async fn throttled(&self) -> RPiResult<ThrottledFlags> {
+        let content = self
+            .read_sysfs("devices/platform/soc/soc:firmware/get_throttled")
+            .await?;
+        let trimmed = content.trim();
+
+        u32::from_str_radix(trimmed.trim_start_matches("0x"), 16)
+            .map(ThrottledFlags)
+            .map_err(|_| parse_error("throttled flags", trimmed))
+    }
+
+    /// Get the current frequency of the given CPU core, in Hz.
+    pub async fn cpu_frequency(&self, core: usize) -> RPiResult<u64> {
+        let content = self
+            .read_sysfs(&format!(
+                "devices/system/cpu/cpu{core}/cpufreq/scaling_cur_freq"
+            ))
+            .await?;
+
+        parse_value::<u64>(&content, "CPU frequency").map(|kilohertz| kilohertz * 1000)
+    }
+
+    /// Get the system load averages.
+    pub async fn load_average(&self) -> RPiResult<LoadAverage> {
+        let content = self.read_procfs("loadavg").await?;
+        let mut values = content
+            .split_whitespace()
+            .map(|value| parse_value::<f64>(value, "load average"));
+
+        let mut next = || {
+            values
+                .next()
+                .unwrap_or_else(|| Err(parse_error("load average", &content)))
+        };
+
+        Ok(LoadAverage {
+            one: next()?,
+            five: next()?,
+            fifteen: next()?,
+        })
+    }
+
+    /// Get the memory usage.
+    pub async fn memory(&self) -> RPiResult<MemoryUsage> {
+        let content = self.read_procfs("meminfo").await?;
+
+        let field = |name: &str| -> RPiResult<u64> {
+            content
+                .lines()
+                .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+                .ok_or_else(|| parse_error("memory info", name))
+                .and_then(|value| {
+                    parse_value::<u64>(value.trim().trim_end_matches("kB"), "memory info")
+                })
+                .map(|kilobytes| kilobytes * 1024)
+        };
+
+        Ok(MemoryUsage {
+            total: field("MemTotal")?,
+            free: field("MemFree")?,
+            available: field("MemAvailable")?,
+        })
+    }
+
+    /// Get the time since boot.
+    pub async fn uptime(&self) -> RPiResult<Duration> {
+        let content = self.read_procfs("uptime").await?;
+        let seconds = content
+            .split_whitespace()
+            .next()
+            .ok_or_else(|| parse_error("uptime", &content))?;
+
+        parse_value::<f64>(seconds, "uptime").map(Duration::from_secs_f64)
+    }
+
+    /// Get the usage of the filesystem containing `path`.
+    ///
+    /// # Note
+    ///
+    /// Unlike the other readers, `path` is not resolved against the roots of this
+    /// reader, as disk usage is not exposed through sysfs or procfs.
+    pub async fn disk_usage(&self, path: impl AsRef<Path>) -> RPiResult<DiskUsage> {
+        let path = CString::new(path.as_ref().as_os_str().as_bytes())
+            .map_err(|err| RPiError::InvalidInput("path".into(), err.to_string().into()))?;
+
+        let stat = tokio::task::spawn_blocking(move || {
+            let mut stat = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+
+            // SAFETY: `path` is a valid null-terminated string, and `stat` is only
+            // read after `statvfs` reports success.
+            unsafe {
+                if libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(stat.assume_init())
+            }
+        })
+        .await??;
+
+        let fragment_size = stat.f_frsize as u64;
+        Ok(DiskUsage {
+            total: stat.f_blocks as u64 * fragment_size,
+            free: stat.f_bfree as u64 * fragment_size,
+            available: stat.f_bavail as u64 * fragment_size,
+        })
+    }
+
+    /// Get the network interfaces and their addresses, ordered by name.
+    ///
+    /// Missing IPv4 or IPv6 tables are treated as empty, as either protocol can be
+    /// disabled.
+    pub async fn network_interfaces(&self) -> RPiResult<Vec<NetworkInterface>> {
+        let mut interfaces = Vec::new();
+
+        let mut entries = fs::read_dir(self.sysfs_root.join("class/net")).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().into_owned();
+
+            // Not every entry is an interface, such as the `bonding_masters` file.
+            if !fs::try_exists(entry.path().join("operstate"))
+                .await
+                .unwrap_or(false)
+            {
+                continue;
+            }
+
+            let operstate = self
+                .read_sysfs(&format!("class/net/{name}/operstate"))
+                .await?;
+            let is_up = match operstate.trim() {
+                "up" => true,
+                // Loopback and some drivers never report their state, so fall back to
+                // whether the interface was brought up, `IFF_UP`.
+                "unknown" => {
+                    let flags = self.read_sysfs(&format!("class/net/{name}/flags")).await?;
+                    u32::from_str_radix(flags.trim().trim_start_matches("0x"), 16)
+                        .map_err(|_| parse_error("interface flags", flags.trim()))?
+                        & 0x1
+                        != 0
+                }
+                _ => false,
+            };
+
+            interfaces.push(NetworkInterface {
+                name,
+                is_up,
+                addresses: Vec::new(),
+            });
+        }
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let fib_trie = self.read_procfs("net/fib_trie").await.unwrap_or_default();
+        let routes =
+            network::parse_routes(&self.read_procfs("net/route").await.unwrap_or_default());
+        let if_inet6 = self.read_procfs("net/if_inet6").await.unwrap_or_default();
+
+        let ipv4 = network::parse_fib_trie_local_addresses(&fib_trie)
+            .into_iter()
+            .filter_map(|address| {
+                network::interface_for_address(&address, &routes)
+                    .map(|name| (name.to_owned(), IpAddr::V4(address)))
+            });
+        let ipv6 = network::parse_if_inet6(&if_inet6);
+
+        for (name, address) in ipv4.chain(ipv6) {
+            if let Some(interface) = interfaces
+                .iter_mut()
+                .find(|interface| interface.name == name)
+            {
+                interface.addresses.push(address);
+            }
+        }
+
+        Ok(interfaces)
+    }
+}
+
+/// Create an error for a value that could not be parsed.
+fn parse_error(name: &str, value: &str) -> RPiError {
+    RPiError::System(format!("Failed to parse {name} from {value:?}.").into())
+}
+
+/// Parse a single value, ignoring surrounding whitespace.
+fn parse_value<T: std::str::FromStr>(value: &str, name: &str) -> RPiResult<T> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| parse_error(name, value.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    fn fixture() -> Telemetry {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        Telemetry::with_roots(root.join("sys"), root.join("proc"))
+    }
+
+    #[tokio::test]
+    async fn temperature() {
+        assert_eq!(fixture().temperature().await.unwrap(), 48.312);
+    }
+
+    #[tokio::test]
+    async fn throttled() {
+        let flags = fixture().throttled().await.unwrap();
+
+        assert!(!flags.under_voltage());
+        assert!(flags.under_voltage_occurred());
+        assert!(flags.soft_temperature_limit());
+        assert!(!flags.throttled_occurred());
+        assert!(!flags.is_healthy());
+    }
+
+    #[tokio::test]
+    async fn cpu_frequency() {
+        assert_eq!(fixture().cpu_frequency(0).await.unwrap(), 1_000_000_000);
+        let err = fixture().cpu_frequency(4).await.unwrap_err();
+        assert!(matches!(err.root(), RPiError::IOError(_)));
+        assert!(err.to_string().starts_with("Failed to read "));
+    }
+
+    #[tokio::test]
+    async fn load_average() {
+        let load = fixture().load_average().await.unwrap();
+
+        assert_eq!(
+            load,
+            LoadAverage {
+                one: 0.42,
+                five: 0.31,
+                fifteen: 0.17
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn memory() {
+        let memory = fixture().memory().await.unwrap();
+
+        assert_eq!(memory.total, 439_952 * 1024);
+        assert_eq!(memory.free, 131_180 * 1024);
+        assert_eq!(memory.available, 293_492 * 1024);
+        assert_eq!(memory.used(), (439_952 - 293_492) * 1024);
+    }
+
+    #[tokio::test]
+    async fn uptime() {
+        assert_eq!(
+            fixture().uptime().await.unwrap(),
+            Duration::from_secs_f64(86_523.67)
+        );
+    }
+
+    #[tokio::test]
+    async fn disk_usage() {
+        let usage = fixture()
+            .disk_usage(env!("CARGO_MANIFEST_DIR"))
+            .await
+            .unwrap();
+
+        assert!(usage.total > 0);
+        assert!(usage.free <= usage.total);
+    }
+
+    #[tokio::test]
+    async fn network_interfaces() {
+        let interfaces = fixture().network_interfaces().await.unwrap();
+
+        assert_eq!(
+            interfaces,
+            vec![
+                NetworkInterface {
+                    name: "eth0".to_owned(),
+                    is_up: false,
+                    addresses: Vec::new(),
+                },
+                NetworkInterface {
+                    name: "lo".to_owned(),
+                    is_up: true,
+                    addresses: vec![
+                        IpAddr::V4(Ipv4Addr::LOCALHOST),
+                        IpAddr::V6(Ipv6Addr::LOCALHOST)
+                    ],
+                },
+                NetworkInterface {
+                    name: "wlan0".to_owned(),
+                    is_up: true,
+                    addresses: vec![
+                        IpAddr::V4(Ipv4Addr::new(192, 168, 1, 23)),
+                        IpAddr::V6("fe80::ba27:ebff:fe12:3456".parse().unwrap())
+                    ],
+                },
+            ]
+        );
+    }
+}
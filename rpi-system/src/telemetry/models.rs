@@ -0,0 +1,134 @@
+//! Readings returned by [`Telemetry`](super::Telemetry).
+//!
+
+use std::net::IpAddr;
+
+/// Under-voltage and throttling flags reported by the firmware, in the same layout
+/// as `vcgencmd get_throttled`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ThrottledFlags(pub u32);
+
+macro_rules! expand_throttled_flags {
+    ($((
+        $name:ident,
+        $occurred:ident,
+        $bit:literal,
+        $doc:literal
+    )),*) => {
+        impl ThrottledFlags {
+            $(
+                #[doc = "Whether "]
+                #[doc = $doc]
+                #[doc = " right now."]
+                pub fn $name(&self) -> bool {
+                    self.0 & (1 << $bit) != 0
+                }
+
+                #[doc = "Whether "]
+                #[doc = $doc]
+                #[doc = " at any point since boot."]
+                pub fn $occurred(&self) -> bool {
+                    self.0 & (1 << ($bit + 16)) != 0
+                }
+            )*
+        }
+    };
+}
+
+expand_throttled_flags!(
+    (
+        under_voltage,
+        under_voltage_occurred,
+        0,
+        "the supply is under-voltage"
+    ),
+    (
+        frequency_capped,
+        frequency_capped_occurred,
+        1,
+        "the ARM frequency is capped"
+    ),
+    (throttled, throttled_occurred, 2, "the CPU is throttled"),
+    (
+        soft_temperature_limit,
+        soft_temperature_limit_occurred,
+        3,
+        "the soft temperature limit is active"
+    )
+);
+
+impl ThrottledFlags {
+    /// Whether none of the flags are set, now or since boot.
+    pub fn is_healthy(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// System load averages over 1, 5 and 15 minutes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+}
+
+/// Memory usage, in bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryUsage {
+    pub total: u64,
+    pub free: u64,
+    pub available: u64,
+}
+
+impl MemoryUsage {
+    /// The memory in use, in bytes.
+    pub fn used(&self) -> u64 {
+        self.total.saturating_sub(self.available)
+    }
+
+    /// The fraction of memory in use, between `0` and `1`.
+    pub fn used_fraction(&self) -> f64 {
+        if self.total == 0 {
+            0.
+        } else {
+            self.used() as f64 / self.total as f64
+        }
+    }
+}
+
+/// Usage of a filesystem, in bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiskUsage {
+    pub total: u64,
+    pub free: u64,
+
+    /// The space available to unprivileged users.
+    pub available: u64,
+}
+
+impl DiskUsage {
+    /// The space in use, in bytes.
+    pub fn used(&self) -> u64 {
+        self.total.saturating_sub(self.free)
+    }
+
+    /// The fraction of space in use, between `0` and `1`.
+    pub fn used_fraction(&self) -> f64 {
+        if self.total == 0 {
+            0.
+        } else {
+            self.used() as f64 / self.total as f64
+        }
+    }
+}
+
+/// A network interface and its addresses.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NetworkInterface {
+    pub name: String,
+
+    /// Whether the operational state of the interface is `up`, or whether it was
+    /// brought up if the driver does not report one, as for loopback.
+    pub is_up: bool,
+    pub addresses: Vec<IpAddr>,
+}
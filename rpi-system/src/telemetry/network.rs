@@ -0,0 +1,100 @@
+//! Parsers for the network tables in procfs.
+//!
+//! Linux does not list the IPv4 addresses of each interface in procfs; instead, the
+//! local addresses are taken from the routing trie, and assigned to the interface
+//! with the most specific route covering each of them.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// Parse the local IPv4 addresses out of `/proc/net/fib_trie`.
+pub(crate) fn parse_fib_trie_local_addresses(content: &str) -> Vec<Ipv4Addr> {
+    let mut addresses = Vec::new();
+    let mut last_address = None;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if let Some(address) = trimmed.strip_prefix("|-- ") {
+            last_address = address.parse::<Ipv4Addr>().ok();
+        } else if trimmed == "/32 host LOCAL" {
+            if let Some(address) = last_address.take() {
+                if !addresses.contains(&address) {
+                    addresses.push(address);
+                }
+            }
+        }
+    }
+
+    addresses
+}
+
+/// A row of `/proc/net/route`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Route {
+    pub interface: String,
+    pub destination: Ipv4Addr,
+    pub mask: Ipv4Addr,
+}
+
+/// Parse an address written as hexadecimal in host byte order, as in
+/// `/proc/net/route`.
+fn parse_route_address(hex: &str) -> Option<Ipv4Addr> {
+    u32::from_str_radix(hex, 16)
+        .ok()
+        .map(|value| Ipv4Addr::from(value.to_ne_bytes()))
+}
+
+/// Parse the rows of `/proc/net/route`.
+pub(crate) fn parse_routes(content: &str) -> Vec<Route> {
+    content
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let columns = line.split_whitespace().collect::<Vec<_>>();
+
+            Some(Route {
+                interface: columns.first()?.to_string(),
+                destination: parse_route_address(columns.get(1)?)?,
+                mask: parse_route_address(columns.get(7)?)?,
+            })
+        })
+        .collect()
+}
+
+/// Find the interface with the most specific route to a local IPv4 address.
+pub(crate) fn interface_for_address<'r>(
+    address: &Ipv4Addr,
+    routes: &'r [Route],
+) -> Option<&'r str> {
+    if address.is_loopback() {
+        return Some("lo");
+    }
+
+    let address = u32::from(*address);
+
+    routes
+        .iter()
+        .filter(|route| {
+            let mask = u32::from(route.mask);
+            mask != 0 && address & mask == u32::from(route.destination)
+        })
+        .max_by_key(|route| u32::from(route.mask).count_ones())
+        .map(|route| route.interface.as_str())
+}
+
+/// Parse the IPv6 addresses of each interface out of `/proc/net/if_inet6`.
+pub(crate) fn parse_if_inet6(content: &str) -> Vec<(String, IpAddr)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let columns = line.split_whitespace().collect::<Vec<_>>();
+            let [hex, _, _, _, _, name] = columns[..] else {
+                return None;
+            };
+
+            let address = u128::from_str_radix(hex, 16).ok()?;
+
+            Some((name.to_string(), IpAddr::V6(Ipv6Addr::from(address))))
+        })
+        .collect()
+}
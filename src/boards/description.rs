@@ -0,0 +1,600 @@
+//! Runtime description of a board with a SPI display, buttons and LEDs.
+//!
+//! A board description can be written in TOML or JSON, and used to build a
+//! [`GenericDisplayBoard`](super::GenericDisplayBoard). The SPI bus, pins,
+//! orientation, buttons and LEDs are all read at runtime; the panel model and size
+//! are type parameters of the board, so they are only checked against the board
+//! type the description is loaded into. For example, the Pimoroni Display HAT Mini
+//! is described by the following, and loaded into a
+//! [`GenericST7789Board<320, 240>`](super::GenericST7789Board):
+//!
+//! ```toml
+//! name = "Pimoroni Display HAT Mini"
+//!
+//! [spi]
+//! bus = 0
+//! slave = 1
+//! clock_speed = 70_000_000
+//! mode = 0
+//!
+//! [display]
+//! model = "ST7789"
+//! width = 320
+//! height = 240
+//! dc = 9
+//! backlight = 13
+//! orientation = "landscape-inverted"
+//! mirrored = true
+//! invert_colours = true
+//! tearing_effect = "horizontal-and-vertical"
+//!
+//! [[buttons]]
+//! name = "a"
+//! pin = 5
+//!
+//! [[buttons]]
+//! name = "b"
+//! pin = 6
+//!
+//! [[buttons]]
+//! name = "x"
+//! pin = 16
+//!
+//! [[buttons]]
+//! name = "y"
+//! pin = 24
+//!
+//! [[leds]]
+//! name = "led"
+//! red = 17
+//! green = 27
+//! blue = 22
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::display_mipidsi::{func::fs, ColorInversion, Orientation, TearingEffect};
+use crate::errors::{RPiError, RPiResult};
+use rppal::spi::{Bus, Mode as SpiMode, SlaveSelect};
+
+/// The names of the panel models a [`GenericDisplayBoard`](super::GenericDisplayBoard)
+/// can be built with.
+pub const PANEL_MODELS: &[&str] = &["ST7735s", "ST7735", "ST7789"];
+
+/// The default PWM frequency for backlights and LEDs, in Hz.
+fn default_pwm_frequency() -> f64 {
+    50.
+}
+
+/// Description of a board with a SPI display, buttons and LEDs.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BoardDescription {
+    pub name: String,
+    pub spi: SpiDescription,
+    pub display: DisplayDescription,
+
+    #[serde(default)]
+    pub buttons: Vec<ButtonDescription>,
+
+    #[serde(default)]
+    pub leds: Vec<LedDescription>,
+}
+
+impl BoardDescription {
+    /// Parse a board description from a TOML string.
+    pub fn from_toml_str(s: &str) -> RPiResult<Self> {
+        toml::from_str(s).map_err(|err| {
+            RPiError::InvalidInput("board description".into(), err.to_string().into())
+        })
+    }
+
+    /// Parse a board description from a JSON string.
+    pub fn from_json_str(s: &str) -> RPiResult<Self> {
+        serde_json::from_str(s).map_err(|err| {
+            RPiError::InvalidInput("board description".into(), err.to_string().into())
+        })
+    }
+
+    /// Read a board description from a file; the format is chosen by the file
+    /// extension, which must be either `.toml` or `.json`.
+    pub async fn from_path(path: impl AsRef<Path> + std::fmt::Debug) -> RPiResult<Self> {
+        let extension = path
+            .as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+
+        let bytes = fs::read_bytes_from_file(&path).await?;
+        let content = String::from_utf8(bytes).map_err(|err| {
+            RPiError::InvalidInput("board description".into(), err.to_string().into())
+        })?;
+
+        match extension.as_deref() {
+            Some("toml") => Self::from_toml_str(&content),
+            Some("json") => Self::from_json_str(&content),
+            _ => Err(RPiError::InvalidInput(
+                "board description path".into(),
+                format!("{:?} is neither a `.toml` nor a `.json` file", path).into(),
+            )),
+        }
+    }
+
+    /// Check that the panel model is known, that every GPIO pin is only used once
+    /// in this description, and that no two buttons nor two LEDs share a name.
+    pub fn validate(&self) -> RPiResult<()> {
+        if !PANEL_MODELS
+            .iter()
+            .any(|model| model.eq_ignore_ascii_case(&self.display.model))
+        {
+            return Err(RPiError::InvalidInput(
+                "display.model".into(),
+                format!(
+                    "{} is not one of the panel models {}",
+                    self.display.model,
+                    PANEL_MODELS.join(", ")
+                )
+                .into(),
+            ));
+        }
+
+        let mut pins = vec![("display DC", self.display.dc)];
+        pins.extend(self.display.reset.map(|pin| ("display reset", pin)));
+        pins.push(("display backlight", self.display.backlight));
+        pins.extend(self.buttons.iter().map(|button| ("button", button.pin)));
+        pins.extend(
+            self.leds
+                .iter()
+                .flat_map(|led| [("LED", led.red), ("LED", led.green), ("LED", led.blue)]),
+        );
+
+        for (index, (usage, pin)) in pins.iter().enumerate() {
+            if let Some((other_usage, _)) =
+                pins[..index].iter().find(|(_, other_pin)| other_pin == pin)
+            {
+                return Err(RPiError::InvalidInput(
+                    "board description".into(),
+                    format!("GPIO {pin} is used by both {other_usage} and {usage}").into(),
+                ));
+            }
+        }
+
+        // Buttons and LEDs are looked up by name, so a duplicate could never be used.
+        let names = [
+            (
+                "button",
+                self.buttons.iter().map(|button| &button.name).collect(),
+            ),
+            (
+                "LED",
+                self.leds.iter().map(|led| &led.name).collect::<Vec<_>>(),
+            ),
+        ];
+        for (usage, names) in names {
+            for (index, name) in names.iter().enumerate() {
+                if names[..index].contains(name) {
+                    return Err(RPiError::InvalidInput(
+                        "board description".into(),
+                        format!("more than one {usage} is named {name:?}").into(),
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Description of the SPI bus that a display is connected to.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpiDescription {
+    /// The SPI bus number, i.e. `0` for `SPI0`.
+    pub bus: u8,
+
+    /// The slave select line, i.e. `1` for `CE1`.
+    pub slave: u8,
+
+    /// The clock speed in Hz.
+    pub clock_speed: u32,
+
+    /// The SPI mode, from `0` to `3`.
+    #[serde(default)]
+    pub mode: u8,
+}
+
+impl SpiDescription {
+    /// Get the SPI bus.
+    pub fn bus(&self) -> RPiResult<Bus> {
+        match self.bus {
+            0 => Ok(Bus::Spi0),
+            1 => Ok(Bus::Spi1),
+            2 => Ok(Bus::Spi2),
+            3 => Ok(Bus::Spi3),
+            4 => Ok(Bus::Spi4),
+            5 => Ok(Bus::Spi5),
+            6 => Ok(Bus::Spi6),
+            bus => Err(RPiError::InvalidInput(
+                "spi.bus".into(),
+                bus.to_string().into(),
+            )),
+        }
+    }
+
+    /// Get the slave select line.
+    pub fn slave(&self) -> RPiResult<SlaveSelect> {
+        match self.slave {
+            0 => Ok(SlaveSelect::Ss0),
+            1 => Ok(SlaveSelect::Ss1),
+            2 => Ok(SlaveSelect::Ss2),
+            3 => Ok(SlaveSelect::Ss3),
+            4 => Ok(SlaveSelect::Ss4),
+            5 => Ok(SlaveSelect::Ss5),
+            6 => Ok(SlaveSelect::Ss6),
+            7 => Ok(SlaveSelect::Ss7),
+            8 => Ok(SlaveSelect::Ss8),
+            9 => Ok(SlaveSelect::Ss9),
+            10 => Ok(SlaveSelect::Ss10),
+            11 => Ok(SlaveSelect::Ss11),
+            12 => Ok(SlaveSelect::Ss12),
+            13 => Ok(SlaveSelect::Ss13),
+            14 => Ok(SlaveSelect::Ss14),
+            15 => Ok(SlaveSelect::Ss15),
+            slave => Err(RPiError::InvalidInput(
+                "spi.slave".into(),
+                slave.to_string().into(),
+            )),
+        }
+    }
+
+    /// Get the `MOSI`, `SCLK` and Chip Select pins of the SPI bus, if known.
+    ///
+    /// `MISO` is left out, as it is commonly repurposed as the Data/Command line of
+    /// write-only displays.
+    pub fn reserved_pins(&self) -> Vec<u8> {
+        let (mosi, sclk, chip_selects): (u8, u8, &[u8]) = match self.bus {
+            0 => (10, 11, &[8, 7]),
+            1 => (20, 21, &[18, 17, 16]),
+            _ => return Vec::new(),
+        };
+
+        let mut pins = vec![mosi, sclk];
+        pins.extend(chip_selects.get(self.slave as usize));
+        pins
+    }
+
+    /// Get the SPI mode.
+    pub fn mode(&self) -> RPiResult<SpiMode> {
+        match self.mode {
+            0 => Ok(SpiMode::Mode0),
+            1 => Ok(SpiMode::Mode1),
+            2 => Ok(SpiMode::Mode2),
+            3 => Ok(SpiMode::Mode3),
+            mode => Err(RPiError::InvalidInput(
+                "spi.mode".into(),
+                mode.to_string().into(),
+            )),
+        }
+    }
+}
+
+/// Orientation of a display, without the mirroring.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OrientationDescription {
+    #[default]
+    Portrait,
+    Landscape,
+    PortraitInverted,
+    LandscapeInverted,
+}
+
+/// Tearing effect output of a display.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TearingEffectDescription {
+    #[default]
+    Off,
+    Vertical,
+    HorizontalAndVertical,
+}
+
+/// Description of a SPI display panel and its control pins.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DisplayDescription {
+    /// Name of the panel model, such as `ST7789`.
+    pub model: String,
+    pub width: u16,
+    pub height: u16,
+
+    /// The Data/Command pin.
+    pub dc: u8,
+
+    /// The reset pin, if connected.
+    #[serde(default)]
+    pub reset: Option<u8>,
+
+    /// The backlight pin, controlled by PWM.
+    pub backlight: u8,
+
+    #[serde(default = "default_pwm_frequency")]
+    pub backlight_frequency: f64,
+
+    #[serde(default)]
+    pub orientation: OrientationDescription,
+
+    #[serde(default)]
+    pub mirrored: bool,
+
+    #[serde(default)]
+    pub invert_colours: bool,
+
+    #[serde(default)]
+    pub tearing_effect: TearingEffectDescription,
+}
+
+impl DisplayDescription {
+    /// Check that the display is the given panel model and size, which a board
+    /// fixes at compile time.
+    pub fn check_panel(&self, model: &str, width: u16, height: u16) -> RPiResult<()> {
+        if !self.model.eq_ignore_ascii_case(model) {
+            return Err(RPiError::InvalidInput(
+                "display.model".into(),
+                format!(
+                    "{} does not match the panel model {} of this board",
+                    self.model, model
+                )
+                .into(),
+            ));
+        }
+
+        if (self.width, self.height) != (width, height) {
+            return Err(RPiError::InvalidInput(
+                "display size".into(),
+                format!(
+                    "{}x{} does not match the size {}x{} of this board",
+                    self.width, self.height, width, height
+                )
+                .into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Get the orientation of the display.
+    pub fn orientation(&self) -> Orientation {
+        match self.orientation {
+            OrientationDescription::Portrait => Orientation::Portrait(self.mirrored),
+            OrientationDescription::Landscape => Orientation::Landscape(self.mirrored),
+            OrientationDescription::PortraitInverted => {
+                Orientation::PortraitInverted(self.mirrored)
+            }
+            OrientationDescription::LandscapeInverted => {
+                Orientation::LandscapeInverted(self.mirrored)
+            }
+        }
+    }
+
+    /// Get the colour inversion of the display.
+    pub fn colour_inversion(&self) -> ColorInversion {
+        if self.invert_colours {
+            ColorInversion::Inverted
+        } else {
+            ColorInversion::Normal
+        }
+    }
+
+    /// Get the tearing effect output of the display.
+    pub fn tearing_effect(&self) -> TearingEffect {
+        match self.tearing_effect {
+            TearingEffectDescription::Off => TearingEffect::Off,
+            TearingEffectDescription::Vertical => TearingEffect::Vertical,
+            TearingEffectDescription::HorizontalAndVertical => TearingEffect::HorizontalAndVertical,
+        }
+    }
+}
+
+/// Description of a button connected to a single pin.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ButtonDescription {
+    pub name: String,
+    pub pin: u8,
+
+    /// Whether the pin should be pulled down instead of up.
+    #[serde(default)]
+    pub inverted: bool,
+}
+
+/// Description of a RGB LED connected to three pins.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LedDescription {
+    pub name: String,
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+
+    #[serde(default = "default_pwm_frequency")]
+    pub frequency: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOML: &str = r#"
+        name = "Pimoroni Display HAT Mini"
+
+        [spi]
+        bus = 0
+        slave = 1
+        clock_speed = 70_000_000
+
+        [display]
+        model = "ST7789"
+        width = 320
+        height = 240
+        dc = 9
+        backlight = 13
+        orientation = "landscape-inverted"
+        mirrored = true
+        tearing_effect = "horizontal-and-vertical"
+
+        [[buttons]]
+        name = "a"
+        pin = 5
+
+        [[leds]]
+        name = "led"
+        red = 17
+        green = 27
+        blue = 22
+    "#;
+
+    const JSON: &str = r#"{
+        "name": "Pimoroni Display HAT Mini",
+        "spi": { "bus": 0, "slave": 1, "clock_speed": 70000000 },
+        "display": {
+            "model": "ST7789",
+            "width": 320,
+            "height": 240,
+            "dc": 9,
+            "backlight": 13,
+            "orientation": "landscape-inverted",
+            "mirrored": true,
+            "tearing_effect": "horizontal-and-vertical"
+        },
+        "buttons": [{ "name": "a", "pin": 5 }],
+        "leds": [{ "name": "led", "red": 17, "green": 27, "blue": 22 }]
+    }"#;
+
+    fn hat_mini() -> BoardDescription {
+        BoardDescription::from_toml_str(TOML).unwrap()
+    }
+
+    #[test]
+    fn toml_and_json_are_equivalent() {
+        let description = hat_mini();
+        assert_eq!(BoardDescription::from_json_str(JSON).unwrap(), description);
+        assert!(description.validate().is_ok());
+
+        assert_eq!(description.spi.mode, 0);
+        assert_eq!(description.spi.reserved_pins(), [10, 11, 7]);
+        assert_eq!(description.display.reset, None);
+        assert_eq!(description.display.backlight_frequency, 50.);
+        assert!(matches!(
+            description.display.orientation(),
+            Orientation::LandscapeInverted(true)
+        ));
+        assert!(matches!(
+            description.display.colour_inversion(),
+            ColorInversion::Normal
+        ));
+        assert!(matches!(
+            description.display.tearing_effect(),
+            TearingEffect::HorizontalAndVertical
+        ));
+        assert!(!description.buttons[0].inverted);
+        assert_eq!(description.leds[0].frequency, 50.);
+    }
+
+    #[test]
+    fn malformed_descriptions_are_rejected() {
+        assert!(BoardDescription::from_toml_str(&TOML.replace("dc = 9", "")).is_err());
+        assert!(BoardDescription::from_toml_str(&TOML.replace("dc = 9", "dc = 256")).is_err());
+        assert!(BoardDescription::from_toml_str(&TOML.replace("mirrored", "mirrorred")).is_err());
+        assert!(BoardDescription::from_toml_str(
+            &TOML.replace("\"landscape-inverted\"", "\"upside-down\"")
+        )
+        .is_err());
+        assert!(BoardDescription::from_json_str(&JSON.replace("}", "")).is_err());
+    }
+
+    #[test]
+    fn unknown_model() {
+        let mut description = hat_mini();
+
+        description.display.model = "st7735s".to_owned();
+        assert!(description.validate().is_ok());
+
+        description.display.model = "ILI9341".to_owned();
+        assert!(matches!(
+            description.validate(),
+            Err(RPiError::InvalidInput(name, _)) if name == "display.model"
+        ));
+    }
+
+    #[test]
+    fn pin_conflicts() {
+        let mut description = hat_mini();
+        description.buttons[0].pin = 13;
+        assert!(description.validate().is_err());
+
+        let mut description = hat_mini();
+        description.display.reset = Some(22);
+        assert!(description.validate().is_err());
+
+        let mut description = hat_mini();
+        description.leds[0].blue = 17;
+        assert!(description.validate().is_err());
+    }
+
+    #[test]
+    fn duplicate_names() {
+        let mut description = hat_mini();
+        description.buttons.push(ButtonDescription {
+            name: "a".to_owned(),
+            pin: 6,
+            inverted: false,
+        });
+        assert!(matches!(
+            description.validate(),
+            Err(RPiError::InvalidInput(_, reason)) if reason == "more than one button is named \"a\""
+        ));
+
+        let mut description = hat_mini();
+        description.leds.push(LedDescription {
+            name: "led".to_owned(),
+            red: 23,
+            green: 24,
+            blue: 25,
+            frequency: 50.,
+        });
+        assert!(matches!(
+            description.validate(),
+            Err(RPiError::InvalidInput(_, reason)) if reason == "more than one LED is named \"led\""
+        ));
+
+        // A button and a LED are looked up separately, so they can share a name.
+        let mut description = hat_mini();
+        description.leds[0].name = "a".to_owned();
+        assert!(description.validate().is_ok());
+    }
+
+    #[test]
+    fn panel_must_match_the_board() {
+        let display = hat_mini().display;
+
+        assert!(display.check_panel("st7789", 320, 240).is_ok());
+        assert!(display.check_panel("ST7735s", 320, 240).is_err());
+        assert!(display.check_panel("ST7789", 240, 320).is_err());
+    }
+
+    #[test]
+    fn spi_settings() {
+        let mut spi = hat_mini().spi;
+        assert!(matches!(spi.bus(), Ok(Bus::Spi0)));
+        assert!(matches!(spi.slave(), Ok(SlaveSelect::Ss1)));
+        assert!(matches!(spi.mode(), Ok(SpiMode::Mode0)));
+
+        spi.bus = 7;
+        spi.slave = 16;
+        spi.mode = 4;
+        assert!(spi.bus().is_err() && spi.slave().is_err() && spi.mode().is_err());
+        assert!(spi.reserved_pins().is_empty());
+    }
+}
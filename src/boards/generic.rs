@@ -0,0 +1,283 @@
+//! A board with a SPI display, buttons and LEDs, built from a [`BoardDescription`].
+//!
+
+use async_trait::async_trait;
+use std::{collections::HashMap, marker::PhantomData, path::Path};
+
+use super::BoardDescription;
+use crate::display_mipidsi::{
+    screen_models,
+    traits::{BacklightComponent, DisplayComponent, PresetModel},
+    DisplaySPIInterfaceNoCS, SpiLcdDisplay,
+};
+use crate::errors::{ErrorContext, IntoRPiResult, RPiError, RPiResult, ResultContext};
+use crate::gpio::{
+    func,
+    registry::{self, PinFunction, PinGuard},
+    traits::HardwareComponent,
+    Button, DisplayBacklight, OutputPin, RgbLed,
+};
+use crate::system;
+use async_mutex::Mutex;
+use rppal::{hal::Delay, spi::Spi};
+
+/// A board with a SPI display, buttons and LEDs, wired as described by a
+/// [`BoardDescription`].
+///
+/// # Limitations
+///
+/// The display driver needs the panel model and the display size at compile time,
+/// so they are type parameters of the board rather than read from the description;
+/// pick them in code, such as `GenericST7789Board::<320, 240>`. The description must
+/// name the same model and size, or [`from_description`](Self::from_description)
+/// returns [`RPiError::InvalidInput`]. Everything else is read at runtime.
+pub struct GenericDisplayBoard<MODEL, const W: u16, const H: u16>
+where
+    MODEL: PresetModel,
+{
+    // Prevents instantiation of this struct.
+    _phantom: PhantomData<()>,
+    pub description: BoardDescription,
+    pub buttons: HashMap<String, Button>,
+    pub leds: HashMap<String, Mutex<RgbLed>>,
+
+    pub display: Mutex<SpiLcdDisplay<MODEL, W, H>>,
+
+    // Released only after all the devices above are dropped.
+    _pins: Vec<PinGuard>,
+}
+
+/// Convenience type for a generic board with a ST7735S display.
+pub type GenericST7735sBoard<const W: u16, const H: u16> =
+    GenericDisplayBoard<screen_models::ST7735s, W, H>;
+
+/// Convenience type for a generic board with a ST7735 display.
+pub type GenericST7735Board<const W: u16, const H: u16, const INVERT: bool = true> =
+    GenericDisplayBoard<screen_models::ST7735<INVERT>, W, H>;
+
+/// Convenience type for a generic board with a ST7789 display.
+pub type GenericST7789Board<const W: u16, const H: u16> =
+    GenericDisplayBoard<screen_models::ST7789, W, H>;
+
+impl<MODEL, const W: u16, const H: u16> GenericDisplayBoard<MODEL, W, H>
+where
+    MODEL: PresetModel,
+{
+    /// Initialize the board from a description.
+    ///
+    /// # Note
+    ///
+    /// Any pin used by the board cannot be claimed by other components, including
+    /// another board, until the instance is dropped.
+    ///
+    /// The panel model and size of the description must match those of the board
+    /// type. The SPI bus, clock speed and pins are checked against the running model
+    /// first, returning [`RPiError::System`] if the board cannot be used on it.
+    pub fn from_description(description: BoardDescription) -> RPiResult<Self> {
+        let display_description = &description.display;
+
+        display_description.check_panel(MODEL::NAME, W, H)?;
+        description.validate()?;
+
+        let capabilities = system::capabilities()?;
+        capabilities.validate_spi(description.spi.bus()?, description.spi.clock_speed)?;
+        capabilities.validate_gpio_pins(
+            &[display_description.dc, display_description.backlight]
+                .into_iter()
+                .chain(display_description.reset)
+                .chain(description.buttons.iter().map(|button| button.pin))
+                .chain(
+                    description
+                        .leds
+                        .iter()
+                        .flat_map(|led| [led.red, led.green, led.blue]),
+                )
+                .collect::<Vec<_>>(),
+        )?;
+
+        let mut pins = registry::claim_pins(
+            &description.spi.reserved_pins(),
+            PinFunction::Spi,
+            &description.name,
+        )?;
+        pins.extend(registry::claim_pins(
+            &[display_description.dc]
+                .into_iter()
+                .chain(display_description.reset)
+                .collect::<Vec<_>>(),
+            PinFunction::Output,
+            &description.name,
+        )?);
+
+        let gpio = func::init_gpio()?;
+
+        let backlight = DisplayBacklight::try_new(
+            &gpio,
+            display_description.backlight,
+            display_description.backlight_frequency,
+        )?;
+
+        // The SPI bus, without the Chip Select Line; supposingly this can be used by
+        // more than one device.
+        let spi = Spi::new(
+            description.spi.bus()?,
+            description.spi.slave()?,
+            description.spi.clock_speed,
+            description.spi.mode()?,
+        )
+        .with_context(|| {
+            ErrorContext::new("open the SPI bus").with_component(description.name.clone())
+        })?;
+
+        let rst = display_description
+            .reset
+            .map(|pin| RPiResult::Ok(func::get_pin(&gpio, pin, "display reset")?.into_output()))
+            .transpose()?;
+
+        let dc =
+            func::get_pin(&gpio, display_description.dc, "display data/command")?.into_output();
+
+        let di = DisplaySPIInterfaceNoCS::new(spi, dc);
+
+        let display = SpiLcdDisplay::<MODEL, W, H>::with_model(
+            di,
+            MODEL::preset(),
+            rst,
+            Delay::new(),
+            display_description.orientation(),
+            display_description.colour_inversion(),
+            display_description.tearing_effect(),
+            backlight,
+        )?;
+
+        let buttons = description
+            .buttons
+            .iter()
+            .map(|button| {
+                let device = if button.inverted {
+                    Button::try_new_inverted(&gpio, button.pin)
+                } else {
+                    Button::try_new(&gpio, button.pin)
+                }?;
+
+                Ok((button.name.clone(), device))
+            })
+            .collect::<RPiResult<HashMap<_, _>>>()?;
+
+        let leds = description
+            .leds
+            .iter()
+            .map(|led| {
+                let device = RgbLed::try_new(&gpio, led.red, led.green, led.blue, led.frequency)?;
+
+                Ok((led.name.clone(), device.into()))
+            })
+            .collect::<RPiResult<HashMap<_, _>>>()?;
+
+        Ok(Self {
+            _phantom: PhantomData,
+            description,
+            buttons,
+            leds,
+            display: display.into(),
+            _pins: pins,
+        })
+    }
+
+    /// Initialize the board from a TOML description.
+    pub fn from_toml_str(s: &str) -> RPiResult<Self> {
+        Self::from_description(BoardDescription::from_toml_str(s)?)
+    }
+
+    /// Initialize the board from a JSON description.
+    pub fn from_json_str(s: &str) -> RPiResult<Self> {
+        Self::from_description(BoardDescription::from_json_str(s)?)
+    }
+
+    /// Initialize the board from a description file in either TOML or JSON.
+    pub async fn from_path(path: impl AsRef<Path> + std::fmt::Debug) -> RPiResult<Self> {
+        Self::from_description(BoardDescription::from_path(path).await?)
+    }
+
+    /// Get a button by its name in the description.
+    pub fn button(&self, name: &str) -> RPiResult<&Button> {
+        self.buttons
+            .get(name)
+            .ok_or_else(|| RPiError::InvalidInput("button".into(), name.to_owned().into()))
+    }
+
+    /// Get a LED by its name in the description.
+    pub fn led(&self, name: &str) -> RPiResult<&Mutex<RgbLed>> {
+        self.leds
+            .get(name)
+            .ok_or_else(|| RPiError::InvalidInput("led".into(), name.to_owned().into()))
+    }
+}
+
+/// Marker trait only.
+impl<MODEL, const W: u16, const H: u16> HardwareComponent for GenericDisplayBoard<MODEL, W, H> where
+    MODEL: PresetModel
+{
+}
+
+/// Mark the [`GenericDisplayBoard`] as a [`DisplayComponent`].
+#[async_trait]
+impl<MODEL, const W: u16, const H: u16> DisplayComponent for GenericDisplayBoard<MODEL, W, H>
+where
+    MODEL: PresetModel + Send + Sync,
+    MODEL::ColorFormat: Send,
+{
+    type COLOUR = MODEL::ColorFormat;
+    type DI = DisplaySPIInterfaceNoCS<Spi, OutputPin>;
+    type MODEL = MODEL;
+    type RST = OutputPin;
+
+    const W: u16 = W;
+    const H: u16 = H;
+
+    /// Clear the display.
+    async fn fill_display(&self, colour: Self::COLOUR) -> RPiResult<()> {
+        self.display.lock().await.fill(colour).await
+    }
+
+    /// Recover the display if the error came from it, per its recovery policy.
+    async fn recover_display(&self, error: &RPiError, attempt: u32) -> bool {
+        self.display.lock().await.recover(error, attempt).await
+    }
+}
+
+#[async_trait]
+impl<MODEL, const W: u16, const H: u16> BacklightComponent for GenericDisplayBoard<MODEL, W, H>
+where
+    MODEL: PresetModel + Send + Sync,
+{
+    /// Turn the backlight on over an interval of time.
+    async fn backlight_fade_in(&self, step: u32, duration: std::time::Duration) -> RPiResult<()> {
+        self.display
+            .lock()
+            .await
+            .backlight
+            .transition_to(1., step, duration)
+            .await
+    }
+
+    /// Turn the backlight off over an interval of time.
+    async fn backlight_fade_out(&self, step: u32, duration: std::time::Duration) -> RPiResult<()> {
+        self.display
+            .lock()
+            .await
+            .backlight
+            .transition_to(0., step, duration)
+            .await
+    }
+
+    /// Turn the backlight on.
+    async fn backlight_on(&self) -> RPiResult<f64> {
+        self.display.lock().await.backlight.set_value(1.)
+    }
+
+    /// Turn the backlight off.
+    async fn backlight_off(&self) -> RPiResult<f64> {
+        self.display.lock().await.backlight.set_value(0.)
+    }
+}
@@ -7,3 +7,13 @@ pub use pimoroni_display_hat_mini::*;
 mod pimoroni_enviro_plus;
 #[cfg(feature = "pimoroni-enviro-plus")]
 pub use pimoroni_enviro_plus::*;
+
+#[cfg(feature = "board-description")]
+mod description;
+#[cfg(feature = "board-description")]
+pub use description::*;
+
+#[cfg(feature = "board-description")]
+mod generic;
+#[cfg(feature = "board-description")]
+pub use generic::*;
@@ -8,14 +8,23 @@ use crate::display_mipidsi::{
     traits::{BacklightComponent, DisplayComponent},
     ColorInversion, DisplaySPIInterfaceNoCS, Orientation, TearingEffect,
 };
-use crate::errors::{IntoRPiResult, RPiResult};
-use crate::gpio::{func, traits::HardwareComponent, Button, DisplayBacklight, OutputPin, RgbLed};
+use crate::errors::{ErrorContext, IntoRPiResult, RPiError, RPiResult, ResultContext};
+use crate::gpio::{
+    func,
+    registry::{self, PinFunction, PinGuard, SingletonGuard},
+    traits::{HardwareComponent, Navigation, NavigationInput},
+    Button, DisplayBacklight, OutputPin, RgbLed,
+};
+use crate::system;
 use async_mutex::Mutex;
 use rppal::{
     hal::Delay,
     spi::{Bus, Mode as SpiMode, SlaveSelect, Spi},
 };
-use std::marker::PhantomData;
+use std::{marker::PhantomData, sync::atomic::AtomicBool, time::Duration};
+
+/// Whether a [`PimoroniDisplayHATMini`] currently exists.
+static INITIALISED: AtomicBool = AtomicBool::new(false);
 
 /// Pimoroni Display HAT Mini on a Raspberry Pi.
 pub struct PimoroniDisplayHATMini {
@@ -28,6 +37,10 @@ pub struct PimoroniDisplayHATMini {
     pub led: Mutex<RgbLed>,
 
     pub display: Mutex<LcdST7789<320, 240>>,
+
+    // Released only after all the devices above are dropped.
+    _pins: Vec<PinGuard>,
+    _singleton: SingletonGuard,
 }
 
 impl PimoroniDisplayHATMini {
@@ -58,13 +71,62 @@ impl PimoroniDisplayHATMini {
 
     /// Initialize the Pimoroni Display HAT Mini.
     ///
-    /// # Note
+    /// # Note
     ///
-    /// Creating two instances of this struct is undefined behaviour.
-    pub fn init<'e>() -> RPiResult<'e, Self> {
+    /// Only one instance of this struct can exist at a time; initializing another
+    /// one returns [`RPiError::AlreadyInitialised`](crate::errors::RPiError).
+    /// Any pin used by the HAT cannot be claimed by other components until the
+    /// instance is dropped.
+    ///
+    /// The SPI bus, clock speed and pins are checked against the running model
+    /// first, returning [`RPiError::System`](crate::errors::RPiError) if the HAT
+    /// cannot be used on it.
+    pub fn init() -> RPiResult<Self> {
+        let singleton = SingletonGuard::try_acquire(&INITIALISED, "PimoroniDisplayHATMini")?;
+
+        let capabilities = system::capabilities()?;
+        capabilities.validate_spi(Self::SPI_BUS, Self::SPI_CLOCK_SPEED)?;
+        // The backlight is dimmed by software PWM, so it can be on any GPIO pin.
+        capabilities.validate_gpio_pins(
+            &[
+                Self::SPI_DC,
+                Self::DISPLAY_BACKLIGHT,
+                Self::BUTTON_A,
+                Self::BUTTON_B,
+                Self::BUTTON_X,
+                Self::BUTTON_Y,
+                Self::LED_R,
+                Self::LED_G,
+                Self::LED_B,
+            ]
+            .into_iter()
+            .chain(Self::DISPLAY_RESET)
+            .collect::<Vec<_>>(),
+        )?;
+
+        // `GPIO 9` is the `MISO` line of `SPI0`, repurposed as the Data/Command line
+        // of the display.
+        let mut pins = registry::claim_pins(
+            &[Self::SPI_MOSI, Self::SPI_SLCK, Self::SPI_CS],
+            PinFunction::Spi,
+            "PimoroniDisplayHATMini",
+        )?;
+        pins.push(registry::claim_pin(
+            Self::SPI_DC,
+            PinFunction::Output,
+            "PimoroniDisplayHATMini",
+        )?);
+        if let Some(pin) = Self::DISPLAY_RESET {
+            pins.push(registry::claim_pin(
+                pin,
+                PinFunction::Output,
+                "PimoroniDisplayHATMini",
+            )?);
+        }
+
         let gpio = func::init_gpio()?;
 
-        let backlight = DisplayBacklight::new(&gpio, Self::DISPLAY_BACKLIGHT, 50.);
+        let backlight = DisplayBacklight::try_new(&gpio, Self::DISPLAY_BACKLIGHT, 50.)?;
 
         // The SPI bus, without the Chip Select Line; supposingly this can be used by
         // more than one device.
@@ -74,12 +136,14 @@ impl PimoroniDisplayHATMini {
             Self::SPI_CLOCK_SPEED,
             Self::SPI_MODE,
         )
-        .into_rpi_result()?;
+        .context(ErrorContext::new("open the SPI bus").with_component("PimoroniDisplayHATMini"))?;
 
         let rst = RPiResult::Ok(Self::DISPLAY_RESET)
             .and_then(|pin_opt| {
                 if let Some(pin) = pin_opt {
-                    Ok(Some(gpio.get(pin)?.into_output()))
+                    Ok(Some(
+                        func::get_pin(&gpio, pin, "display reset")?.into_output(),
+                    ))
                 } else {
                     Ok(None)
                 }
@@ -87,18 +151,18 @@ impl PimoroniDisplayHATMini {
             .into_rpi_result()?;
 
         // The Chip Select Line.
-        let dc = gpio.get(Self::SPI_DC).into_rpi_result()?.into_output();
+        let dc = func::get_pin(&gpio, Self::SPI_DC, "display data/command")?.into_output();
 
         let di = DisplaySPIInterfaceNoCS::new(spi, dc);
 
         Ok(Self {
             _phantom: PhantomData,
-            button_a: Button::new(&gpio, Self::BUTTON_A),
-            button_b: Button::new(&gpio, Self::BUTTON_B),
-            button_x: Button::new(&gpio, Self::BUTTON_X),
-            button_y: Button::new(&gpio, Self::BUTTON_Y),
+            button_a: Button::try_new(&gpio, Self::BUTTON_A)?,
+            button_b: Button::try_new(&gpio, Self::BUTTON_B)?,
+            button_x: Button::try_new(&gpio, Self::BUTTON_X)?,
+            button_y: Button::try_new(&gpio, Self::BUTTON_Y)?,
 
-            led: RgbLed::new(&gpio, Self::LED_R, Self::LED_G, Self::LED_B, 50.).into(),
+            led: RgbLed::try_new(&gpio, Self::LED_R, Self::LED_G, Self::LED_B, 50.)?.into(),
 
             display: LcdST7789::<320, 240>::new(
                 di,
@@ -110,6 +174,9 @@ impl PimoroniDisplayHATMini {
                 backlight,
             )?
             .into(),
+
+            _pins: pins,
+            _singleton: singleton,
         })
     }
 }
@@ -117,6 +184,21 @@ impl PimoroniDisplayHATMini {
 /// Marker trait only.
 impl HardwareComponent for PimoroniDisplayHATMini {}
 
+/// Use the 4 buttons of the HAT for list and menu navigation, following the icons of
+/// the example user interface: `A` for previous, `X` for next, `B` to select and
+/// `Y` to go back.
+#[async_trait]
+impl NavigationInput for PimoroniDisplayHATMini {
+    async fn next_navigation(&self, timeout: Option<Duration>) -> RPiResult<Navigation> {
+        tokio::select! {
+            pressed = self.button_a.pressed_and_released(timeout) => pressed.map(|_| Navigation::Previous),
+            pressed = self.button_x.pressed_and_released(timeout) => pressed.map(|_| Navigation::Next),
+            pressed = self.button_b.pressed_and_released(timeout) => pressed.map(|_| Navigation::Select),
+            pressed = self.button_y.pressed_and_released(timeout) => pressed.map(|_| Navigation::Back),
+        }
+    }
+}
+
 /// Mark the [`PimoroniDisplayHATMini`] as a [`DisplayComponent`].
 #[async_trait]
 impl DisplayComponent for PimoroniDisplayHATMini {
@@ -129,19 +211,20 @@ impl DisplayComponent for PimoroniDisplayHATMini {
     const H: u16 = 240;
 
     /// Clear the display.
-    async fn fill_display<'e>(&self, colour: Self::COLOUR) -> RPiResult<'e, ()> {
-        self.display.lock().await.fill(colour)
+    async fn fill_display(&self, colour: Self::COLOUR) -> RPiResult<()> {
+        self.display.lock().await.fill(colour).await
+    }
+
+    /// Recover the display if the error came from it, per its recovery policy.
+    async fn recover_display(&self, error: &RPiError, attempt: u32) -> bool {
+        self.display.lock().await.recover(error, attempt).await
     }
 }
 
 #[async_trait]
 impl BacklightComponent for PimoroniDisplayHATMini {
     /// Turn the backlight off over an interval of time.
-    async fn backlight_fade_in<'e>(
-        &self,
-        step: u32,
-        duration: std::time::Duration,
-    ) -> RPiResult<'e, ()> {
+    async fn backlight_fade_in(&self, step: u32, duration: std::time::Duration) -> RPiResult<()> {
         self.display
             .lock()
             .await
@@ -151,11 +234,7 @@ impl BacklightComponent for PimoroniDisplayHATMini {
     }
 
     /// Turn the backlight off over an interval of time.
-    async fn backlight_fade_out<'e>(
-        &self,
-        step: u32,
-        duration: std::time::Duration,
-    ) -> RPiResult<'e, ()> {
+    async fn backlight_fade_out(&self, step: u32, duration: std::time::Duration) -> RPiResult<()> {
         self.display
             .lock()
             .await
@@ -165,12 +244,12 @@ impl BacklightComponent for PimoroniDisplayHATMini {
     }
 
     /// Turn the backlight on.
-    async fn backlight_on<'e>(&self) -> RPiResult<'e, f64> {
+    async fn backlight_on(&self) -> RPiResult<f64> {
         self.display.lock().await.backlight.set_value(1.)
     }
 
     /// Turn the backlight off.
-    async fn backlight_off<'e>(&self) -> RPiResult<'e, f64> {
+    async fn backlight_off(&self) -> RPiResult<f64> {
         self.display.lock().await.backlight.set_value(0.)
     }
 }
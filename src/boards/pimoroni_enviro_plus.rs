@@ -8,19 +8,31 @@ use rpi_display_mipidsi::{
     LcdST7735,
 };
 use rpi_display_mipidsi::{ColorInversion, DisplaySPIInterfaceNoCS, Orientation, TearingEffect};
-use rpi_errors::{IntoRPiResult, RPiResult};
-use rpi_gpio::{func, traits::HardwareComponent, DisplayBacklight, OutputPin};
+use rpi_errors::{ErrorContext, IntoRPiResult, RPiError, RPiResult, ResultContext};
+use rpi_gpio::{
+    func,
+    registry::{self, PinFunction, PinGuard, SingletonGuard},
+    traits::HardwareComponent,
+    DisplayBacklight, OutputPin,
+};
 use rppal::{
     hal::Delay,
     spi::{Bus, Mode as SpiMode, SlaveSelect, Spi},
 };
-use std::marker::PhantomData;
+use std::{marker::PhantomData, sync::atomic::AtomicBool};
+
+/// Whether a [`PimoroniEnviroPlus`] currently exists.
+static INITIALISED: AtomicBool = AtomicBool::new(false);
 
 pub struct PimoroniEnviroPlus {
     // Prevents instantiation of this struct.
     _phantom: PhantomData<()>,
     // INCOMPLETE
     pub display: Mutex<LcdST7735<160, 80, true>>,
+
+    // Released only after all the devices above are dropped.
+    _pins: Vec<PinGuard>,
+    _singleton: SingletonGuard,
 }
 
 impl PimoroniEnviroPlus {
@@ -59,12 +71,61 @@ impl PimoroniEnviroPlus {
     ///
     /// # Note
     ///
-    /// Creating two instances of this struct is undefined behaviour, and will likely
-    /// fail.
-    pub fn init<'e>() -> RPiResult<'e, Self> {
+    /// Only one instance of this struct can exist at a time; initializing another
+    /// one returns [`RPiError::AlreadyInitialised`](rpi_errors::RPiError).
+    /// Any pin used by the board cannot be claimed by other components until the
+    /// instance is dropped.
+    ///
+    /// The SPI bus, clock speed and pins are checked against the running model
+    /// first, returning [`RPiError::System`](rpi_errors::RPiError) if the board
+    /// cannot be used on it.
+    pub fn init() -> RPiResult<Self> {
+        let singleton = SingletonGuard::try_acquire(&INITIALISED, "PimoroniEnviroPlus")?;
+
+        let capabilities = rpi_system::capabilities()?;
+        capabilities.validate_spi(Self::SPI_BUS, Self::SPI_CLOCK_SPEED)?;
+        // The backlight is dimmed by software PWM, so it can be on any GPIO pin.
+        capabilities.validate_gpio_pins(
+            &[Self::SPI_DC, Self::DISPLAY_BACKLIGHT]
+                .into_iter()
+                .chain(Self::DISPLAY_RESET)
+                .collect::<Vec<_>>(),
+        )?;
+
+        let mut pins = Vec::new();
+        for (claimed, function) in [
+            (
+                &[Self::SPI_MOSI, Self::SPI_SLCK, Self::SPI_CS][..],
+                PinFunction::Spi,
+            ),
+            (&[Self::SPI_DC][..], PinFunction::Output),
+            (&[Self::I2C_SDA, Self::I2C_SCL][..], PinFunction::I2c),
+            (
+                &[Self::PMS5003_UART_0, Self::PMS5003_UART_1][..],
+                PinFunction::Uart,
+            ),
+            (
+                &[Self::MIC_I2S_FS, Self::MIC_I2S_DATA][..],
+                PinFunction::I2s,
+            ),
+        ] {
+            pins.extend(registry::claim_pins(
+                claimed,
+                function,
+                "PimoroniEnviroPlus",
+            )?);
+        }
+        if let Some(pin) = Self::DISPLAY_RESET {
+            pins.push(registry::claim_pin(
+                pin,
+                PinFunction::Output,
+                "PimoroniEnviroPlus",
+            )?);
+        }
+
         let gpio = func::init_gpio()?;
 
-        let backlight = DisplayBacklight::new(&gpio, Self::DISPLAY_BACKLIGHT, 50.);
+        let backlight = DisplayBacklight::try_new(&gpio, Self::DISPLAY_BACKLIGHT, 50.)?;
 
         // The SPI bus, without the Chip Select Line; supposingly this can be used by
         // more than one device.
@@ -74,12 +135,14 @@ impl PimoroniEnviroPlus {
             Self::SPI_CLOCK_SPEED,
             Self::SPI_MODE,
         )
-        .into_rpi_result()?;
+        .context(ErrorContext::new("open the SPI bus").with_component("PimoroniEnviroPlus"))?;
 
         let rst = RPiResult::Ok(Self::DISPLAY_RESET)
             .and_then(|pin_opt| {
                 if let Some(pin) = pin_opt {
-                    Ok(Some(gpio.get(pin)?.into_output()))
+                    Ok(Some(
+                        func::get_pin(&gpio, pin, "display reset")?.into_output(),
+                    ))
                 } else {
                     Ok(None)
                 }
@@ -87,7 +150,7 @@ impl PimoroniEnviroPlus {
             .into_rpi_result()?;
 
         // The Chip Select Line.
-        let dc = gpio.get(Self::SPI_DC).into_rpi_result()?.into_output();
+        let dc = func::get_pin(&gpio, Self::SPI_DC, "display data/command")?.into_output();
 
         let di = DisplaySPIInterfaceNoCS::new(spi, dc);
 
@@ -103,6 +166,9 @@ impl PimoroniEnviroPlus {
                 backlight,
             )?
             .into(),
+
+            _pins: pins,
+            _singleton: singleton,
         })
     }
 }
@@ -122,19 +188,20 @@ impl DisplayComponent for PimoroniEnviroPlus {
     const H: u16 = 80;
 
     /// Clear the display.
-    async fn fill_display<'e>(&self, colour: Self::COLOUR) -> RPiResult<'e, ()> {
-        self.display.lock().await.fill(colour)
+    async fn fill_display(&self, colour: Self::COLOUR) -> RPiResult<()> {
+        self.display.lock().await.fill(colour).await
+    }
+
+    /// Recover the display if the error came from it, per its recovery policy.
+    async fn recover_display(&self, error: &RPiError, attempt: u32) -> bool {
+        self.display.lock().await.recover(error, attempt).await
     }
 }
 
 #[async_trait]
 impl BacklightComponent for PimoroniEnviroPlus {
     /// Turn the backlight off over an interval of time.
-    async fn backlight_fade_in<'e>(
-        &self,
-        step: u32,
-        duration: std::time::Duration,
-    ) -> RPiResult<'e, ()> {
+    async fn backlight_fade_in(&self, step: u32, duration: std::time::Duration) -> RPiResult<()> {
         self.display
             .lock()
             .await
@@ -144,11 +211,7 @@ impl BacklightComponent for PimoroniEnviroPlus {
     }
 
     /// Turn the backlight off over an interval of time.
-    async fn backlight_fade_out<'e>(
-        &self,
-        step: u32,
-        duration: std::time::Duration,
-    ) -> RPiResult<'e, ()> {
+    async fn backlight_fade_out(&self, step: u32, duration: std::time::Duration) -> RPiResult<()> {
         self.display
             .lock()
             .await
@@ -158,12 +221,12 @@ impl BacklightComponent for PimoroniEnviroPlus {
     }
 
     /// Turn the backlight on.
-    async fn backlight_on<'e>(&self) -> RPiResult<'e, f64> {
+    async fn backlight_on(&self) -> RPiResult<f64> {
         self.display.lock().await.backlight.set_value(1.)
     }
 
     /// Turn the backlight off.
-    async fn backlight_off<'e>(&self) -> RPiResult<'e, f64> {
+    async fn backlight_off(&self) -> RPiResult<f64> {
         self.display.lock().await.backlight.set_value(0.)
     }
 }
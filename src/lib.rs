@@ -8,7 +8,7 @@ pub mod system {
 /// A library for interacting with GPIO pins on a Raspberry Pi, with all polling done
 /// asynchronously.
 ///
-/// Supports [`Button`]s and [RGB LED]s.
+/// Supports [`Button`]s, [`RotaryEncoder`]s and [RGB LED]s.
 ///
 /// This is currently written for the express purpose of using a
 /// [Pimoroni Display HAT Mini] on a Pi Zero 2W; features are added as required.
@@ -24,7 +24,7 @@ const IMAGE_BIN_PATHS: [&str; 3] = [
 ];
 
 #[allow(dead_code)]
-async fn load_bytes<'e>() -> RPiResult<'e, Vec<Vec<u8>>> {
+async fn load_bytes() -> RPiResult<Vec<Vec<u8>>> {
     let mut tasks = Vec::with_capacity(IMAGE_BIN_PATHS.len());
     for path in IMAGE_BIN_PATHS {
         // This call will make them start running in the background
@@ -41,7 +41,7 @@ async fn load_bytes<'e>() -> RPiResult<'e, Vec<Vec<u8>>> {
 }
 
 #[allow(dead_code)]
-fn load_raw<'a, 'e, COLOUR, const W: u16>(bytes_array: &'a [Vec<u8>]) -> Vec<ImageRaw<'a, COLOUR>>
+fn load_raw<'a, COLOUR, const W: u16>(bytes_array: &'a [Vec<u8>]) -> Vec<ImageRaw<'a, COLOUR>>
 where
     COLOUR: PixelColor + From<<COLOUR as PixelColor>::Raw>,
     ImageRaw<'a, COLOUR>: ImageDrawable<Color = COLOUR>,
@@ -53,7 +53,7 @@ where
 }
 
 #[allow(dead_code)]
-fn load_images<'a, 'e, COLOUR, const W: u16>(
+fn load_images<'a, COLOUR, const W: u16>(
     raws: &'a [ImageRaw<'a, COLOUR>],
 ) -> Vec<Image<'a, ImageRaw<'a, COLOUR>>>
 where
@@ -91,9 +91,9 @@ mod pimoroni_display_hat_mini {
                 tokio::select! {
                     $(
                         result = $name.pressed_and_released(None) => if result.unwrap_or(false) {
-                            logger::info(&format!("Pressed {}", stringify!($name)));
+                            logger::info!("Pressed {}", stringify!($name));
                         } else {
-                            logger::warning(&format!("Timeout {} or error", stringify!($name)));
+                            logger::warning!("Timeout {} or error", stringify!($name));
                         },
                     )*
                 }
@@ -200,16 +200,28 @@ mod pimoroni_display_hat_mini {
                 .await
                 .expect("Failed to transition backlight to full power.");
 
-            display.fill_white().expect("Failed to fill display white.");
+            display
+                .fill_white()
+                .await
+                .expect("Failed to fill display white.");
             tokio::time::sleep(Duration::from_secs(1)).await;
 
-            display.fill_red().expect("Failed to fill display red.");
+            display
+                .fill_red()
+                .await
+                .expect("Failed to fill display red.");
             tokio::time::sleep(Duration::from_secs(1)).await;
 
-            display.fill_green().expect("Failed to fill display green.");
+            display
+                .fill_green()
+                .await
+                .expect("Failed to fill display green.");
             tokio::time::sleep(Duration::from_secs(1)).await;
 
-            display.fill_blue().expect("Failed to fill display blue.");
+            display
+                .fill_blue()
+                .await
+                .expect("Failed to fill display blue.");
             tokio::time::sleep(Duration::from_secs(1)).await;
 
             display
@@ -232,13 +244,16 @@ mod pimoroni_display_hat_mini {
             let bytes_array = load_bytes().await.expect("Failed to load bytes.");
             let raws = load_raw::<pixelcolor::Rgb565, 320>(&bytes_array);
 
-            lcd.fill_black().expect("Failed to fill display black.");
+            lcd.fill_black()
+                .await
+                .expect("Failed to fill display black.");
 
             const STEPS: u32 = 40;
 
             for (id, raw) in raws.into_iter().enumerate() {
                 if id == 0 {
                     lcd.draw_image(&img_func::image_conversions::image_from_raw(&raw, 0, 0))
+                        .await
                         .expect("Failed to draw image.");
 
                     // For the first image, once we have loaded the image to the screen, we
@@ -264,7 +279,9 @@ mod pimoroni_display_hat_mini {
                 .transition_to(0., 32, Duration::from_secs(2))
                 .await
                 .expect("Failed to transition backlight to dark.");
-            lcd.fill_black().expect("Failed to fill display black.");
+            lcd.fill_black()
+                .await
+                .expect("Failed to fill display black.");
         }
         .await;
     }
@@ -293,7 +310,10 @@ mod pimoroni_display_hat_mini {
             let sub_image = img_func::crop::crop_horizontal(&bmp, (step * 40 / STEPS) as i32, 320);
 
             let image = img_func::image_conversions::image_from_raw(&sub_image, 0, 0);
-            display.draw_image(&image).expect("Failed to draw image.");
+            display
+                .draw_image(&image)
+                .await
+                .expect("Failed to draw image.");
 
             if step < 32 {
                 display
@@ -303,9 +323,7 @@ mod pimoroni_display_hat_mini {
             }
 
             let frame_time = tokio::time::Instant::now() - start_time;
-            logger::debug(&format!(
-                "Target duration: {step_duration:?}, Frame time: {frame_time:?}"
-            ));
+            logger::debug!("Target duration: {step_duration:?}, Frame time: {frame_time:?}");
             start_time = tokio::time::Instant::now();
             tokio::time::sleep_until(target_time).await;
         }
@@ -317,7 +335,10 @@ mod pimoroni_display_hat_mini {
             .transition_to(0., 32, Duration::from_secs(2))
             .await
             .expect("Failed to transition backlight to dark.");
-        display.fill_black().expect("Failed to fill display black.");
+        display
+            .fill_black()
+            .await
+            .expect("Failed to fill display black.");
     }
 
     #[tokio::test]
@@ -375,6 +396,7 @@ mod pimoroni_display_hat_mini {
             .lock()
             .await
             .draw_image(image.image().expect("Failed to create image."))
+            .await
             .expect("Failed to draw image.");
 
         let duration = Duration::from_secs(2);
@@ -470,13 +492,14 @@ mod pimoroni_display_hat_mini {
         \x1b[94m-\x1b[37m VSYNC Interface
         ".to_owned();
 
-        lcd.fill_blue().expect("Failed to fill display blue.");
+        lcd.fill_blue().await.expect("Failed to fill display blue.");
         lcd.draw_title::<20>(
             "\x1b[97mST7789\x1b[33m | LCD Display",
             pixelcolor::Rgb565::WHITE,
             Some(Point::new(0, 120 - 10)),
             None,
         )
+        .await
         .expect("Failed to draw title.");
         lcd.backlight
             .transition_to(1., STEPS, Duration::from_secs_f32(0.3))
@@ -491,7 +514,9 @@ mod pimoroni_display_hat_mini {
 
         const STEPS: u32 = 12;
         loop {
-            lcd.fill_black().expect("Failed to fill display black.");
+            lcd.fill_black()
+                .await
+                .expect("Failed to fill display black.");
 
             lcd.draw_text::<20>(
                 "ST7789VW",
@@ -499,6 +524,7 @@ mod pimoroni_display_hat_mini {
                 Some(Point::new(MARGIN, MARGIN)),
                 None,
             )
+            .await
             .expect("Failed to draw text.");
 
             text = lcd
@@ -508,6 +534,7 @@ mod pimoroni_display_hat_mini {
                     Some(Point::new(MARGIN, MARGIN + 20 + 4)),
                     None,
                 )
+                .await
                 .expect("Failed to draw text.");
 
             lcd.backlight
@@ -547,16 +574,28 @@ mod pimoroni_enviro_plus {
                 .await
                 .expect("Failed to transition backlight to full power.");
 
-            display.fill_white().expect("Failed to fill display white.");
+            display
+                .fill_white()
+                .await
+                .expect("Failed to fill display white.");
             tokio::time::sleep(Duration::from_secs(1)).await;
 
-            display.fill_red().expect("Failed to fill display red.");
+            display
+                .fill_red()
+                .await
+                .expect("Failed to fill display red.");
             tokio::time::sleep(Duration::from_secs(1)).await;
 
-            display.fill_green().expect("Failed to fill display green.");
+            display
+                .fill_green()
+                .await
+                .expect("Failed to fill display green.");
             tokio::time::sleep(Duration::from_secs(1)).await;
 
-            display.fill_blue().expect("Failed to fill display blue.");
+            display
+                .fill_blue()
+                .await
+                .expect("Failed to fill display blue.");
             tokio::time::sleep(Duration::from_secs(1)).await;
 
             display